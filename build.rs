@@ -0,0 +1,53 @@
+//! Build script
+//!
+//! Embeds the built-in scenario files so an installed binary has scenarios
+//! to play without a checkout. Writes `embedded_scenarios.rs` to `OUT_DIR`
+//! with one `(path, content)` entry per file below `scenarios/`.
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+fn main() {
+    let manifest_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").expect("CARGO_MANIFEST_DIR"));
+    let scenarios_dir = manifest_dir.join("scenarios");
+    println!("cargo:rerun-if-changed=scenarios");
+
+    let mut files = Vec::new();
+    collect_toml_files(&scenarios_dir, &mut files);
+    files.sort();
+
+    let mut out = String::from("pub static EMBEDDED_SCENARIOS: &[(&str, &str)] = &[\n");
+    for file in &files {
+        let relative = file
+            .strip_prefix(&scenarios_dir)
+            .expect("file below scenarios/")
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        out.push_str(&format!(
+            "    ({:?}, include_str!({:?})),\n",
+            relative,
+            file.display().to_string()
+        ));
+    }
+    out.push_str("];\n");
+
+    let out_dir = PathBuf::from(env::var("OUT_DIR").expect("OUT_DIR"));
+    fs::write(out_dir.join("embedded_scenarios.rs"), out).expect("write embedded_scenarios.rs");
+}
+
+/// Recursively collect `.toml` files below a directory
+fn collect_toml_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for path in entries.flatten().map(|entry| entry.path()) {
+        if path.is_dir() {
+            collect_toml_files(&path, files);
+        } else if path.extension().and_then(|s| s.to_str()) == Some("toml") {
+            files.push(path);
+        }
+    }
+}
@@ -0,0 +1,150 @@
+//! Scenario and configuration directory resolution
+//!
+//! Besides the built-in set, scenarios are read from the user's data
+//! directory (`$XDG_DATA_HOME/helix-trainer/scenarios`), from the
+//! `scenario_dirs` of the configuration file, from the directory named by
+//! `HELIX_TRAINER_SCENARIOS`, and from `--scenarios <dir>`. Each
+//! directory may hold one subdirectory per locale, like the built-in set.
+//! Settings live in `$XDG_CONFIG_HOME/helix-trainer/config.toml`.
+
+use super::ScenarioSource;
+use super::scenarios::BASE_LOCALE;
+use std::env;
+use std::path::{Path, PathBuf};
+
+/// Environment variable naming an extra scenarios directory
+pub const SCENARIOS_ENV_VAR: &str = "HELIX_TRAINER_SCENARIOS";
+
+/// Built-in scenario directories, in the order they are tried
+pub const BUILTIN_SCENARIO_DIRS: [&str; 2] = ["./scenarios", "/usr/share/helix-trainer/scenarios"];
+
+/// Scenario directories outside the built-in set
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ScenarioDirs {
+    /// `helix-trainer/scenarios` in the XDG data directory
+    pub data_dir: Option<PathBuf>,
+    /// `scenario_dirs` from the configuration file
+    pub config_dirs: Vec<PathBuf>,
+    /// Directory from `HELIX_TRAINER_SCENARIOS`
+    pub env_dir: Option<PathBuf>,
+    /// Directory from `--scenarios`
+    pub cli_dir: Option<PathBuf>,
+}
+
+/// Name of the settings file in the configuration directory
+pub const CONFIG_FILE_NAME: &str = "config.toml";
+
+/// XDG base directory from the variable, or the fallback under `$HOME`
+///
+/// XDG variables must hold absolute paths; others are ignored.
+fn xdg_dir(name: &str, home_fallback: &str) -> Option<PathBuf> {
+    let var = |name: &str| env::var_os(name).filter(|value| !value.is_empty());
+    var(name)
+        .map(PathBuf::from)
+        .filter(|path| path.is_absolute())
+        .or_else(|| var("HOME").map(|home| Path::new(&home).join(home_fallback)))
+}
+
+impl ScenarioDirs {
+    /// Resolve the directories from the environment and the `--scenarios` flag
+    pub fn from_env(cli_dir: Option<PathBuf>) -> Self {
+        let data_home = xdg_dir("XDG_DATA_HOME", ".local/share");
+
+        Self {
+            data_dir: data_home.map(|dir| dir.join("helix-trainer").join("scenarios")),
+            config_dirs: Vec::new(),
+            env_dir: env::var_os(SCENARIOS_ENV_VAR)
+                .filter(|value| !value.is_empty())
+                .map(PathBuf::from),
+            cli_dir,
+        }
+    }
+
+    /// Directories to load, lowest priority first
+    ///
+    /// The data directory is optional and skipped when it does not exist;
+    /// directories named explicitly are always returned so a typo is
+    /// reported rather than ignored.
+    pub fn user_dirs(&self) -> Vec<PathBuf> {
+        let data_dir = self.data_dir.as_ref().filter(|dir| dir.is_dir());
+        data_dir
+            .into_iter()
+            .chain(&self.config_dirs)
+            .chain(&self.env_dir)
+            .chain(&self.cli_dir)
+            .cloned()
+            .collect()
+    }
+}
+
+/// `helix-trainer/config.toml` in the XDG configuration directory
+pub fn config_file() -> Option<PathBuf> {
+    xdg_dir("XDG_CONFIG_HOME", ".config")
+        .map(|dir| dir.join("helix-trainer").join(CONFIG_FILE_NAME))
+}
+
+/// First built-in scenario directory that exists
+pub fn builtin_scenarios_dir() -> Option<PathBuf> {
+    BUILTIN_SCENARIO_DIRS
+        .iter()
+        .map(PathBuf::from)
+        .find(|dir| dir.is_dir())
+}
+
+/// English base set of a scenarios directory
+///
+/// That is the `en` subdirectory if there is one, else the directory
+/// itself without the subdirectories of other locales.
+pub fn base_source(dir: &Path) -> ScenarioSource {
+    let localized = dir.join(BASE_LOCALE);
+    if localized.is_dir() {
+        ScenarioSource::Directory(localized)
+    } else {
+        ScenarioSource::BaseDirectory(dir.to_path_buf())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_user_dirs_skip_missing_data_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let dirs = ScenarioDirs {
+            data_dir: Some(dir.path().join("missing")),
+            config_dirs: vec![PathBuf::from("/config")],
+            env_dir: Some(PathBuf::from("env")),
+            cli_dir: Some(PathBuf::from("cli")),
+        };
+        assert_eq!(
+            dirs.user_dirs(),
+            [
+                PathBuf::from("/config"),
+                PathBuf::from("env"),
+                PathBuf::from("cli")
+            ]
+        );
+
+        let dirs = ScenarioDirs {
+            data_dir: Some(dir.path().to_path_buf()),
+            ..ScenarioDirs::default()
+        };
+        assert_eq!(dirs.user_dirs(), [dir.path().to_path_buf()]);
+    }
+
+    #[test]
+    fn test_base_source_prefers_locale_subdirectory() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(
+            base_source(dir.path()),
+            ScenarioSource::BaseDirectory(dir.path().to_path_buf())
+        );
+
+        std::fs::create_dir(dir.path().join("en")).unwrap();
+        assert_eq!(
+            base_source(dir.path()),
+            ScenarioSource::Directory(dir.path().join("en"))
+        );
+    }
+}
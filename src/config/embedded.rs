@@ -0,0 +1,104 @@
+//! Built-in scenarios compiled into the binary
+//!
+//! The build script embeds every file below `scenarios/`, so an installed
+//! binary works without the repository checkout. UI translations in
+//! `locales/` are embedded by `rust_i18n::i18n!` in the same way.
+
+use std::fs;
+use std::path::Path;
+
+include!(concat!(env!("OUT_DIR"), "/embedded_scenarios.rs"));
+
+/// Embedded files for a locale, as (path inside the locale, content)
+///
+/// Returns an empty list when the locale has no built-in scenarios.
+pub fn embedded_locale_files(locale: &str) -> Vec<(String, String)> {
+    let prefix = format!("{}/", locale);
+    EMBEDDED_SCENARIOS
+        .iter()
+        .filter_map(|(path, content)| {
+            path.strip_prefix(&prefix)
+                .map(|path| (path.to_string(), content.to_string()))
+        })
+        .collect()
+}
+
+/// Whether a directory holds exactly the embedded files of a locale
+///
+/// An on-disk copy of the built-in set, such as `./scenarios` in a
+/// checkout, adds nothing on top of the embedded one unless it was edited.
+pub fn matches_embedded(dir: &Path, locale: &str) -> bool {
+    let mut on_disk = Vec::new();
+    if !read_toml_files(dir, "", &mut on_disk) {
+        return false;
+    }
+    let mut embedded = embedded_locale_files(locale);
+    on_disk.sort();
+    embedded.sort();
+    on_disk == embedded
+}
+
+/// Collect (relative path, content) of the `.toml` files below a directory
+///
+/// Returns false if any directory or file cannot be read.
+fn read_toml_files(dir: &Path, prefix: &str, files: &mut Vec<(String, String)>) -> bool {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return false;
+    };
+    for path in entries.flatten().map(|entry| entry.path()) {
+        let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+            return false;
+        };
+        let relative = format!("{prefix}{name}");
+        if path.is_dir() {
+            if !read_toml_files(&path, &format!("{relative}/"), files) {
+                return false;
+            }
+        } else if path.extension().and_then(|s| s.to_str()) == Some("toml") {
+            let Ok(content) = fs::read_to_string(&path) else {
+                return false;
+            };
+            files.push((relative, content));
+        }
+    }
+    true
+}
+
+/// Locales with built-in scenarios
+pub fn embedded_locales() -> Vec<String> {
+    let mut locales: Vec<String> = EMBEDDED_SCENARIOS
+        .iter()
+        .filter_map(|(path, _)| path.split_once('/').map(|(locale, _)| locale.to_string()))
+        .collect();
+    locales.dedup();
+    locales
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_english_scenarios_are_embedded() {
+        assert!(embedded_locales().contains(&"en".to_string()));
+
+        let files = embedded_locale_files("en");
+        assert!(files.iter().any(|(path, _)| path == "pack.toml"));
+        assert!(files.iter().any(|(path, _)| path.ends_with(".toml")));
+        assert!(embedded_locale_files("xx").is_empty());
+    }
+
+    #[test]
+    fn test_matches_embedded_detects_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        for (path, content) in embedded_locale_files("en") {
+            let path = dir.path().join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        }
+        assert!(matches_embedded(dir.path(), "en"));
+
+        fs::write(dir.path().join("extra.toml"), "").unwrap();
+        assert!(!matches_embedded(dir.path(), "en"));
+    }
+}
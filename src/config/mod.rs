@@ -1,11 +1,21 @@
 //! Configuration and scenario loading
 //!
 //! This module handles loading and parsing scenario files in TOML format,
-//! as well as application configuration.
+//! as well as the user's configuration file.
 
+pub mod dirs;
+pub mod embedded;
 pub mod scenarios;
+pub mod trainer;
 
+pub use dirs::{SCENARIOS_ENV_VAR, ScenarioDirs};
 pub use scenarios::{
-    AlternativeSolution, Scenario, ScenarioLoader, ScenariosFile, ScoringConfig, Setup, Solution,
-    TargetState,
+    AlternativeSolution, BASE_LOCALE, Completion, ContentPattern, Diagnostic, DiagnosticCode,
+    ImportMeta, LessonStep, LintOptions, LintReport, PACK_MANIFEST, PackManifest, SCHEMA_VERSION,
+    Scenario, ScenarioLoader, ScenarioPack, ScenarioSource, ScenariosFile, ScoringConfig,
+    SelectionRange, Setup, SetupMode, Solution, TargetState, Template, TranslatedSolution,
+    TranslatedStep, Translation, Variable, import_challenge, scenario_schema,
+};
+pub use trainer::{
+    ConfigOverrides, GameMode, HintMode, KeyNotation, KeymapMode, Theme, TrainerConfig,
 };
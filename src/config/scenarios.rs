@@ -2,20 +2,44 @@
 //!
 //! This module handles loading TOML scenario files with security validations.
 
+use super::embedded::embedded_locales;
+use crate::helix::is_valid_indent_unit;
 use crate::security::limits::*;
 use crate::security::{SecurityError, UserError, path_validator, sanitizer};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
 
+mod import;
+mod lint;
+mod pack;
+mod schema;
+mod template;
+mod translation;
+mod verify;
+
+pub use import::{ImportMeta, import_challenge};
+pub use lint::{Diagnostic, DiagnosticCode, LintOptions, LintReport};
+pub use pack::{PACK_MANIFEST, PackManifest, SCHEMA_VERSION, ScenarioPack};
+pub use schema::scenario_schema;
+pub use template::{Template, Variable};
+pub use translation::{
+    BASE_LOCALE, TranslatedSolution, TranslatedStep, Translation, is_locale_code,
+};
+
 /// Wrapper for scenarios array in TOML file
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct ScenariosFile {
+    #[serde(default)]
     pub scenarios: Vec<Scenario>,
+    /// Texts of scenarios from other files in this file's locale
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub translations: Vec<Translation>,
 }
 
 /// Scenario definition
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(deny_unknown_fields)]
 pub struct Scenario {
     #[serde(deserialize_with = "validate_id_field")]
@@ -23,8 +47,46 @@ pub struct Scenario {
 
     pub name: String,
     pub description: String,
+
+    /// Topic the scenario belongs to, e.g. `"movement"`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+
+    /// Difficulty from 1 (first steps) to 5 (expert)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub difficulty: Option<u8>,
+
+    /// Free-form labels for filtering, e.g. `["word", "selection"]`
+    #[serde(default)]
+    pub tags: Vec<String>,
+
+    /// Ids of scenarios to complete before this one
+    #[serde(default)]
+    pub requires: Vec<String>,
+
+    /// Commands the scenario practices, e.g. `["w", "b"]`
+    #[serde(default)]
+    pub commands_taught: Vec<String>,
+
+    /// Commands that may not be used, e.g. `["h", "l"]` to practise word
+    /// motions; outside insert mode they are blocked and still cost a key
+    #[serde(default)]
+    pub forbidden_commands: Vec<String>,
+
+    /// Commands that must be used at least once, e.g. `["."]`; finishing
+    /// without them fails the scenario
+    #[serde(default)]
+    pub required_commands: Vec<String>,
+
+    /// Expected time to solve, in seconds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub estimated_seconds: Option<u32>,
+
     pub setup: Setup,
-    pub target: TargetState,
+    /// Required, except for templates: their target comes from replaying
+    /// the solution
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target: Option<TargetState>,
     pub solution: Solution,
 
     #[serde(default)]
@@ -34,34 +96,220 @@ pub struct Scenario {
     pub hints: Vec<String>,
 
     pub scoring: ScoringConfig,
+
+    /// Setup and target `file_content` carry `#[...|]#` selection markers
+    /// instead of `cursor_position` and `selections`
+    #[serde(default)]
+    pub markers: bool,
+
+    /// Variables that turn the scenario into a template for generated
+    /// instances
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub template: Option<Template>,
+
+    /// Further steps that turn the scenario into a lesson; the scenario's
+    /// own description, target, solution, hints and scoring are step 1
+    #[serde(default)]
+    pub steps: Vec<LessonStep>,
+}
+
+/// A lesson step after the first, played on the buffer the previous step
+/// left behind
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct LessonStep {
+    /// What to do in this step, shown in place of the scenario description
+    pub instructions: String,
+    pub target: TargetState,
+    pub solution: Solution,
+    #[serde(default)]
+    pub hints: Vec<String>,
+    pub scoring: ScoringConfig,
 }
 
 /// Initial editor setup
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Setup {
     pub file_content: String,
-    pub cursor_position: (usize, usize),
+    /// Cursor position: [line, col]; required unless `markers = true`, which
+    /// takes it from the markers instead
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cursor_position: Option<(usize, usize)>,
+    /// Initial selections (the primary head must be `cursor_position`)
+    #[serde(default)]
+    pub selections: Vec<SelectionRange>,
+    /// Index of the primary selection in `selections`
+    #[serde(default)]
+    pub primary_selection: usize,
+    /// Mode the editor starts in
+    #[serde(default)]
+    pub mode: SetupMode,
+    /// Pre-filled registers, e.g. `registers = { '"' = ["foo"] }`
+    #[serde(default)]
+    pub registers: BTreeMap<char, Vec<String>>,
+    /// Simulator commands whose action is already recorded for `.`,
+    /// e.g. `["dd"]` or `["i", "x", "Escape"]`
+    #[serde(default)]
+    pub last_action: Vec<String>,
+    /// Indentation `>` adds and `<` removes, for scenarios written for one;
+    /// the configured `indent_unit` applies when left out
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub indent_unit: Option<String>,
+}
+
+/// Editor mode a scenario starts in
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SetupMode {
+    #[default]
+    Normal,
+    Select,
+    Insert,
 }
 
 /// Target state to achieve
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
 pub struct TargetState {
     pub file_content: String,
-    pub cursor_position: (usize, usize),
+    /// Cursor position: [line, col]; required unless `markers = true`, which
+    /// takes it from the markers instead
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cursor_position: Option<(usize, usize)>,
     /// Optional selection range: [start_line, start_col, end_line, end_col]
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub selection: Option<[usize; 4]>,
+    /// Target selections for multi-selection exercises (takes precedence over `selection`)
+    #[serde(default)]
+    pub selections: Vec<SelectionRange>,
+    /// Index of the primary selection in `selections`
+    #[serde(default)]
+    pub primary_selection: usize,
+    /// Require the selections to match, on top of the completion rule
+    #[serde(default)]
+    pub match_selection: bool,
+    /// Rule deciding when the target is reached; exact content and cursor
+    /// by default
+    #[serde(default)]
+    pub completion: Completion,
+}
+
+/// When the current state counts as reaching a target
+///
+/// Set as `completion = { rule = "content" }` in a target. `match_selection`
+/// adds a selection check to any rule.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(tag = "rule", rename_all = "snake_case", deny_unknown_fields)]
+pub enum Completion {
+    /// Content and cursor equal the target
+    #[default]
+    Exact,
+    /// Content equals the target, wherever the cursor is
+    Content,
+    /// Cursor is at the target position, whatever the content
+    Cursor,
+    /// Selections, including direction and primary, equal the target's
+    Selection,
+    /// Content matches a regular expression; anchor it with `^`/`$` (and
+    /// `(?m)` for lines) to match more than a part
+    Regex { pattern: ContentPattern },
+    /// Line `line` (counted from 0, like `cursor_position`) is exactly `text`
+    Line { line: usize, text: String },
+    /// Content equals the target, ignoring whitespace at the end of lines
+    /// and of the buffer
+    TrimmedContent,
+}
+
+impl Completion {
+    /// Name of the rule as written in scenario files
+    pub fn name(&self) -> &'static str {
+        match self {
+            Completion::Exact => "exact",
+            Completion::Content => "content",
+            Completion::Cursor => "cursor",
+            Completion::Selection => "selection",
+            Completion::Regex { .. } => "regex",
+            Completion::Line { .. } => "line",
+            Completion::TrimmedContent => "trimmed_content",
+        }
+    }
+}
+
+/// Compiled regular expression of a `regex` completion rule
+#[derive(Debug, Clone)]
+pub struct ContentPattern(regex::Regex);
+
+impl ContentPattern {
+    /// Compile a pattern, rejecting invalid or oversized ones
+    ///
+    /// # Errors
+    ///
+    /// Returns `SecurityError::InvalidInput` if the pattern does not compile
+    /// within `MAX_PATTERN_SIZE`.
+    pub fn new(pattern: &str) -> Result<Self, SecurityError> {
+        regex::RegexBuilder::new(pattern)
+            .size_limit(MAX_PATTERN_SIZE)
+            .build()
+            .map(Self)
+            .map_err(|err| SecurityError::InvalidInput(format!("Invalid pattern: {}", err)))
+    }
+
+    /// The pattern as written
+    pub fn as_str(&self) -> &str {
+        self.0.as_str()
+    }
+
+    /// Whether the pattern matches somewhere in `content`
+    pub fn is_match(&self, content: &str) -> bool {
+        self.0.is_match(content)
+    }
+}
+
+impl PartialEq for ContentPattern {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl Eq for ContentPattern {}
+
+impl Serialize for ContentPattern {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for ContentPattern {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let pattern = String::deserialize(deserializer)?;
+        Self::new(&pattern).map_err(serde::de::Error::custom)
+    }
+}
+
+/// One selection range, keeping its direction
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct SelectionRange {
+    /// Fixed end of the selection: [line, col]
+    pub anchor: (usize, usize),
+    /// Moving end of the selection (where the cursor is): [line, col]
+    pub head: (usize, usize),
 }
 
 /// Optimal solution
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Solution {
     pub commands: Vec<String>,
     pub description: String,
 }
 
 /// Alternative solution
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct AlternativeSolution {
     pub commands: Vec<String>,
     pub points_multiplier: f32,
@@ -69,13 +317,188 @@ pub struct AlternativeSolution {
 }
 
 /// Scoring configuration
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct ScoringConfig {
     pub optimal_count: usize,
     pub max_points: u32,
     pub tolerance: usize,
 }
 
+/// Content with its selection markers stripped out
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MarkedContent {
+    /// Content without markers
+    pub content: String,
+    /// Marked selections in document order
+    pub selections: Vec<SelectionRange>,
+    /// Index of the `#[...]#` selection in `selections`
+    pub primary_selection: usize,
+}
+
+impl MarkedContent {
+    /// Cursor position: the head of the primary selection
+    pub fn cursor_position(&self) -> (usize, usize) {
+        self.selections[self.primary_selection].head
+    }
+}
+
+/// Parse content with inline selection markers, as in Helix's own tests
+///
+/// - `#[...]#` marks the primary selection and `#(...)#` every other one.
+/// - `|` inside a selection marks its head and must be at either end:
+///   `#[foo|]#` selects forward, `#[|foo]#` backward, `#[|]#` is a bare cursor.
+///
+/// Positions are (line, char column) in the stripped content.
+///
+/// # Errors
+///
+/// Returns `SecurityError::InvalidInput` for unbalanced or nested markers, a
+/// selection without a head or with it in the middle, or anything other than
+/// exactly one primary selection.
+pub fn parse_markers(text: &str) -> Result<MarkedContent, SecurityError> {
+    let invalid = |reason: &str| {
+        SecurityError::InvalidInput(format!("Invalid selection markers: {}", reason))
+    };
+
+    let mut content = String::with_capacity(text.len());
+    // (primary, anchor offset, head offset) in chars of `content`
+    let mut ranges: Vec<(bool, usize, usize)> = Vec::new();
+    // Currently open selection: (primary, start offset, head offset)
+    let mut open: Option<(bool, usize, Option<usize>)> = None;
+    let mut offset = 0;
+
+    let mut chars = text.chars().peekable();
+    while let Some(ch) = chars.next() {
+        if ch == '|'
+            && let Some((_, _, head)) = open.as_mut()
+        {
+            if head.replace(offset).is_some() {
+                return Err(invalid("selection with more than one '|'"));
+            }
+            continue;
+        }
+
+        match (ch, chars.peek().copied()) {
+            ('#', Some(bracket @ ('[' | '('))) => {
+                if open.is_some() {
+                    return Err(invalid("nested selection"));
+                }
+                chars.next();
+                open = Some((bracket == '[', offset, None));
+            }
+            (bracket @ (']' | ')'), Some('#')) => {
+                let Some((primary, start, head)) = open.take() else {
+                    return Err(invalid("closing marker without opening marker"));
+                };
+                if primary != (bracket == ']') {
+                    return Err(invalid("mismatched closing marker"));
+                }
+                chars.next();
+                let head = head.ok_or_else(|| invalid("selection without '|' head"))?;
+                let anchor = if head == offset {
+                    start
+                } else if head == start {
+                    offset
+                } else {
+                    return Err(invalid("'|' must be at either end of a selection"));
+                };
+                ranges.push((primary, anchor, head));
+            }
+            _ => {
+                content.push(ch);
+                offset += 1;
+            }
+        }
+    }
+
+    if open.is_some() {
+        return Err(invalid("unclosed selection"));
+    }
+    let mut primaries = ranges
+        .iter()
+        .enumerate()
+        .filter(|(_, (primary, _, _))| *primary)
+        .map(|(index, _)| index);
+    let primary_selection = match (primaries.next(), primaries.next()) {
+        (Some(index), None) => index,
+        (None, _) => return Err(invalid("no primary #[...]# selection")),
+        (Some(_), Some(_)) => return Err(invalid("more than one primary selection")),
+    };
+
+    let selections = ranges
+        .iter()
+        .map(|&(_, anchor, head)| SelectionRange {
+            anchor: position_at(&content, anchor),
+            head: position_at(&content, head),
+        })
+        .collect();
+
+    Ok(MarkedContent {
+        content,
+        selections,
+        primary_selection,
+    })
+}
+
+/// Convert a char offset into a (line, col) position
+fn position_at(content: &str, offset: usize) -> (usize, usize) {
+    content
+        .chars()
+        .take(offset)
+        .fold((0, 0), |(line, col), ch| {
+            if ch == '\n' {
+                (line + 1, 0)
+            } else {
+                (line, col + 1)
+            }
+        })
+}
+
+/// Replace marked setup and target content with plain content and selections
+fn apply_markers(scenario: &mut Scenario) -> Result<(), SecurityError> {
+    apply_setup_markers(&mut scenario.setup)?;
+    if let Some(target) = &mut scenario.target {
+        apply_target_markers(target)?;
+    }
+    for step in &mut scenario.steps {
+        apply_target_markers(&mut step.target)?;
+    }
+    Ok(())
+}
+
+/// Replace marked target content with plain content and selections
+fn apply_target_markers(target: &mut TargetState) -> Result<(), SecurityError> {
+    if target.cursor_position.is_some() || !target.selections.is_empty() {
+        return Err(marker_conflict());
+    }
+    let marked = parse_markers(&target.file_content)?;
+    target.cursor_position = Some(marked.cursor_position());
+    target.file_content = marked.content;
+    target.selections = marked.selections;
+    target.primary_selection = marked.primary_selection;
+    Ok(())
+}
+
+/// Replace marked setup content with plain content and selections
+fn apply_setup_markers(setup: &mut Setup) -> Result<(), SecurityError> {
+    if setup.cursor_position.is_some() || !setup.selections.is_empty() {
+        return Err(marker_conflict());
+    }
+    let marked = parse_markers(&setup.file_content)?;
+    setup.cursor_position = Some(marked.cursor_position());
+    setup.file_content = marked.content;
+    setup.selections = marked.selections;
+    setup.primary_selection = marked.primary_selection;
+    Ok(())
+}
+
+/// Error for a cursor or selections given next to the markers
+fn marker_conflict() -> SecurityError {
+    SecurityError::InvalidInput(
+        "cursor_position and selections come from the markers when markers = true".to_string(),
+    )
+}
+
 /// Custom deserialization for ID field to validate format
 fn validate_id_field<'de, D>(deserializer: D) -> Result<String, D::Error>
 where
@@ -83,8 +506,7 @@ where
 {
     let s = String::deserialize(deserializer)?;
 
-    // Validate ID format: alphanumeric with underscores, max 64 chars
-    if s.len() > 64 || !s.chars().all(|c| c.is_alphanumeric() || c == '_') {
+    if !is_valid_id(&s) {
         return Err(serde::de::Error::custom(
             "Invalid ID: must be alphanumeric with underscores, max 64 chars",
         ));
@@ -93,6 +515,176 @@ where
     Ok(s)
 }
 
+/// Check the scenario ID format: alphanumeric with underscores, max 64 chars
+fn is_valid_id(id: &str) -> bool {
+    id.len() <= 64 && id.chars().all(|c| c.is_alphanumeric() || c == '_')
+}
+
+/// Check a category or tag: non-empty lowercase words joined by `-` or `_`
+fn is_valid_label(label: &str) -> bool {
+    !label.is_empty()
+        && label.len() <= MAX_LABEL_LENGTH
+        && label
+            .chars()
+            .all(|c| c.is_lowercase() || c.is_ascii_digit() || c == '-' || c == '_')
+}
+
+/// Parse the scenarios and translations of a TOML file and check how many
+/// there are
+fn parse_file(content: &str) -> Result<ScenariosFile, SecurityError> {
+    let scenarios_file: ScenariosFile =
+        toml::from_str(content).map_err(|e| SecurityError::InvalidToml(e.to_string()))?;
+
+    if scenarios_file.scenarios.is_empty() && scenarios_file.translations.is_empty() {
+        return Err(SecurityError::InvalidToml(
+            "no [[scenarios]] or [[translations]] tables".to_string(),
+        ));
+    }
+
+    // Validate scenario and translation count
+    for count in [
+        scenarios_file.scenarios.len(),
+        scenarios_file.translations.len(),
+    ] {
+        if count > MAX_SCENARIOS_PER_FILE {
+            return Err(SecurityError::TooManyScenarios {
+                max: MAX_SCENARIOS_PER_FILE,
+                actual: count,
+            });
+        }
+    }
+
+    Ok(scenarios_file)
+}
+
+/// Parse a TOML file and check its translations, leaving its scenarios
+/// unexpanded and unvalidated
+fn parse_unprepared(content: &str) -> Result<ScenariosFile, SecurityError> {
+    let file = parse_file(content)?;
+    for translation in &file.translations {
+        translation::validate_translation(translation)?;
+    }
+    Ok(file)
+}
+
+/// Find `requires` entries naming scenarios that are not in the set
+///
+/// Returns (scenario id, missing id) pairs in scenario order.
+fn missing_requirements(scenarios: &[Scenario]) -> Vec<(String, String)> {
+    let ids: BTreeSet<&str> = scenarios.iter().map(|s| s.id.as_str()).collect();
+    scenarios
+        .iter()
+        .flat_map(|scenario| {
+            scenario
+                .requires
+                .iter()
+                .filter(|required| !ids.contains(required.as_str()))
+                .map(|required| (scenario.id.clone(), required.clone()))
+        })
+        .collect()
+}
+
+/// Find a chain of `requires` that leads back to where it started
+///
+/// Returns the ids along the first cycle found, starting and ending with
+/// the same id.
+fn requirement_cycle(scenarios: &[Scenario]) -> Option<Vec<String>> {
+    let requires: HashMap<&str, &[String]> = scenarios
+        .iter()
+        .map(|scenario| (scenario.id.as_str(), scenario.requires.as_slice()))
+        .collect();
+    let mut checked = HashSet::new();
+    scenarios.iter().find_map(|scenario| {
+        find_requirement_cycle(&scenario.id, &requires, &mut Vec::new(), &mut checked)
+    })
+}
+
+/// Depth-first search for a cycle through `id`, with the ids on the way in `path`
+fn find_requirement_cycle<'a>(
+    id: &'a str,
+    requires: &HashMap<&'a str, &'a [String]>,
+    path: &mut Vec<&'a str>,
+    checked: &mut HashSet<&'a str>,
+) -> Option<Vec<String>> {
+    if let Some(start) = path.iter().position(|step| *step == id) {
+        let mut cycle: Vec<String> = path[start..].iter().map(|step| step.to_string()).collect();
+        cycle.push(id.to_string());
+        return Some(cycle);
+    }
+    if checked.contains(id) {
+        return None;
+    }
+
+    path.push(id);
+    for required in requires.get(id).copied().unwrap_or_default() {
+        if let Some(cycle) = find_requirement_cycle(required, requires, path, checked) {
+            return Some(cycle);
+        }
+    }
+    path.pop();
+    checked.insert(id);
+    None
+}
+
+/// Move scenarios after those they require from the same list
+///
+/// Scenarios otherwise keep their order. Requirements must not form a cycle.
+fn order_by_requirements(scenarios: Vec<Scenario>) -> Vec<Scenario> {
+    let ids: HashSet<String> = scenarios.iter().map(|s| s.id.clone()).collect();
+    let mut placed = HashSet::new();
+    let mut pending = scenarios;
+    let mut ordered = Vec::with_capacity(pending.len());
+    while !pending.is_empty() {
+        let next = pending
+            .iter()
+            .position(|scenario| {
+                scenario
+                    .requires
+                    .iter()
+                    .all(|required| placed.contains(required) || !ids.contains(required))
+            })
+            .unwrap_or(0);
+        let scenario = pending.remove(next);
+        placed.insert(scenario.id.clone());
+        ordered.push(scenario);
+    }
+    ordered
+}
+
+/// Parse and check a pack manifest
+fn parse_manifest(content: &str) -> Result<PackManifest, SecurityError> {
+    let manifest: PackManifest =
+        toml::from_str(content).map_err(|e| SecurityError::InvalidToml(e.to_string()))?;
+    pack::validate_manifest(&manifest)?;
+    Ok(manifest)
+}
+
+/// Drop empty packs and put the scenarios of the others in manifest order
+fn finish_packs(mut packs: Vec<ScenarioPack>) -> Result<Vec<ScenarioPack>, UserError> {
+    packs.retain(|pack| !pack.scenarios.is_empty());
+    for pack in &mut packs {
+        pack::apply_order(pack).map_err(UserError::from)?;
+        tracing::info!(
+            pack = pack.title().as_deref().unwrap_or("(no pack)"),
+            scenario_count = pack.scenarios.len(),
+            "Loaded scenario pack"
+        );
+    }
+    Ok(packs)
+}
+
+/// Where a set of scenario files comes from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScenarioSource {
+    /// A directory on disk, searched recursively
+    Directory(PathBuf),
+    /// A directory on disk used as the base set, searched recursively
+    /// except for its locale subdirectories
+    BaseDirectory(PathBuf),
+    /// Files held in memory as (relative path, content), like the built-in set
+    Memory(Vec<(String, String)>),
+}
+
 /// Secure scenario loader with path validation and content verification
 pub struct ScenarioLoader {
     allowed_base_paths: Vec<PathBuf>,
@@ -111,40 +703,40 @@ impl ScenarioLoader {
         }
     }
 
-    /// Detect available locales by scanning the scenarios directory
+    /// Detect available locales from the embedded scenarios, the UI
+    /// translations and the scenario directories
     ///
-    /// Returns a list of locale codes (e.g., ["en", "ru"]) found as subdirectories
-    /// in the scenarios directory.
-    pub fn available_locales() -> Vec<String> {
-        let scenarios_path = Path::new("./scenarios");
-
-        if !scenarios_path.exists() || !scenarios_path.is_dir() {
-            tracing::warn!("Scenarios directory not found");
-            return vec!["en".to_string()]; // Fallback to English
-        }
-
-        let mut locales = Vec::new();
+    /// Returns a list of locale codes (e.g., ["en", "ru"]) with built-in
+    /// scenarios or UI texts, or found as subdirectories of `dirs`, the
+    /// directories scenarios are loaded from.
+    pub fn available_locales(dirs: &[PathBuf]) -> Vec<String> {
+        let mut locales = embedded_locales();
+        locales.extend(
+            rust_i18n::available_locales!()
+                .into_iter()
+                .map(str::to_string),
+        );
 
-        if let Ok(entries) = fs::read_dir(scenarios_path) {
+        for dir in dirs {
+            let Ok(entries) = fs::read_dir(dir) else {
+                continue;
+            };
             for entry in entries.flatten() {
                 if let Ok(file_type) = entry.file_type()
                     && file_type.is_dir()
                     && let Some(name) = entry.file_name().to_str()
+                    && is_locale_code(name)
                 {
-                    // Validate locale code: 2-letter ISO code
-                    if name.len() == 2 && name.chars().all(|c| c.is_ascii_lowercase()) {
-                        locales.push(name.to_string());
-                    }
+                    locales.push(name.to_string());
                 }
             }
         }
 
-        // Always ensure English is available as fallback
-        if locales.is_empty() {
-            locales.push("en".to_string());
-        }
+        // English is the base set, always available as fallback
+        locales.push(BASE_LOCALE.to_string());
 
         locales.sort();
+        locales.dedup();
         locales
     }
 
@@ -155,8 +747,26 @@ impl ScenarioLoader {
         }
     }
 
+    /// Allow loading from a directory outside the default paths
+    ///
+    /// The directory is checked by `path_validator` and added to the allowed
+    /// base paths in canonical form.
+    ///
+    /// # Errors
+    /// Returns UserError if the path is not a directory or looks suspicious
+    pub fn allow_directory(&mut self, dir: &Path) -> Result<PathBuf, UserError> {
+        let canonical = path_validator::validate_base_dir(dir).map_err(UserError::from)?;
+        if !self.allowed_base_paths.contains(&canonical) {
+            self.allowed_base_paths.push(canonical.clone());
+        }
+        Ok(canonical)
+    }
+
     /// Load scenarios from a directory, scanning recursively for all .toml files
     ///
+    /// Scenarios of every pack below the directory are returned in pack
+    /// order; see `load_packs`.
+    ///
     /// # Security Validations
     /// - Directory must be within allowed directories
     /// - Each file is validated using the same security checks as `load()`
@@ -165,6 +775,140 @@ impl ScenarioLoader {
     /// # Errors
     /// Returns UserError if any file fails to load or validation fails
     pub fn load_directory(&self, dir_path: &Path) -> Result<Vec<Scenario>, UserError> {
+        Ok(self
+            .load_packs(dir_path)?
+            .into_iter()
+            .flat_map(|pack| pack.scenarios)
+            .collect())
+    }
+
+    /// Load scenarios from a directory, grouped by pack
+    ///
+    /// A directory holding a `pack.toml` manifest starts a pack that covers
+    /// every scenario file below it, down to the next manifest. Scenarios
+    /// outside any pack come first, in a pack without a manifest. Files and
+    /// directories are visited in name order.
+    ///
+    /// # Errors
+    /// Returns `UserError::IncompatiblePack` if a manifest declares another
+    /// schema version or a newer trainer, and other `UserError`s if any
+    /// file fails to load or validation fails
+    pub fn load_packs(&self, dir_path: &Path) -> Result<Vec<ScenarioPack>, UserError> {
+        self.load_sources(&[ScenarioSource::Directory(dir_path.to_path_buf())])
+    }
+
+    /// Load scenarios from several sources, grouped by pack
+    ///
+    /// Packs of each source follow those of the previous one. A scenario
+    /// from a later source replaces an earlier one with the same id, in the
+    /// earlier one's place, so user packs can override built-in scenarios.
+    /// `requires` may name scenarios from any of the sources, and within a
+    /// pack a scenario moves after those it requires. Translations from any
+    /// source replace the texts of the scenarios they name; a template is
+    /// translated by its own id before its instances are generated.
+    ///
+    /// # Errors
+    /// Returns UserError if any source fails to load, if none of them holds
+    /// a scenario, or if `requires` names a missing scenario or forms a cycle
+    pub fn load_sources(&self, sources: &[ScenarioSource]) -> Result<Vec<ScenarioPack>, UserError> {
+        let mut source_packs = Vec::new();
+        let mut translations = Vec::new();
+        for source in sources {
+            source_packs.push(match source {
+                ScenarioSource::Directory(dir) => {
+                    self.load_directory_packs(dir, false, &mut translations)?
+                }
+                ScenarioSource::BaseDirectory(dir) => {
+                    self.load_directory_packs(dir, true, &mut translations)?
+                }
+                ScenarioSource::Memory(files) => {
+                    self.load_memory_packs(files, &mut translations)?
+                }
+            });
+        }
+
+        // Translate before templates are expanded, so placeholders in the
+        // translated texts are filled in every instance
+        let scenarios = source_packs
+            .iter_mut()
+            .flatten()
+            .flat_map(|pack| &mut pack.scenarios);
+        for id in translation::apply_translations(scenarios, &translations) {
+            tracing::warn!(scenario = %id, "Translation of a scenario that is not loaded");
+        }
+
+        let mut packs = Vec::new();
+        for mut parsed in source_packs {
+            for pack in &mut parsed {
+                let scenarios = std::mem::take(&mut pack.scenarios);
+                pack.scenarios = self.prepare_scenarios(scenarios).map_err(UserError::from)?;
+            }
+            packs.extend(finish_packs(parsed)?);
+        }
+
+        // Keep the first place of each id, filled with its last definition
+        let mut latest: HashMap<String, Scenario> = packs
+            .iter()
+            .flat_map(|pack| &pack.scenarios)
+            .map(|scenario| (scenario.id.clone(), scenario.clone()))
+            .collect();
+        for pack in &mut packs {
+            let scenarios = std::mem::take(&mut pack.scenarios);
+            pack.scenarios = scenarios
+                .into_iter()
+                .filter_map(|scenario| latest.remove(&scenario.id))
+                .collect();
+        }
+        packs.retain(|pack| !pack.scenarios.is_empty());
+
+        if packs.is_empty() {
+            tracing::warn!("No scenario files found in directory");
+            return Err(UserError::ScenarioLoadError);
+        }
+
+        let all_scenarios: Vec<Scenario> = packs
+            .iter()
+            .flat_map(|pack| pack.scenarios.iter().cloned())
+            .collect();
+        if let Some((scenario, required)) = missing_requirements(&all_scenarios).first() {
+            tracing::error!(
+                scenario = %scenario,
+                required = %required,
+                "Scenario requires a scenario that is not loaded"
+            );
+            return Err(UserError::ScenarioLoadError);
+        }
+        if let Some(cycle) = requirement_cycle(&all_scenarios) {
+            tracing::error!(
+                cycle = %cycle.join(" -> "),
+                "Scenarios require each other in a cycle"
+            );
+            return Err(UserError::ScenarioLoadError);
+        }
+
+        // Prerequisites come first within each pack
+        for pack in &mut packs {
+            pack.scenarios = order_by_requirements(std::mem::take(&mut pack.scenarios));
+        }
+
+        tracing::info!(
+            scenario_count = all_scenarios.len(),
+            pack_count = packs.len(),
+            "Successfully loaded scenarios"
+        );
+
+        Ok(packs)
+    }
+
+    /// Parse the packs of one directory, collecting its translations
+    ///
+    /// Scenarios are left for `prepare_scenarios`. With `skip_locales`, subdirectories named like a locale are left out.
+    fn load_directory_packs(
+        &self,
+        dir_path: &Path,
+        skip_locales: bool,
+        translations: &mut Vec<Translation>,
+    ) -> Result<Vec<ScenarioPack>, UserError> {
         // Validate directory path
         let canonical = path_validator::validate_path(dir_path, &self.allowed_base_paths)
             .map_err(UserError::from)?;
@@ -179,55 +923,158 @@ impl ScenarioLoader {
             "Loading scenarios from directory"
         );
 
-        let mut all_scenarios = Vec::new();
+        let mut packs = vec![ScenarioPack {
+            manifest: None,
+            scenarios: Vec::new(),
+        }];
         let mut file_count = 0;
 
         // Recursively walk directory and collect all .toml files
-        self.visit_toml_files(&canonical, &mut all_scenarios, &mut file_count)?;
+        self.visit_toml_files(
+            &canonical,
+            &mut packs,
+            0,
+            translations,
+            &mut file_count,
+            skip_locales,
+        )?;
 
-        if all_scenarios.is_empty() {
-            tracing::warn!("No scenario files found in directory");
-            return Err(UserError::ScenarioLoadError);
+        tracing::info!(file_count = file_count, "Loaded scenarios from directory");
+        Ok(packs)
+    }
+
+    /// Parse the packs of files held in memory, collecting their translations
+    ///
+    /// Files are grouped into packs by their paths the way a directory walk
+    /// groups them, and go through the same checks as files on disk.
+    /// Scenarios are left for `prepare_scenarios`.
+    fn load_memory_packs(
+        &self,
+        files: &[(String, String)],
+        translations: &mut Vec<Translation>,
+    ) -> Result<Vec<ScenarioPack>, UserError> {
+        let mut files: Vec<(&Path, &str)> = files
+            .iter()
+            .filter(|(path, _)| path.ends_with(".toml"))
+            .map(|(path, content)| (Path::new(path.as_str()), content.as_str()))
+            .collect();
+        files.sort_by_key(|(path, _)| *path);
+
+        for (path, content) in &files {
+            if content.len() as u64 > MAX_SCENARIO_FILE_SIZE {
+                tracing::error!(file = %path.display(), "Embedded scenario file too large");
+                return Err(UserError::from(SecurityError::FileTooLarge {
+                    max: MAX_SCENARIO_FILE_SIZE,
+                    actual: content.len() as u64,
+                }));
+            }
         }
 
-        tracing::info!(
-            scenario_count = all_scenarios.len(),
-            file_count = file_count,
-            "Successfully loaded scenarios from directory"
-        );
+        // Pack directories in walk order, with the manifest-less root first
+        let (manifests, scenario_files): (Vec<_>, Vec<_>) =
+            files.into_iter().partition(|(path, _)| {
+                path.file_name().and_then(|s| s.to_str()) == Some(PACK_MANIFEST)
+            });
+        let mut pack_dirs = vec![Path::new("")];
+        let mut packs = vec![ScenarioPack {
+            manifest: None,
+            scenarios: Vec::new(),
+        }];
+        let mut manifests: Vec<(&Path, &str)> = manifests
+            .into_iter()
+            .map(|(path, content)| (path.parent().unwrap_or(Path::new("")), content))
+            .collect();
+        manifests.sort_by_key(|(dir, _)| *dir);
+        for (dir, content) in manifests {
+            let manifest = parse_manifest(content).map_err(UserError::from)?;
+            pack_dirs.push(dir);
+            packs.push(ScenarioPack {
+                manifest: Some(manifest),
+                scenarios: Vec::new(),
+            });
+        }
 
-        Ok(all_scenarios)
+        for (path, content) in scenario_files {
+            // The deepest pack directory holding the file
+            let pack = pack_dirs
+                .iter()
+                .enumerate()
+                .filter(|(_, dir)| path.starts_with(dir))
+                .max_by_key(|(_, dir)| dir.components().count())
+                .map_or(0, |(index, _)| index);
+            let mut file = parse_unprepared(content).map_err(|e| {
+                tracing::error!(file = %path.display(), "Failed to load scenario file: {:?}", e);
+                UserError::from(e)
+            })?;
+            packs[pack].scenarios.append(&mut file.scenarios);
+            translations.append(&mut file.translations);
+        }
+
+        Ok(packs)
     }
 
     /// Recursively visit all .toml files in a directory
+    ///
+    /// Scenarios go to `packs[pack]` unless the directory has its own
+    /// manifest, which starts a new pack. Translations go to `translations`.
+    /// With `skip_locales`, this directory's locale subdirectories are left
+    /// out.
     fn visit_toml_files(
         &self,
         dir: &Path,
-        scenarios: &mut Vec<Scenario>,
+        packs: &mut Vec<ScenarioPack>,
+        mut pack: usize,
+        translations: &mut Vec<Translation>,
         file_count: &mut usize,
+        skip_locales: bool,
     ) -> Result<(), UserError> {
+        let manifest_path = dir.join(PACK_MANIFEST);
+        if manifest_path.is_file() {
+            let manifest = self
+                .load_manifest(&manifest_path)
+                .map_err(UserError::from)?;
+            packs.push(ScenarioPack {
+                manifest: Some(manifest),
+                scenarios: Vec::new(),
+            });
+            pack = packs.len() - 1;
+        }
+
         let entries = fs::read_dir(dir).map_err(|e| {
             tracing::error!("Failed to read directory: {}", e);
             UserError::ScenarioLoadError
         })?;
 
+        let mut paths = Vec::new();
         for entry in entries {
             let entry = entry.map_err(|e| {
                 tracing::error!("Failed to read directory entry: {}", e);
                 UserError::ScenarioLoadError
             })?;
+            paths.push(entry.path());
+        }
+        paths.sort();
 
-            let path = entry.path();
-
+        for path in paths {
             if path.is_dir() {
+                let is_locale = path
+                    .file_name()
+                    .and_then(|s| s.to_str())
+                    .is_some_and(is_locale_code);
+                if skip_locales && is_locale {
+                    continue;
+                }
                 // Recursively visit subdirectories
-                self.visit_toml_files(&path, scenarios, file_count)?;
-            } else if path.extension().and_then(|s| s.to_str()) == Some("toml") {
-                // Load scenarios from this file
-                match self.load(&path) {
-                    Ok(mut file_scenarios) => {
+                self.visit_toml_files(&path, packs, pack, translations, file_count, false)?;
+            } else if path.extension().and_then(|s| s.to_str()) == Some("toml")
+                && path.file_name().and_then(|s| s.to_str()) != Some(PACK_MANIFEST)
+            {
+                // Parse scenarios from this file
+                match self.read_file(&path) {
+                    Ok(mut file) => {
                         *file_count += 1;
-                        scenarios.append(&mut file_scenarios);
+                        packs[pack].scenarios.append(&mut file.scenarios);
+                        translations.append(&mut file.translations);
                     }
                     Err(e) => {
                         tracing::error!(
@@ -244,6 +1091,11 @@ impl ScenarioLoader {
         Ok(())
     }
 
+    /// Read, parse and check a pack manifest
+    fn load_manifest(&self, path: &Path) -> Result<PackManifest, SecurityError> {
+        parse_manifest(&self.read_scenario_file(path)?)
+    }
+
     /// Load scenarios from a TOML file with comprehensive security validations
     ///
     /// # Security Validations
@@ -256,48 +1108,92 @@ impl ScenarioLoader {
     /// # Errors
     /// Returns UserError with sanitized message if any validation fails
     pub fn load(&self, path: &Path) -> Result<Vec<Scenario>, UserError> {
-        // Validate path to prevent path traversal attacks
-        let canonical = path_validator::validate_path(path, &self.allowed_base_paths)
-            .map_err(UserError::from)?;
+        self.load_file(path).map(|file| file.scenarios)
+    }
 
-        // Validate file size to prevent resource exhaustion
-        path_validator::validate_file_size(&canonical, MAX_SCENARIO_FILE_SIZE)
+    /// Load the scenarios and translations of a TOML file
+    fn load_file(&self, path: &Path) -> Result<ScenariosFile, UserError> {
+        let mut file = self.read_file(path)?;
+        file.scenarios = self
+            .prepare_scenarios(file.scenarios)
             .map_err(UserError::from)?;
 
-        // Log with sanitized path (doesn't leak full path)
         tracing::info!(
-            file = %sanitizer::sanitize_path_for_logging(&canonical),
-            "Loading scenario file"
+            count = file.scenarios.len(),
+            translations = file.translations.len(),
+            "Successfully loaded scenarios"
         );
 
-        // Read file content
-        let content = fs::read_to_string(&canonical).map_err(|e| {
-            tracing::error!("Failed to read scenario file: {}", e);
-            UserError::ScenarioLoadError
-        })?;
+        Ok(file)
+    }
 
-        // Parse TOML with proper error handling
-        let scenarios_file: ScenariosFile = toml::from_str(&content)
-            .map_err(|e| UserError::from(SecurityError::InvalidToml(e.to_string())))?;
+    /// Parse and validate the scenarios of a file's content
+    fn load_content(&self, content: &str) -> Result<Vec<Scenario>, SecurityError> {
+        self.load_file_content(content).map(|file| file.scenarios)
+    }
 
-        let scenarios = scenarios_file.scenarios;
+    /// Read and parse a TOML file, leaving its scenarios for `prepare_scenarios`
+    fn read_file(&self, path: &Path) -> Result<ScenariosFile, UserError> {
+        let content = self.read_scenario_file(path).map_err(UserError::from)?;
+        parse_unprepared(&content).map_err(UserError::from)
+    }
 
-        // Validate scenario count
-        if scenarios.len() > MAX_SCENARIOS_PER_FILE {
-            return Err(UserError::from(SecurityError::TooManyScenarios {
-                max: MAX_SCENARIOS_PER_FILE,
-                actual: scenarios.len(),
-            }));
-        }
+    /// Parse and validate the scenarios and translations of a file's content
+    fn load_file_content(&self, content: &str) -> Result<ScenariosFile, SecurityError> {
+        let mut file = parse_unprepared(content)?;
+        file.scenarios = self.prepare_scenarios(file.scenarios)?;
+        Ok(file)
+    }
 
-        // Validate each scenario
-        for scenario in &scenarios {
-            self.validate_scenario(scenario).map_err(UserError::from)?;
+    /// Expand templates, then resolve selection markers and validate each scenario
+    fn prepare_scenarios(&self, scenarios: Vec<Scenario>) -> Result<Vec<Scenario>, SecurityError> {
+        let mut prepared = Vec::new();
+        for scenario in scenarios {
+            let id = scenario.id.clone();
+            let expanded = template::expand(scenario).and_then(|mut instances| {
+                for instance in &mut instances {
+                    self.prepare_scenario(instance)?;
+                }
+                Ok(instances)
+            });
+            match expanded {
+                Ok(instances) => prepared.extend(instances),
+                Err(e) => {
+                    tracing::error!(scenario = %id, "Invalid scenario: {:?}", e);
+                    return Err(e);
+                }
+            }
         }
+        Ok(prepared)
+    }
+
+    /// Read a scenario file after checking its path and size
+    fn read_scenario_file(&self, path: &Path) -> Result<String, SecurityError> {
+        // Validate path to prevent path traversal attacks
+        let canonical = path_validator::validate_path(path, &self.allowed_base_paths)?;
 
-        tracing::info!(count = scenarios.len(), "Successfully loaded scenarios");
+        // Validate file size to prevent resource exhaustion
+        path_validator::validate_file_size(&canonical, MAX_SCENARIO_FILE_SIZE)?;
 
-        Ok(scenarios)
+        // Log with sanitized path (doesn't leak full path)
+        tracing::info!(
+            file = %sanitizer::sanitize_path_for_logging(&canonical),
+            "Loading scenario file"
+        );
+
+        fs::read_to_string(&canonical).map_err(|e| {
+            tracing::error!("Failed to read scenario file: {}", e);
+            SecurityError::InvalidPath
+        })
+    }
+
+    /// Resolve selection markers and validate a parsed scenario
+    fn prepare_scenario(&self, scenario: &mut Scenario) -> Result<(), SecurityError> {
+        if scenario.markers {
+            apply_markers(scenario)?;
+            scenario.markers = false;
+        }
+        self.validate_scenario(scenario)
     }
 
     /// Validate a single scenario for security and correctness
@@ -310,17 +1206,71 @@ impl ScenarioLoader {
             });
         }
 
-        // Validate target content size
-        if scenario.target.file_content.len() > MAX_FILE_CONTENT_LENGTH {
-            return Err(SecurityError::ContentTooLarge {
-                max: MAX_FILE_CONTENT_LENGTH,
-                actual: scenario.target.file_content.len(),
+        let target = scenario.target.as_ref().ok_or_else(|| {
+            SecurityError::InvalidInput("Scenario has no [scenarios.target]".to_string())
+        })?;
+        self.validate_target(target)?;
+
+        // Validate setup cursor position and selections
+        let cursor = scenario.setup.cursor_position.ok_or_else(|| {
+            SecurityError::InvalidInput(
+                "Setup needs a cursor_position unless markers = true".to_string(),
+            )
+        })?;
+        self.validate_cursor_position(cursor)?;
+        self.validate_selections(&scenario.setup.selections, scenario.setup.primary_selection)?;
+        if let Some(primary) = scenario
+            .setup
+            .selections
+            .get(scenario.setup.primary_selection)
+            && primary.head != cursor
+        {
+            return Err(SecurityError::InvalidInput(
+                "Setup cursor_position must be the head of the primary selection".to_string(),
+            ));
+        }
+
+        // Validate pre-filled registers
+        if scenario.setup.registers.len() > MAX_REGISTERS {
+            return Err(SecurityError::InvalidInput(format!(
+                "Too many registers (max {})",
+                MAX_REGISTERS
+            )));
+        }
+        for values in scenario.setup.registers.values() {
+            if values.len() > MAX_SELECTIONS {
+                return Err(SecurityError::InvalidInput(format!(
+                    "Too many register values (max {})",
+                    MAX_SELECTIONS
+                )));
+            }
+            let total: usize = values.iter().map(String::len).sum();
+            if total > MAX_FILE_CONTENT_LENGTH {
+                return Err(SecurityError::ContentTooLarge {
+                    max: MAX_FILE_CONTENT_LENGTH,
+                    actual: total,
+                });
+            }
+        }
+
+        // Validate the pre-seeded repeat action
+        if scenario.setup.last_action.len() > MAX_COMMAND_SEQUENCE_LENGTH {
+            return Err(SecurityError::CommandSequenceTooLong {
+                max: MAX_COMMAND_SEQUENCE_LENGTH,
             });
         }
 
-        // Validate cursor positions
-        self.validate_cursor_position(scenario.setup.cursor_position)?;
-        self.validate_cursor_position(scenario.target.cursor_position)?;
+        if let Some(unit) = &scenario.setup.indent_unit
+            && !is_valid_indent_unit(unit)
+        {
+            return Err(SecurityError::InvalidInput(format!(
+                "Setup indent_unit must be a tab or 1 to {} spaces",
+                MAX_INDENT_WIDTH
+            )));
+        }
+
+        self.validate_metadata(scenario)?;
+        self.validate_constraints(scenario)?;
 
         // Validate hints count
         if scenario.hints.len() > MAX_HINTS {
@@ -355,6 +1305,216 @@ impl ScenarioLoader {
             }
         }
 
+        self.validate_lesson_steps(scenario)?;
+
+        // Replay the solution and alternatives to make sure they work
+        verify::verify_solutions(scenario)?;
+
+        Ok(())
+    }
+
+    /// Validate target content size, cursor position and selections
+    fn validate_target(&self, target: &TargetState) -> Result<(), SecurityError> {
+        if target.file_content.len() > MAX_FILE_CONTENT_LENGTH {
+            return Err(SecurityError::ContentTooLarge {
+                max: MAX_FILE_CONTENT_LENGTH,
+                actual: target.file_content.len(),
+            });
+        }
+
+        let cursor = target.cursor_position.ok_or_else(|| {
+            SecurityError::InvalidInput(
+                "Target needs a cursor_position unless markers = true".to_string(),
+            )
+        })?;
+        self.validate_cursor_position(cursor)?;
+        self.validate_selections(&target.selections, target.primary_selection)?;
+        let checks_selections =
+            target.match_selection || target.completion == Completion::Selection;
+        if checks_selections && target.selections.is_empty() && target.selection.is_none() {
+            return Err(SecurityError::InvalidInput(
+                "match_selection and the selection rule require target selections".to_string(),
+            ));
+        }
+        if let Completion::Line { text, .. } = &target.completion
+            && (text.len() > MAX_FILE_CONTENT_LENGTH || text.contains('\n'))
+        {
+            return Err(SecurityError::InvalidInput(
+                "A line completion rule needs a single line of text".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Validate the steps of a lesson after the first
+    fn validate_lesson_steps(&self, scenario: &Scenario) -> Result<(), SecurityError> {
+        if scenario.steps.len() > MAX_LESSON_STEPS {
+            return Err(SecurityError::InvalidInput(format!(
+                "Too many lesson steps (max {})",
+                MAX_LESSON_STEPS + 1
+            )));
+        }
+
+        for (index, step) in scenario.steps.iter().enumerate() {
+            if step.instructions.trim().is_empty() {
+                return Err(SecurityError::InvalidInput(format!(
+                    "Lesson step {} has no instructions",
+                    index + 2
+                )));
+            }
+            self.validate_target(&step.target)?;
+            if step.hints.len() > MAX_HINTS {
+                return Err(SecurityError::TooManyHints { max: MAX_HINTS });
+            }
+            if step.scoring.optimal_count == 0 {
+                return Err(SecurityError::InvalidScoringConfig);
+            }
+            if step.solution.commands.len() > MAX_COMMAND_SEQUENCE_LENGTH {
+                return Err(SecurityError::CommandSequenceTooLong {
+                    max: MAX_COMMAND_SEQUENCE_LENGTH,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Validate the optional category, difficulty and other metadata
+    fn validate_metadata(&self, scenario: &Scenario) -> Result<(), SecurityError> {
+        if let Some(category) = &scenario.category
+            && !is_valid_label(category)
+        {
+            return Err(SecurityError::InvalidInput(format!(
+                "Invalid category '{}': use lowercase words joined by '-' or '_'",
+                category
+            )));
+        }
+
+        if let Some(difficulty) = scenario.difficulty
+            && !(1..=MAX_DIFFICULTY).contains(&difficulty)
+        {
+            return Err(SecurityError::InvalidInput(format!(
+                "Difficulty must be between 1 and {} (got {})",
+                MAX_DIFFICULTY, difficulty
+            )));
+        }
+
+        if let Some(seconds) = scenario.estimated_seconds
+            && (seconds == 0 || u64::from(seconds) > SESSION_TIMEOUT.as_secs())
+        {
+            return Err(SecurityError::InvalidInput(format!(
+                "estimated_seconds must be between 1 and {}",
+                SESSION_TIMEOUT.as_secs()
+            )));
+        }
+
+        for (field, values) in [
+            ("tags", &scenario.tags),
+            ("requires", &scenario.requires),
+            ("commands_taught", &scenario.commands_taught),
+        ] {
+            if values.len() > MAX_METADATA_ENTRIES {
+                return Err(SecurityError::InvalidInput(format!(
+                    "Too many {} (max {})",
+                    field, MAX_METADATA_ENTRIES
+                )));
+            }
+        }
+
+        if let Some(tag) = scenario.tags.iter().find(|tag| !is_valid_label(tag)) {
+            return Err(SecurityError::InvalidInput(format!(
+                "Invalid tag '{}': use lowercase words joined by '-' or '_'",
+                tag
+            )));
+        }
+
+        for required in &scenario.requires {
+            if required.is_empty() || !is_valid_id(required) {
+                return Err(SecurityError::InvalidInput(format!(
+                    "Invalid scenario id '{}' in requires",
+                    required
+                )));
+            }
+            if *required == scenario.id {
+                return Err(SecurityError::InvalidInput(format!(
+                    "Scenario '{}' requires itself",
+                    scenario.id
+                )));
+            }
+        }
+
+        if let Some(command) = scenario
+            .commands_taught
+            .iter()
+            .find(|command| command.is_empty() || command.len() > MAX_LABEL_LENGTH)
+        {
+            return Err(SecurityError::InvalidInput(format!(
+                "Invalid command '{}' in commands_taught",
+                command
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Validate forbidden and required commands
+    fn validate_constraints(&self, scenario: &Scenario) -> Result<(), SecurityError> {
+        for (field, commands) in [
+            ("forbidden_commands", &scenario.forbidden_commands),
+            ("required_commands", &scenario.required_commands),
+        ] {
+            if commands.len() > MAX_METADATA_ENTRIES {
+                return Err(SecurityError::InvalidInput(format!(
+                    "Too many {} (max {})",
+                    field, MAX_METADATA_ENTRIES
+                )));
+            }
+            if let Some(command) = commands
+                .iter()
+                .find(|command| command.is_empty() || command.len() > MAX_LABEL_LENGTH)
+            {
+                return Err(SecurityError::InvalidInput(format!(
+                    "Invalid command '{}' in {}",
+                    command, field
+                )));
+            }
+        }
+
+        if let Some(command) = scenario
+            .required_commands
+            .iter()
+            .find(|command| scenario.forbidden_commands.contains(command))
+        {
+            return Err(SecurityError::InvalidInput(format!(
+                "Command '{}' is both forbidden and required",
+                command
+            )));
+        }
+        Ok(())
+    }
+
+    /// Validate selection count, positions and primary index
+    fn validate_selections(
+        &self,
+        selections: &[SelectionRange],
+        primary_selection: usize,
+    ) -> Result<(), SecurityError> {
+        if selections.len() > MAX_SELECTIONS {
+            return Err(SecurityError::InvalidInput(format!(
+                "Too many selections (max {})",
+                MAX_SELECTIONS
+            )));
+        }
+        for sel in selections {
+            self.validate_cursor_position(sel.anchor)?;
+            self.validate_cursor_position(sel.head)?;
+        }
+        if !selections.is_empty() && primary_selection >= selections.len() {
+            return Err(SecurityError::InvalidInput(format!(
+                "Primary selection {} out of range for {} selections",
+                primary_selection,
+                selections.len()
+            )));
+        }
         Ok(())
     }
 
@@ -374,5 +1534,7 @@ impl Default for ScenarioLoader {
     }
 }
 
+#[cfg(test)]
+pub(crate) mod fixtures;
 #[cfg(test)]
 mod tests;
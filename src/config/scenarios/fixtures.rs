@@ -0,0 +1,154 @@
+//! Scenario files for tests
+//!
+//! `ScenarioToml` writes a single scenario with a default for every table,
+//! so a test spells out only the parts it is about. `write_files` puts files
+//! in a fresh directory together with a loader allowed to read it.
+
+use super::{Scenario, ScenarioLoader, ScenariosFile};
+use std::path::PathBuf;
+use tempfile::TempDir;
+
+/// A `[[scenarios]]` table, by default deleting the "f" of "foo bar" with `x`
+pub(crate) struct ScenarioToml {
+    id: String,
+    name: String,
+    fields: String,
+    setup: String,
+    target: Option<String>,
+    commands: String,
+    solution_fields: String,
+    optimal_count: usize,
+    sections: String,
+}
+
+impl ScenarioToml {
+    pub(crate) fn new(id: &str) -> Self {
+        Self {
+            id: id.to_string(),
+            name: "Test".to_string(),
+            fields: String::new(),
+            setup: section("foo bar", [0, 0]),
+            target: Some(section("oo bar", [0, 0])),
+            commands: r#"["x"]"#.to_string(),
+            solution_fields: String::new(),
+            optimal_count: 1,
+            sections: String::new(),
+        }
+    }
+
+    pub(crate) fn name(mut self, name: &str) -> Self {
+        self.name = name.to_string();
+        self
+    }
+
+    /// Lines added to the scenario table after its description
+    pub(crate) fn fields(mut self, fields: &str) -> Self {
+        self.fields = fields.to_string();
+        self
+    }
+
+    /// Body of the `[scenarios.setup]` table
+    pub(crate) fn setup(mut self, setup: &str) -> Self {
+        self.setup = setup.to_string();
+        self
+    }
+
+    /// Body of the `[scenarios.target]` table
+    pub(crate) fn target(mut self, target: &str) -> Self {
+        self.target = Some(target.to_string());
+        self
+    }
+
+    /// Leave out the target table, as templates do
+    pub(crate) fn without_target(mut self) -> Self {
+        self.target = None;
+        self
+    }
+
+    /// Solution commands, as a TOML array, and their optimal key count
+    pub(crate) fn solution(mut self, commands: &str, optimal_count: usize) -> Self {
+        self.commands = commands.to_string();
+        self.optimal_count = optimal_count;
+        self
+    }
+
+    /// Lines added to the solution table
+    pub(crate) fn solution_fields(mut self, fields: &str) -> Self {
+        self.solution_fields = fields.to_string();
+        self
+    }
+
+    /// Tables appended after the scoring table, such as lesson steps
+    pub(crate) fn sections(mut self, sections: &str) -> Self {
+        self.sections = sections.to_string();
+        self
+    }
+
+    pub(crate) fn build(&self) -> String {
+        let target = match &self.target {
+            Some(target) => format!("[scenarios.target]\n{target}\n"),
+            None => String::new(),
+        };
+        format!(
+            r#"
+[[scenarios]]
+id = "{}"
+name = "{}"
+description = "Test"
+{}
+
+[scenarios.setup]
+{}
+
+{}
+[scenarios.solution]
+commands = {}
+description = "test"
+{}
+
+[scenarios.scoring]
+optimal_count = {}
+max_points = 100
+tolerance = 0
+
+{}
+"#,
+            self.id,
+            self.name,
+            self.fields,
+            self.setup,
+            target,
+            self.commands,
+            self.solution_fields,
+            self.optimal_count,
+            self.sections
+        )
+    }
+
+    /// Parse the scenario without validating it
+    pub(crate) fn parse(&self) -> Scenario {
+        let file: ScenariosFile = toml::from_str(&self.build()).unwrap();
+        file.scenarios.into_iter().next().unwrap()
+    }
+}
+
+/// Setup or target table body with the given content and cursor
+pub(crate) fn section(content: &str, cursor: [usize; 2]) -> String {
+    format!("file_content = {content:?}\ncursor_position = {cursor:?}")
+}
+
+/// Write files (paths relative to a fresh directory) and make a loader for it
+///
+/// Returns the directory, which is removed when dropped, and its
+/// canonical path.
+pub(crate) fn write_files(files: &[(&str, String)]) -> (TempDir, PathBuf, ScenarioLoader) {
+    let dir = tempfile::tempdir().unwrap();
+    for (name, content) in files {
+        let path = dir.path().join(name);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+    let base = dir.path().canonicalize().unwrap();
+    let loader = ScenarioLoader::with_allowed_paths(vec![base.clone()]);
+    (dir, base, loader)
+}
@@ -0,0 +1,422 @@
+//! Challenge import
+//!
+//! Turns a VimGolf-style challenge, a start text and the end text it should
+//! become, into a scenario file. Only the end content counts, so the
+//! scenario uses the `content` completion rule; the solution and
+//! `optimal_count` come from the shortest solution search, and the target
+//! cursor is wherever that solution leaves it. An optional meta TOML names
+//! the scenario and can supply a solution for challenges too long to search.
+//!
+//! # Examples
+//!
+//! ```ignore
+//! use helix_trainer::config::{ImportMeta, import_challenge};
+//!
+//! let toml = import_challenge("foo bar\n", "bar\n", &ImportMeta::default(), "golf")?;
+//! std::fs::write("golf.toml", toml)?;
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use super::{
+    Completion, Scenario, ScenarioLoader, ScoringConfig, Setup, SetupMode, Solution, TargetState,
+    is_valid_id,
+};
+use crate::game::GameSession;
+use crate::game::solver::{SearchBudget, SearchOutcome, find_shortest_solution};
+use crate::helix::keys::push_key;
+use crate::security::SecurityError;
+use crate::security::limits::*;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+/// Marker sequences that would clash with a `#[|]#` cursor marker
+const MARKER_SYNTAX: [&str; 4] = ["#[", "#(", "]#", ")#"];
+
+/// Optional details of an imported challenge, read from its meta TOML
+#[derive(Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct ImportMeta {
+    /// Scenario id; defaults to the one derived from the start file name
+    pub id: Option<String>,
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub category: Option<String>,
+    pub difficulty: Option<u8>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub hints: Vec<String>,
+    /// Start cursor: [line, col]; the first character by default
+    #[serde(default)]
+    pub cursor: (usize, usize),
+    /// Keys to use when the search finds no solution
+    #[serde(default)]
+    pub solution: Vec<String>,
+    pub max_points: Option<u32>,
+    pub tolerance: Option<usize>,
+    /// Longest solution to search for, in keys
+    pub max_keys: Option<usize>,
+}
+
+/// Convert a start and end text into the TOML of a validated scenario
+///
+/// `default_id` is used when the meta has no id; characters an id may not
+/// hold become underscores.
+///
+/// # Errors
+///
+/// Returns `SecurityError::InvalidInput` if the texts are equal or no
+/// solution is found or given, and any loader error for the generated
+/// scenario.
+pub fn import_challenge(
+    start: &str,
+    end: &str,
+    meta: &ImportMeta,
+    default_id: &str,
+) -> Result<String, SecurityError> {
+    for text in [start, end] {
+        if text.len() > MAX_FILE_CONTENT_LENGTH {
+            return Err(SecurityError::ContentTooLarge {
+                max: MAX_FILE_CONTENT_LENGTH,
+                actual: text.len(),
+            });
+        }
+    }
+    if start == end {
+        return Err(SecurityError::InvalidInput(
+            "The start and end texts are the same".to_string(),
+        ));
+    }
+
+    let id = meta.id.clone().unwrap_or_else(|| to_id(default_id));
+    if id.is_empty() || !is_valid_id(&id) {
+        return Err(SecurityError::InvalidScenarioId);
+    }
+    let mut scenario = challenge_scenario(&id, start, end, meta);
+
+    let budget = SearchBudget {
+        max_keys: meta.max_keys.unwrap_or(SearchBudget::default().max_keys),
+        ..SearchBudget::default()
+    };
+    let keys = match find_shortest_solution(&scenario, &budget) {
+        SearchOutcome::Found(keys) => keys,
+        SearchOutcome::NotFound | SearchOutcome::OutOfBudget if !meta.solution.is_empty() => {
+            meta.solution.clone()
+        }
+        SearchOutcome::NotFound | SearchOutcome::OutOfBudget => {
+            return Err(SecurityError::InvalidInput(format!(
+                "No solution found within {} keys; add a solution to the meta file",
+                budget.max_keys
+            )));
+        }
+    };
+    scenario.scoring.optimal_count = keys.len();
+    let cursor = end_cursor(&scenario, &keys)?;
+    if let Some(target) = &mut scenario.target {
+        target.cursor_position = Some(cursor);
+    }
+    scenario.solution.commands = keys;
+
+    let toml = to_toml(&scenario);
+    // Load the result the way a scenario file is loaded
+    ScenarioLoader::new().load_content(&toml)?;
+    Ok(toml)
+}
+
+/// Lowercase id from a file name, with other characters as underscores
+fn to_id(name: &str) -> String {
+    name.chars()
+        .take(64)
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// Scenario for the challenge, still without solution and target cursor
+fn challenge_scenario(id: &str, start: &str, end: &str, meta: &ImportMeta) -> Scenario {
+    Scenario {
+        id: id.to_string(),
+        name: meta.name.clone().unwrap_or_else(|| id.to_string()),
+        description: meta
+            .description
+            .clone()
+            .unwrap_or_else(|| "Turn the text into the target text".to_string()),
+        category: meta.category.clone(),
+        difficulty: meta.difficulty,
+        tags: meta.tags.clone(),
+        requires: Vec::new(),
+        commands_taught: Vec::new(),
+        forbidden_commands: Vec::new(),
+        required_commands: Vec::new(),
+        estimated_seconds: None,
+        setup: Setup {
+            file_content: start.to_string(),
+            cursor_position: Some(meta.cursor),
+            selections: Vec::new(),
+            primary_selection: 0,
+            mode: SetupMode::Normal,
+            registers: BTreeMap::new(),
+            last_action: Vec::new(),
+            indent_unit: None,
+        },
+        target: Some(TargetState {
+            file_content: end.to_string(),
+            completion: Completion::Content,
+            ..TargetState::default()
+        }),
+        solution: Solution {
+            commands: Vec::new(),
+            description: "Shortest solution found".to_string(),
+        },
+        alternatives: Vec::new(),
+        hints: meta.hints.clone(),
+        scoring: ScoringConfig {
+            optimal_count: 0,
+            max_points: meta.max_points.unwrap_or(100),
+            tolerance: meta.tolerance.unwrap_or(2),
+        },
+        markers: false,
+        template: None,
+        steps: Vec::new(),
+    }
+}
+
+/// Cursor position once the keys have reached the end text
+fn end_cursor(scenario: &Scenario, keys: &[String]) -> Result<(usize, usize), SecurityError> {
+    let failed = |reason: &str| SecurityError::InvalidInput(format!("Solution {}", reason));
+    let mut session = GameSession::new(scenario.clone()).map_err(|_| failed("cannot start"))?;
+    let mut buffer = String::new();
+    for key in keys {
+        if session.is_completed() {
+            break;
+        }
+        let command = if session.is_insert_mode() {
+            Some(key.clone())
+        } else {
+            push_key(&mut buffer, key)
+        };
+        if let Some(command) = command {
+            session
+                .record_action(command)
+                .map_err(|_| failed(&format!("key '{}' failed", key)))?;
+        }
+    }
+    if !session.is_completed() {
+        return Err(failed("does not reach the end text"));
+    }
+    let cursor = session.current_state().cursor_position();
+    Ok((cursor.row, cursor.col))
+}
+
+/// Scenario file text, marking cursors inline when the texts allow it
+fn to_toml(scenario: &Scenario) -> String {
+    let setup = &scenario.setup;
+    let target = scenario.target.clone().unwrap_or_default();
+    let setup_cursor = setup.cursor_position.unwrap_or_default();
+    let target_cursor = target.cursor_position.unwrap_or_default();
+    let markers = [&setup.file_content, &target.file_content]
+        .iter()
+        .all(|text| MARKER_SYNTAX.iter().all(|marker| !text.contains(marker)));
+    let quote = |text: &str| toml::Value::String(text.to_string()).to_string();
+    let list = |items: &[String]| {
+        let quoted: Vec<String> = items.iter().map(|item| quote(item)).collect();
+        format!("[{}]", quoted.join(", "))
+    };
+
+    let mut out = String::new();
+    // Writing to a String cannot fail
+    let _ = writeln!(out, "[[scenarios]]");
+    let _ = writeln!(out, "id = {}", quote(&scenario.id));
+    let _ = writeln!(out, "name = {}", quote(&scenario.name));
+    let _ = writeln!(out, "description = {}", quote(&scenario.description));
+    if let Some(category) = &scenario.category {
+        let _ = writeln!(out, "category = {}", quote(category));
+    }
+    if let Some(difficulty) = scenario.difficulty {
+        let _ = writeln!(out, "difficulty = {}", difficulty);
+    }
+    if !scenario.tags.is_empty() {
+        let _ = writeln!(out, "tags = {}", list(&scenario.tags));
+    }
+    if !scenario.hints.is_empty() {
+        let _ = writeln!(out, "hints = {}", list(&scenario.hints));
+    }
+    if markers {
+        let _ = writeln!(out, "markers = true");
+    }
+
+    let _ = writeln!(out, "\n[scenarios.setup]");
+    if markers {
+        let marked = mark_cursor(&setup.file_content, setup_cursor);
+        let _ = writeln!(out, "file_content = {}", quote(&marked));
+    } else {
+        let _ = writeln!(out, "file_content = {}", quote(&setup.file_content));
+        let (row, col) = setup_cursor;
+        let _ = writeln!(out, "cursor_position = [{}, {}]", row, col);
+    }
+
+    let _ = writeln!(out, "\n[scenarios.target]");
+    if markers {
+        let marked = mark_cursor(&target.file_content, target_cursor);
+        let _ = writeln!(out, "file_content = {}", quote(&marked));
+    } else {
+        let _ = writeln!(out, "file_content = {}", quote(&target.file_content));
+        let (row, col) = target_cursor;
+        let _ = writeln!(out, "cursor_position = [{}, {}]", row, col);
+    }
+    let _ = writeln!(out, "completion = {{ rule = \"content\" }}");
+
+    let _ = writeln!(out, "\n[scenarios.solution]");
+    let _ = writeln!(out, "commands = {}", list(&scenario.solution.commands));
+    let _ = writeln!(
+        out,
+        "description = {}",
+        quote(&scenario.solution.description)
+    );
+
+    let scoring = &scenario.scoring;
+    let _ = writeln!(out, "\n[scenarios.scoring]");
+    let _ = writeln!(out, "optimal_count = {}", scoring.optimal_count);
+    let _ = writeln!(out, "max_points = {}", scoring.max_points);
+    let _ = writeln!(out, "tolerance = {}", scoring.tolerance);
+    out
+}
+
+/// Insert a bare `#[|]#` cursor marker at a (line, char column) position
+fn mark_cursor(text: &str, (row, col): (usize, usize)) -> String {
+    let mut out = String::with_capacity(text.len() + 5);
+    let (mut line, mut column) = (0, 0);
+    let mut marked = false;
+    for ch in text.chars() {
+        if !marked && (line, column) == (row, col) {
+            out.push_str("#[|]#");
+            marked = true;
+        }
+        out.push(ch);
+        if ch == '\n' {
+            line += 1;
+            column = 0;
+        } else {
+            column += 1;
+        }
+    }
+    if !marked {
+        out.push_str("#[|]#");
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mark_cursor() {
+        assert_eq!(mark_cursor("foo\nbar", (1, 1)), "foo\nb#[|]#ar");
+        assert_eq!(mark_cursor("foo", (0, 0)), "#[|]#foo");
+        assert_eq!(mark_cursor("foo", (0, 3)), "foo#[|]#");
+    }
+
+    /// Load imported TOML the way the loader loads a file
+    fn load(toml: &str) -> Scenario {
+        let mut scenarios = ScenarioLoader::new().load_content(toml).unwrap();
+        assert_eq!(scenarios.len(), 1);
+        scenarios.remove(0)
+    }
+
+    #[test]
+    fn test_import_searches_solution_and_marks_cursors() {
+        let toml =
+            import_challenge("foo bar\n", "bar\n", &ImportMeta::default(), "Foo-Bar").unwrap();
+        assert!(toml.contains("markers = true"), "{}", toml);
+        assert!(toml.contains("#[|]#foo bar"), "{}", toml);
+
+        let scenario = load(&toml);
+        assert_eq!(scenario.id, "foo_bar");
+        assert_eq!(scenario.setup.file_content, "foo bar\n");
+        assert_eq!(scenario.target.as_ref().unwrap().file_content, "bar\n");
+        assert_eq!(
+            scenario.target.as_ref().unwrap().completion,
+            Completion::Content
+        );
+        assert_eq!(
+            scenario.scoring.optimal_count,
+            scenario.solution.commands.len()
+        );
+        assert!(scenario.scoring.optimal_count <= 3);
+    }
+
+    #[test]
+    fn test_import_uses_cursor_position_for_marker_like_text() {
+        let toml = import_challenge(
+            "#[derive(Debug)]\nstruct A;\nfn f() {}\n",
+            "#[derive(Debug)]\nfn f() {}\n",
+            &ImportMeta::default(),
+            "derive",
+        )
+        .unwrap();
+        assert!(!toml.contains("markers"), "{}", toml);
+
+        let scenario = load(&toml);
+        assert_eq!(
+            scenario.setup.file_content,
+            "#[derive(Debug)]\nstruct A;\nfn f() {}\n"
+        );
+        assert_eq!(
+            scenario.target.as_ref().unwrap().file_content,
+            "#[derive(Debug)]\nfn f() {}\n"
+        );
+    }
+
+    #[test]
+    fn test_import_applies_meta() {
+        let meta: ImportMeta = toml::from_str(
+            r#"
+id = "golf_001"
+name = "Golf"
+hints = ["Delete the word"]
+tags = ["golf"]
+cursor = [0, 4]
+solution = ["x", "x", "x", "x"]
+max_keys = 1
+"#,
+        )
+        .unwrap();
+        let scenario = load(&import_challenge("foo bar", "foo ", &meta, "ignored").unwrap());
+        assert_eq!(scenario.id, "golf_001");
+        assert_eq!(scenario.name, "Golf");
+        assert_eq!(scenario.hints, ["Delete the word"]);
+        assert_eq!(scenario.tags, ["golf"]);
+        assert_eq!(scenario.setup.cursor_position, Some((0, 4)));
+        // The search gives up after one key, so the meta solution is used
+        assert_eq!(scenario.solution.commands, ["x", "x", "x", "x"]);
+        assert_eq!(scenario.scoring.optimal_count, 4);
+    }
+
+    #[test]
+    fn test_import_rejects_unsolvable_challenges() {
+        let meta = ImportMeta::default();
+        assert!(import_challenge("same", "same", &meta, "same").is_err());
+        let short = ImportMeta {
+            max_keys: Some(1),
+            ..ImportMeta::default()
+        };
+        assert!(import_challenge("foo bar", "bar foo", &short, "swap").is_err());
+        let wrong = ImportMeta {
+            solution: vec!["x".to_string()],
+            ..short
+        };
+        assert!(import_challenge("foo bar", "bar foo", &wrong, "swap").is_err());
+    }
+
+    #[test]
+    fn test_to_id() {
+        assert_eq!(to_id("Hello-World.v2"), "hello_world_v2");
+    }
+}
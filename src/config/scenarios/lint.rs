@@ -0,0 +1,409 @@
+//! Scenario linting for authors
+//!
+//! Runs every check the loader does, but keeps going after a failure so a
+//! single run reports all problems across a set of files. On top of the load
+//! checks it flags ids used more than once, `optimal_count` values that do
+//! not match the solution, hints placed where they are never shown and
+//! prerequisites that name no known scenario. `pack.toml` manifests are
+//! checked as the loader checks them, and templates through the instances
+//! they generate. When the files include `[[translations]]`, scenarios
+//! without a translation and translations of unknown scenarios are
+//! reported, so a locale can be checked against the base set.
+//! With a search budget it also looks for solutions shorter than
+//! `optimal_count`.
+
+use super::{
+    PACK_MANIFEST, Scenario, ScenarioLoader, missing_requirements, parse_file, requirement_cycle,
+    template, translation,
+};
+use crate::game::solver::{SearchBudget, SearchOutcome, find_shortest_solution};
+use crate::security::SecurityError;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Kind of problem found by the linter
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum DiagnosticCode {
+    /// The file cannot be read or parsed
+    InvalidFile,
+    /// The scenario fails a load-time validation check
+    InvalidScenario,
+    /// The solution or an alternative does not reach the target
+    SolutionMismatch,
+    /// The target cursor or selections do not fit the target content
+    UnreachableTarget,
+    /// `optimal_count` differs from the number of solution keys
+    OptimalCount,
+    /// The scenario id is already used by another scenario
+    DuplicateId,
+    /// Hints are nested in a table the trainer never reads them from
+    UnusedHints,
+    /// A solution with fewer keys than `optimal_count` exists
+    ShorterSolution,
+    /// `requires` names a scenario that is not in the checked files
+    UnknownRequirement,
+    /// Scenarios require each other in a cycle
+    RequirementCycle,
+    /// A `pack.toml` manifest is malformed or incompatible with this trainer
+    InvalidPack,
+    /// No translation among the checked files covers the scenario
+    Untranslated,
+    /// A translation names a scenario that is not in the checked files
+    UnknownTranslation,
+}
+
+/// A single problem, located by file and (when known) scenario id
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub file: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scenario: Option<String>,
+    pub code: DiagnosticCode,
+    pub message: String,
+    /// Keys of a better solution, for `shorter-solution`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub suggestion: Option<Vec<String>>,
+}
+
+/// Optional, slower lint checks
+#[derive(Debug, Clone, Default)]
+pub struct LintOptions {
+    /// Search for solutions shorter than `optimal_count` within this budget
+    pub search: Option<SearchBudget>,
+}
+
+/// Result of linting a set of scenario files
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct LintReport {
+    /// Number of files checked
+    pub files: usize,
+    /// Number of pack manifests among them
+    pub packs: usize,
+    /// Number of scenarios parsed
+    pub scenarios: usize,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl LintReport {
+    /// Whether no problems were found
+    pub fn is_clean(&self) -> bool {
+        self.diagnostics.is_empty()
+    }
+}
+
+/// Ids translations may name and ids translated, with the file of each
+#[derive(Default)]
+struct TranslationCoverage {
+    /// Scenario ids, templates by their own id rather than their instances'
+    translatable: BTreeMap<String, String>,
+    translated: BTreeMap<String, String>,
+}
+
+impl ScenarioLoader {
+    /// Check scenario files and directories and report every problem found
+    ///
+    /// Directories are searched recursively for `.toml` files. Unlike `load`,
+    /// this never stops at the first error.
+    pub fn lint(&self, paths: &[PathBuf], options: &LintOptions) -> LintReport {
+        let mut report = LintReport::default();
+        let mut seen_ids: BTreeMap<String, String> = BTreeMap::new();
+        let mut parsed = Vec::new();
+        let mut translations = TranslationCoverage::default();
+
+        for path in paths {
+            let mut files = Vec::new();
+            if path.is_dir() {
+                collect_toml_files(path, &mut files);
+                files.sort();
+            } else {
+                files.push(path.clone());
+            }
+
+            for file in files {
+                report.files += 1;
+                self.lint_file(
+                    &file,
+                    options,
+                    &mut seen_ids,
+                    &mut parsed,
+                    &mut translations,
+                    &mut report,
+                );
+            }
+        }
+
+        // Prerequisites may live in any of the checked files
+        for (id, required) in missing_requirements(&parsed) {
+            report.diagnostics.push(Diagnostic {
+                file: seen_ids.get(&id).cloned().unwrap_or_default(),
+                scenario: Some(id),
+                code: DiagnosticCode::UnknownRequirement,
+                message: format!("requires unknown scenario '{required}'"),
+                suggestion: None,
+            });
+        }
+        if let Some(cycle) = requirement_cycle(&parsed) {
+            let id = cycle[0].clone();
+            report.diagnostics.push(Diagnostic {
+                file: seen_ids.get(&id).cloned().unwrap_or_default(),
+                scenario: Some(id),
+                code: DiagnosticCode::RequirementCycle,
+                message: format!("requires form a cycle: {}", cycle.join(" -> ")),
+                suggestion: None,
+            });
+        }
+
+        // Translations are checked against the scenarios of all files
+        if !translations.translated.is_empty() {
+            for (id, file) in &translations.translatable {
+                if !translations.translated.contains_key(id) {
+                    report.diagnostics.push(Diagnostic {
+                        file: file.clone(),
+                        scenario: Some(id.clone()),
+                        code: DiagnosticCode::Untranslated,
+                        message: "no translation in the checked files".to_string(),
+                        suggestion: None,
+                    });
+                }
+            }
+            for (id, file) in &translations.translated {
+                if !translations.translatable.contains_key(id) {
+                    report.diagnostics.push(Diagnostic {
+                        file: file.clone(),
+                        scenario: Some(id.clone()),
+                        code: DiagnosticCode::UnknownTranslation,
+                        message: format!("translates unknown scenario '{id}'"),
+                        suggestion: None,
+                    });
+                }
+            }
+        }
+
+        report
+    }
+
+    /// Lint one file, recording the ids it defines in `seen_ids`, its
+    /// scenarios in `parsed` and the ids it defines and translates in
+    /// `translations`
+    fn lint_file(
+        &self,
+        path: &Path,
+        options: &LintOptions,
+        seen_ids: &mut BTreeMap<String, String>,
+        parsed: &mut Vec<Scenario>,
+        translations: &mut TranslationCoverage,
+        report: &mut LintReport,
+    ) {
+        let file = path.display().to_string();
+        let diagnostic = |scenario: Option<&str>, code, message: String| Diagnostic {
+            file: file.clone(),
+            scenario: scenario.map(str::to_string),
+            code,
+            message,
+            suggestion: None,
+        };
+
+        if path.file_name().and_then(|s| s.to_str()) == Some(PACK_MANIFEST) {
+            report.packs += 1;
+            if let Err(err) = self.load_manifest(path) {
+                report.diagnostics.push(diagnostic(
+                    None,
+                    DiagnosticCode::InvalidPack,
+                    err.to_string(),
+                ));
+            }
+            return;
+        }
+
+        let content = match self.read_scenario_file(path) {
+            Ok(content) => content,
+            Err(err) => {
+                report.diagnostics.push(diagnostic(
+                    None,
+                    DiagnosticCode::InvalidFile,
+                    err.to_string(),
+                ));
+                return;
+            }
+        };
+        let scenarios_file = match parse_file(&content) {
+            Ok(scenarios_file) => scenarios_file,
+            Err(err) => {
+                report.diagnostics.push(diagnostic(
+                    None,
+                    DiagnosticCode::InvalidFile,
+                    err.to_string(),
+                ));
+                return;
+            }
+        };
+
+        for (id, table) in misplaced_hints(&content) {
+            let first = if table.starts_with("steps.") {
+                "steps.target"
+            } else {
+                "setup"
+            };
+            report.diagnostics.push(diagnostic(
+                Some(&id),
+                DiagnosticCode::UnusedHints,
+                format!(
+                    "hints under [scenarios.{table}] are never shown; move them above [scenarios.{first}]"
+                ),
+            ));
+        }
+
+        for translation in &scenarios_file.translations {
+            if let Err(err) = translation::validate_translation(translation) {
+                report.diagnostics.push(diagnostic(
+                    Some(&translation.id),
+                    DiagnosticCode::InvalidScenario,
+                    err.to_string(),
+                ));
+            } else {
+                translations
+                    .translated
+                    .insert(translation.id.clone(), file.clone());
+            }
+        }
+
+        // Templates are checked through the instances they generate
+        let mut instances = Vec::new();
+        for scenario in scenarios_file.scenarios {
+            let id = scenario.id.clone();
+            translations
+                .translatable
+                .entry(id.clone())
+                .or_insert_with(|| file.clone());
+            match template::expand(scenario) {
+                Ok(expanded) => instances.extend(expanded),
+                Err(err) => {
+                    report.scenarios += 1;
+                    report.diagnostics.push(diagnostic(
+                        Some(&id),
+                        DiagnosticCode::InvalidScenario,
+                        err.to_string(),
+                    ));
+                }
+            }
+        }
+
+        for mut scenario in instances {
+            report.scenarios += 1;
+
+            if let Some(first_file) = seen_ids.get(&scenario.id) {
+                report.diagnostics.push(diagnostic(
+                    Some(&scenario.id),
+                    DiagnosticCode::DuplicateId,
+                    format!("id is already used in {first_file}"),
+                ));
+            } else {
+                seen_ids.insert(scenario.id.clone(), file.clone());
+            }
+
+            if let Err(err) = self.prepare_scenario(&mut scenario) {
+                let code = match err {
+                    SecurityError::SolutionMismatch { .. } => DiagnosticCode::SolutionMismatch,
+                    SecurityError::UnreachableTarget { .. } => DiagnosticCode::UnreachableTarget,
+                    _ => DiagnosticCode::InvalidScenario,
+                };
+                report
+                    .diagnostics
+                    .push(diagnostic(Some(&scenario.id), code, err.to_string()));
+            } else if let Some(budget) = &options.search
+                && let SearchOutcome::Found(keys) = find_shortest_solution(&scenario, budget)
+                && keys.len() < scenario.scoring.optimal_count
+            {
+                report.diagnostics.push(Diagnostic {
+                    suggestion: Some(keys.clone()),
+                    ..diagnostic(
+                        Some(&scenario.id),
+                        DiagnosticCode::ShorterSolution,
+                        format!(
+                            "optimal_count is {} but {:?} reaches the target in {} keys",
+                            scenario.scoring.optimal_count,
+                            keys,
+                            keys.len()
+                        ),
+                    )
+                });
+            }
+
+            let solution_len = scenario.solution.commands.len();
+            if scenario.scoring.optimal_count != solution_len {
+                report.diagnostics.push(diagnostic(
+                    Some(&scenario.id),
+                    DiagnosticCode::OptimalCount,
+                    format!(
+                        "optimal_count is {} but the solution has {} keys",
+                        scenario.scoring.optimal_count, solution_len
+                    ),
+                ));
+            }
+
+            parsed.push(scenario);
+        }
+    }
+}
+
+/// Sub-tables of a scenario or lesson step whose `hints` are dropped
+const HINTLESS_TABLES: [&str; 4] = ["setup", "target", "solution", "scoring"];
+
+/// Find `hints` keys nested in a scenario's sub-tables
+///
+/// Only the `hints` of a scenario and of its lesson steps are shown during
+/// training. Because TOML keys after a `[scenarios.solution]` header belong
+/// to that table, hints written below it are silently dropped; the same
+/// goes for the sub-tables of `[[scenarios.steps]]`.
+fn misplaced_hints(content: &str) -> Vec<(String, String)> {
+    let Ok(table) = content.parse::<toml::Table>() else {
+        return Vec::new();
+    };
+    let Some(scenarios) = table.get("scenarios").and_then(toml::Value::as_array) else {
+        return Vec::new();
+    };
+
+    let mut found = Vec::new();
+    for scenario in scenarios.iter().filter_map(toml::Value::as_table) {
+        let id = scenario
+            .get("id")
+            .and_then(toml::Value::as_str)
+            .unwrap_or_default();
+        let steps = scenario
+            .get("steps")
+            .and_then(toml::Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(toml::Value::as_table);
+        let tables = std::iter::once(("", scenario)).chain(steps.map(|step| ("steps.", step)));
+        for (prefix, table) in tables {
+            for key in HINTLESS_TABLES {
+                let nested = table
+                    .get(key)
+                    .and_then(toml::Value::as_table)
+                    .is_some_and(|table| table.contains_key("hints"));
+                if nested {
+                    found.push((id.to_string(), format!("{prefix}{key}")));
+                }
+            }
+        }
+    }
+    found
+}
+
+/// Recursively collect `.toml` files below a directory
+fn collect_toml_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for path in entries.flatten().map(|entry| entry.path()) {
+        if path.is_dir() {
+            collect_toml_files(&path, files);
+        } else if path.extension().and_then(|s| s.to_str()) == Some("toml") {
+            files.push(path);
+        }
+    }
+}
@@ -0,0 +1,207 @@
+//! Scenario packs
+//!
+//! A directory with a `pack.toml` manifest is a pack: every scenario file
+//! below it, down to the next manifest, belongs to that pack. Packs let
+//! teams ship curated, versioned sets of scenarios that the menu lists
+//! separately.
+//!
+//! # Examples
+//!
+//! ```toml
+//! schema_version = 1
+//! name = "Helix Basics"
+//! version = "1.2.0"
+//! author = "Helix Trainer"
+//! description = "Movement and editing for new users"
+//! order = ["move_word_001", "delete_char_001"]
+//! min_trainer_version = "0.1.0"
+//! locale = "en"
+//! ```
+
+use super::{Scenario, is_locale_code, is_valid_id};
+use crate::security::SecurityError;
+use crate::security::limits::*;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// File name of a pack manifest
+pub const PACK_MANIFEST: &str = "pack.toml";
+
+/// Scenario format version this trainer reads
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Contents of a `pack.toml` manifest
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct PackManifest {
+    /// Version of the scenario format the pack is written for
+    pub schema_version: u32,
+    pub name: String,
+    /// Pack version, as `major.minor.patch`
+    pub version: String,
+    #[serde(default)]
+    pub author: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Scenario ids in play order; unlisted scenarios follow in file order
+    #[serde(default)]
+    pub order: Vec<String>,
+    /// Oldest trainer version that can play the pack
+    #[serde(default)]
+    pub min_trainer_version: Option<String>,
+    /// Language of the pack's texts, as a 2-letter code
+    #[serde(default)]
+    pub locale: Option<String>,
+}
+
+/// Scenarios loaded from one pack
+#[derive(Debug, Clone)]
+pub struct ScenarioPack {
+    /// Manifest of the pack, or `None` for scenarios outside any pack
+    pub manifest: Option<PackManifest>,
+    pub scenarios: Vec<Scenario>,
+}
+
+impl ScenarioPack {
+    /// Title shown for the pack, e.g. `"Helix Basics 1.2.0"`
+    pub fn title(&self) -> Option<String> {
+        self.manifest
+            .as_ref()
+            .map(|manifest| format!("{} {}", manifest.name, manifest.version))
+    }
+}
+
+/// Parse `major.minor.patch`, ignoring any pre-release or build suffix
+fn parse_version(version: &str) -> Option<(u64, u64, u64)> {
+    let core = version.split(['-', '+']).next()?;
+    let mut parts = core.split('.').map(|part| part.parse::<u64>().ok());
+    match (parts.next(), parts.next(), parts.next(), parts.next()) {
+        (Some(Some(major)), Some(Some(minor)), Some(Some(patch)), None) => {
+            Some((major, minor, patch))
+        }
+        _ => None,
+    }
+}
+
+/// Check a parsed manifest
+///
+/// # Errors
+///
+/// Returns `SecurityError::IncompatiblePack` when the pack needs another
+/// schema version or a newer trainer, and `SecurityError::InvalidInput` for
+/// malformed fields.
+pub(super) fn validate_manifest(manifest: &PackManifest) -> Result<(), SecurityError> {
+    let incompatible = |reason: String| SecurityError::IncompatiblePack {
+        name: manifest.name.clone(),
+        reason,
+    };
+
+    if manifest.schema_version != SCHEMA_VERSION {
+        return Err(incompatible(format!(
+            "schema version {} is not supported (this trainer reads version {})",
+            manifest.schema_version, SCHEMA_VERSION
+        )));
+    }
+
+    if manifest.name.trim().is_empty() {
+        return Err(SecurityError::InvalidInput(
+            "Pack name must not be empty".to_string(),
+        ));
+    }
+    for text in [&manifest.name, &manifest.version]
+        .into_iter()
+        .chain(&manifest.author)
+        .chain(&manifest.description)
+    {
+        if text.len() > MAX_PACK_FIELD_LENGTH {
+            return Err(SecurityError::ContentTooLarge {
+                max: MAX_PACK_FIELD_LENGTH,
+                actual: text.len(),
+            });
+        }
+    }
+
+    if parse_version(&manifest.version).is_none() {
+        return Err(SecurityError::InvalidInput(format!(
+            "Invalid pack version '{}': use major.minor.patch",
+            manifest.version
+        )));
+    }
+
+    if let Some(required) = &manifest.min_trainer_version {
+        let required_version = parse_version(required).ok_or_else(|| {
+            SecurityError::InvalidInput(format!(
+                "Invalid min_trainer_version '{}': use major.minor.patch",
+                required
+            ))
+        })?;
+        let current = env!("CARGO_PKG_VERSION");
+        if parse_version(current).is_some_and(|current| current < required_version) {
+            return Err(incompatible(format!(
+                "requires trainer version {} or newer (this is {})",
+                required, current
+            )));
+        }
+    }
+
+    if let Some(locale) = &manifest.locale
+        && !is_locale_code(locale)
+    {
+        return Err(SecurityError::InvalidInput(format!(
+            "Invalid pack locale '{}': use a 2-letter code",
+            locale
+        )));
+    }
+
+    if manifest.order.len() > MAX_PACK_ORDER_LENGTH {
+        return Err(SecurityError::InvalidInput(format!(
+            "Pack order lists too many scenarios (max {})",
+            MAX_PACK_ORDER_LENGTH
+        )));
+    }
+    if let Some(id) = manifest.order.iter().find(|id| !is_valid_id(id)) {
+        return Err(SecurityError::InvalidInput(format!(
+            "Invalid scenario id '{}' in pack order",
+            id
+        )));
+    }
+
+    Ok(())
+}
+
+/// Sort a pack's scenarios by its manifest `order`
+///
+/// # Errors
+///
+/// Returns `SecurityError::InvalidInput` if `order` names a scenario that is
+/// not in the pack.
+pub(super) fn apply_order(pack: &mut ScenarioPack) -> Result<(), SecurityError> {
+    let Some(manifest) = &pack.manifest else {
+        return Ok(());
+    };
+
+    let rank: HashMap<&str, usize> = manifest
+        .order
+        .iter()
+        .enumerate()
+        .map(|(index, id)| (id.as_str(), index))
+        .collect();
+    if let Some(id) = manifest
+        .order
+        .iter()
+        .find(|id| !pack.scenarios.iter().any(|scenario| scenario.id == **id))
+    {
+        return Err(SecurityError::InvalidInput(format!(
+            "Pack '{}' orders unknown scenario '{}'",
+            manifest.name, id
+        )));
+    }
+
+    // Stable sort keeps unlisted scenarios in file order, after listed ones
+    pack.scenarios.sort_by_key(|scenario| {
+        rank.get(scenario.id.as_str())
+            .copied()
+            .unwrap_or(usize::MAX)
+    });
+    Ok(())
+}
@@ -0,0 +1,538 @@
+//! JSON Schema of the scenario file format
+//!
+//! Editors and authoring tools (taplo, web forms) use the schema to validate
+//! and complete scenario files. It describes a whole file, `scenarios` and
+//! `translations` arrays of tables, and mirrors what the loader accepts:
+//! tables that reject
+//! unknown fields when loading are closed in the schema as well, and the
+//! size limits come from `security::limits`. Checks that need the whole
+//! scenario, such as replaying the solution, are left to the loader.
+
+use crate::security::limits::*;
+use serde_json::{Value, json};
+
+/// URI of the JSON Schema dialect the schema is written in
+const DIALECT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+/// JSON Schema of a scenario file
+pub fn scenario_schema() -> Value {
+    json!({
+        "$schema": DIALECT,
+        "title": "Helix Trainer scenario file",
+        "type": "object",
+        "minProperties": 1,
+        "properties": {
+            "scenarios": {
+                "type": "array",
+                "maxItems": MAX_SCENARIOS_PER_FILE,
+                "items": { "$ref": "#/$defs/scenario" }
+            },
+            "translations": {
+                "description": "Texts of scenarios from other files in this file's locale",
+                "type": "array",
+                "maxItems": MAX_SCENARIOS_PER_FILE,
+                "items": { "$ref": "#/$defs/translation" }
+            }
+        },
+        "$defs": {
+            "scenario": scenario(),
+            "lessonStep": lesson_step(),
+            "setup": setup(),
+            "target": target(),
+            "completion": completion(),
+            "solution": solution(),
+            "alternative": alternative(),
+            "scoring": scoring(),
+            "template": template(),
+            "translation": translation(),
+            "selection": {
+                "description": "One selection range, keeping its direction",
+                "type": "object",
+                "required": ["anchor", "head"],
+                "additionalProperties": false,
+                "properties": {
+                    "anchor": position("Fixed end of the selection"),
+                    "head": position("Moving end of the selection, where the cursor is")
+                }
+            },
+            "commands": {
+                "type": "array",
+                "maxItems": MAX_COMMAND_SEQUENCE_LENGTH,
+                "items": { "type": "string" }
+            },
+            "content": {
+                "type": "string",
+                "maxLength": MAX_FILE_CONTENT_LENGTH
+            },
+            "hints": {
+                "type": "array",
+                "maxItems": MAX_HINTS,
+                "items": { "type": "string" }
+            },
+            "label": {
+                "type": "string",
+                "pattern": "^[a-z0-9_-]+$",
+                "maxLength": MAX_LABEL_LENGTH
+            },
+            "scenarioId": {
+                "type": "string",
+                "pattern": "^\\w*$",
+                "maxLength": 64
+            }
+        }
+    })
+}
+
+/// Array of strings naming commands, as used by the metadata and constraints
+fn command_names(description: &str) -> Value {
+    json!({
+        "description": description,
+        "type": "array",
+        "maxItems": MAX_METADATA_ENTRIES,
+        "items": { "type": "string", "minLength": 1, "maxLength": MAX_LABEL_LENGTH }
+    })
+}
+
+/// A `[line, column]` pair, both counted from 0
+fn position(description: &str) -> Value {
+    json!({
+        "description": description,
+        "type": "array",
+        "prefixItems": [
+            { "type": "integer", "minimum": 0 },
+            { "type": "integer", "minimum": 0 }
+        ],
+        "minItems": 2,
+        "maxItems": 2
+    })
+}
+
+fn scenario() -> Value {
+    json!({
+        "type": "object",
+        "required": ["id", "name", "description", "setup", "solution", "scoring"],
+        "additionalProperties": false,
+        "properties": {
+            "id": { "$ref": "#/$defs/scenarioId" },
+            "name": { "type": "string" },
+            "description": { "type": "string" },
+            "category": {
+                "description": "Topic the scenario belongs to, e.g. \"movement\"",
+                "$ref": "#/$defs/label"
+            },
+            "difficulty": {
+                "description": "Difficulty from 1 (first steps) to 5 (expert)",
+                "type": "integer",
+                "minimum": 1,
+                "maximum": MAX_DIFFICULTY
+            },
+            "tags": {
+                "description": "Free-form labels for filtering",
+                "type": "array",
+                "maxItems": MAX_METADATA_ENTRIES,
+                "items": { "$ref": "#/$defs/label" }
+            },
+            "requires": {
+                "description": "Ids of scenarios to complete before this one",
+                "type": "array",
+                "maxItems": MAX_METADATA_ENTRIES,
+                "items": { "$ref": "#/$defs/scenarioId", "minLength": 1 }
+            },
+            "commands_taught": {
+                "description": "Commands the scenario practices",
+                "type": "array",
+                "maxItems": MAX_METADATA_ENTRIES,
+                "items": { "type": "string" }
+            },
+            "forbidden_commands": command_names(
+                "Commands that may not be used; outside insert mode they are blocked and still cost a key"
+            ),
+            "required_commands": command_names(
+                "Commands that must be used at least once"
+            ),
+            "estimated_seconds": {
+                "description": "Expected time to solve, in seconds",
+                "type": "integer",
+                "minimum": 1,
+                "maximum": SESSION_TIMEOUT.as_secs()
+            },
+            "setup": { "$ref": "#/$defs/setup" },
+            "target": {
+                "description": "Required, except for templates: their target comes from replaying the solution",
+                "$ref": "#/$defs/target"
+            },
+            "solution": { "$ref": "#/$defs/solution" },
+            "alternatives": {
+                "type": "array",
+                "maxItems": MAX_ALTERNATIVES,
+                "items": { "$ref": "#/$defs/alternative" }
+            },
+            "hints": { "$ref": "#/$defs/hints" },
+            "scoring": { "$ref": "#/$defs/scoring" },
+            "markers": {
+                "description": "Setup and target file_content carry #[...|]# selection markers instead of cursor_position and selections",
+                "type": "boolean",
+                "default": false
+            },
+            "template": { "$ref": "#/$defs/template" },
+            "steps": {
+                "description": "Further steps that turn the scenario into a lesson",
+                "type": "array",
+                "maxItems": MAX_LESSON_STEPS,
+                "items": { "$ref": "#/$defs/lessonStep" }
+            }
+        },
+        "if": { "required": ["template"] },
+        "then": { "not": { "required": ["target"] } },
+        "else": { "required": ["target"] }
+    })
+}
+
+fn lesson_step() -> Value {
+    json!({
+        "description": "A lesson step after the first, played on the buffer the previous step left behind",
+        "type": "object",
+        "required": ["instructions", "target", "solution", "scoring"],
+        "additionalProperties": false,
+        "properties": {
+            "instructions": { "type": "string", "minLength": 1 },
+            "target": { "$ref": "#/$defs/target" },
+            "solution": { "$ref": "#/$defs/solution" },
+            "hints": { "$ref": "#/$defs/hints" },
+            "scoring": { "$ref": "#/$defs/scoring" }
+        }
+    })
+}
+
+fn setup() -> Value {
+    json!({
+        "description": "Initial editor setup",
+        "type": "object",
+        "required": ["file_content"],
+        "properties": {
+            "file_content": { "$ref": "#/$defs/content" },
+            "cursor_position": position("Cursor position: [line, col]; required without markers, left out with them"),
+            "selections": {
+                "description": "Initial selections; the primary head must be cursor_position",
+                "type": "array",
+                "maxItems": MAX_SELECTIONS,
+                "items": { "$ref": "#/$defs/selection" }
+            },
+            "primary_selection": { "type": "integer", "minimum": 0 },
+            "mode": {
+                "description": "Mode the editor starts in",
+                "enum": ["normal", "select", "insert"],
+                "default": "normal"
+            },
+            "registers": {
+                "description": "Pre-filled registers by their one-character name",
+                "type": "object",
+                "maxProperties": MAX_REGISTERS,
+                "propertyNames": { "minLength": 1, "maxLength": 1 },
+                "additionalProperties": {
+                    "type": "array",
+                    "maxItems": MAX_SELECTIONS,
+                    "items": { "type": "string" }
+                }
+            },
+            "last_action": {
+                "description": "Simulator commands whose action is already recorded for '.'",
+                "$ref": "#/$defs/commands"
+            },
+            "indent_unit": {
+                "description": "Indentation '>' adds and '<' removes; the configured unit applies when left out",
+                "type": "string",
+                "pattern": format!("^(\\t| {{1,{MAX_INDENT_WIDTH}}})$")
+            }
+        }
+    })
+}
+
+fn target() -> Value {
+    json!({
+        "description": "Target state to achieve; left out for templates",
+        "type": "object",
+        "required": ["file_content"],
+        "properties": {
+            "file_content": { "$ref": "#/$defs/content" },
+            "cursor_position": position("Cursor position: [line, col]; required without markers, left out with them"),
+            "selection": {
+                "description": "Selection range: [start_line, start_col, end_line, end_col]",
+                "type": "array",
+                "items": { "type": "integer", "minimum": 0 },
+                "minItems": 4,
+                "maxItems": 4
+            },
+            "selections": {
+                "description": "Target selections; take precedence over selection",
+                "type": "array",
+                "maxItems": MAX_SELECTIONS,
+                "items": { "$ref": "#/$defs/selection" }
+            },
+            "primary_selection": { "type": "integer", "minimum": 0 },
+            "match_selection": {
+                "description": "Require the selections to match, on top of the completion rule",
+                "type": "boolean",
+                "default": false
+            },
+            "completion": { "$ref": "#/$defs/completion" }
+        }
+    })
+}
+
+fn completion() -> Value {
+    json!({
+        "description": "Rule deciding when the target is reached; exact content and cursor by default",
+        "type": "object",
+        "required": ["rule"],
+        "oneOf": [
+            {
+                "properties": {
+                    "rule": { "enum": ["exact", "content", "cursor", "selection", "trimmed_content"] }
+                }
+            },
+            {
+                "description": "Content matches a regular expression",
+                "additionalProperties": false,
+                "required": ["pattern"],
+                "properties": {
+                    "rule": { "const": "regex" },
+                    "pattern": { "type": "string", "format": "regex" }
+                }
+            },
+            {
+                "description": "Line `line`, counted from 0, is exactly `text`",
+                "additionalProperties": false,
+                "required": ["line", "text"],
+                "properties": {
+                    "rule": { "const": "line" },
+                    "line": { "type": "integer", "minimum": 0 },
+                    "text": { "type": "string", "pattern": "^[^\\n]*$" }
+                }
+            }
+        ]
+    })
+}
+
+fn solution() -> Value {
+    json!({
+        "type": "object",
+        "required": ["commands", "description"],
+        "properties": {
+            "commands": { "$ref": "#/$defs/commands" },
+            "description": { "type": "string" }
+        }
+    })
+}
+
+fn alternative() -> Value {
+    json!({
+        "type": "object",
+        "required": ["commands", "points_multiplier", "description"],
+        "properties": {
+            "commands": { "$ref": "#/$defs/commands" },
+            "points_multiplier": { "type": "number" },
+            "description": { "type": "string" }
+        }
+    })
+}
+
+fn scoring() -> Value {
+    json!({
+        "type": "object",
+        "required": ["optimal_count", "max_points", "tolerance"],
+        "properties": {
+            "optimal_count": { "type": "integer", "minimum": 1 },
+            "max_points": { "type": "integer", "minimum": 0 },
+            "tolerance": { "type": "integer", "minimum": 0 }
+        }
+    })
+}
+
+fn template() -> Value {
+    json!({
+        "description": "Variables that turn the scenario into a template for generated instances",
+        "type": "object",
+        "required": ["variables"],
+        "additionalProperties": false,
+        "properties": {
+            "instances": {
+                "type": "integer",
+                "minimum": 1,
+                "maximum": MAX_TEMPLATE_INSTANCES,
+                "default": 1
+            },
+            "seed": { "type": "integer", "minimum": 0 },
+            "variables": {
+                "description": "Variables by the name used in {{name}} placeholders",
+                "type": "object",
+                "maxProperties": MAX_TEMPLATE_VARIABLES,
+                "additionalProperties": {
+                    "type": "object",
+                    "minProperties": 1,
+                    "maxProperties": 1,
+                    "properties": {
+                        "words": {
+                            "type": "array",
+                            "minItems": 1,
+                            "maxItems": MAX_TEMPLATE_WORDS,
+                            "items": { "type": "string" }
+                        },
+                        "range": {
+                            "type": "array",
+                            "items": { "type": "integer" },
+                            "minItems": 2,
+                            "maxItems": 2
+                        }
+                    },
+                    "additionalProperties": false
+                }
+            }
+        }
+    })
+}
+
+fn translation() -> Value {
+    json!({
+        "description": "Translated texts of a scenario, by its id",
+        "type": "object",
+        "required": ["id"],
+        "additionalProperties": false,
+        "properties": {
+            "id": { "$ref": "#/$defs/scenarioId", "minLength": 1 },
+            "name": { "type": "string" },
+            "description": { "type": "string" },
+            "hints": { "$ref": "#/$defs/hints" },
+            "solution": {
+                "type": "object",
+                "required": ["description"],
+                "additionalProperties": false,
+                "properties": {
+                    "description": { "type": "string" }
+                }
+            },
+            "steps": {
+                "description": "Texts of the lesson steps after the first, in order",
+                "type": "array",
+                "maxItems": MAX_LESSON_STEPS,
+                "items": {
+                    "type": "object",
+                    "additionalProperties": false,
+                    "properties": {
+                        "instructions": { "type": "string" },
+                        "hints": { "$ref": "#/$defs/hints" }
+                    }
+                }
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ScenarioLoader;
+
+    const LESSON: &str = r##"
+[[scenarios]]
+id = "lesson"
+name = "Lesson"
+description = "Delete, then move"
+category = "editing"
+difficulty = 2
+tags = ["lesson"]
+forbidden_commands = ["h"]
+required_commands = ["x"]
+estimated_seconds = 30
+markers = true
+
+[scenarios.setup]
+file_content = "#[|]#foo bar"
+registers = { '"' = ["baz "] }
+indent_unit = "    "
+
+[scenarios.target]
+file_content = "#[|]#oo bar"
+completion = { rule = "regex", pattern = "^oo" }
+
+[scenarios.solution]
+commands = ["x"]
+description = "Delete"
+
+[scenarios.scoring]
+optimal_count = 1
+max_points = 100
+tolerance = 1
+
+[[scenarios.steps]]
+instructions = "Move right"
+
+[scenarios.steps.target]
+file_content = "o#[|]#o bar"
+completion = { rule = "cursor" }
+
+[scenarios.steps.solution]
+commands = ["l"]
+description = "Move right"
+
+[scenarios.steps.scoring]
+optimal_count = 1
+max_points = 50
+tolerance = 0
+"##;
+
+    /// Keys of a serialized table that its schema definition does not list
+    fn unknown_keys(value: &Value, definition: &Value) -> Vec<String> {
+        let properties = definition["properties"].as_object().unwrap();
+        value
+            .as_object()
+            .unwrap()
+            .keys()
+            .filter(|key| !properties.contains_key(*key))
+            .cloned()
+            .collect()
+    }
+
+    #[test]
+    fn test_schema_lists_every_serialized_field() {
+        let schema = scenario_schema();
+        let definitions = &schema["$defs"];
+        let scenarios = ScenarioLoader::new().load_content(LESSON).unwrap();
+        let scenario = serde_json::to_value(&scenarios[0]).unwrap();
+        let step = &scenario["steps"][0];
+
+        for (value, definition) in [
+            (&scenario, "scenario"),
+            (&scenario["setup"], "setup"),
+            (&scenario["target"], "target"),
+            (&scenario["solution"], "solution"),
+            (&scenario["scoring"], "scoring"),
+            (step, "lessonStep"),
+            (&step["target"], "target"),
+        ] {
+            let unknown = unknown_keys(value, &definitions[definition]);
+            assert!(unknown.is_empty(), "{}: {:?}", definition, unknown);
+        }
+        assert_eq!(scenario["markers"], false);
+        assert_eq!(scenario["target"]["completion"]["rule"], "regex");
+        assert_eq!(scenario["target"]["completion"]["pattern"], "^oo");
+        assert_eq!(step["target"]["completion"]["rule"], "cursor");
+    }
+
+    #[test]
+    fn test_schema_uses_loader_limits() {
+        let schema = scenario_schema();
+        assert_eq!(schema["$schema"], DIALECT);
+        assert_eq!(
+            schema["properties"]["scenarios"]["maxItems"],
+            MAX_SCENARIOS_PER_FILE
+        );
+        let scenario = &schema["$defs"]["scenario"];
+        assert_eq!(scenario["additionalProperties"], false);
+        assert_eq!(
+            scenario["properties"]["difficulty"]["maximum"],
+            MAX_DIFFICULTY
+        );
+        assert_eq!(schema["$defs"]["hints"]["maxItems"], MAX_HINTS);
+    }
+}
@@ -0,0 +1,365 @@
+//! Scenario templates
+//!
+//! A scenario with a `[scenarios.template]` table is a template: its texts
+//! may use `{{name}}` placeholders for variables drawn from word lists or
+//! number ranges, and every load generates `instances` concrete scenarios
+//! with a seeded random generator. A template has no hand-written target:
+//! each instance's target is what its solution turns the generated setup
+//! into, found by replaying the solution through `HelixSimulator`.
+//!
+//! Placeholders are filled in the name, description, hints, setup content,
+//! registers and `last_action`, and in the solution and alternatives.
+//!
+//! # Examples
+//!
+//! ```toml
+//! [scenarios.template]
+//! instances = 3
+//! seed = 42
+//!
+//! [scenarios.template.variables]
+//! word = { words = ["alpha", "beta", "gamma"] }
+//! count = { range = [2, 9] }
+//! ```
+
+use super::{Scenario, TargetState, apply_setup_markers, is_valid_id};
+use crate::game::GameSession;
+use crate::game::generator::SeededRng;
+use crate::helix::Mode;
+use crate::helix::keys::push_key;
+use crate::security::SecurityError;
+use crate::security::limits::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Draws tried per instance before accepting values another instance has
+const MAX_REDRAWS: usize = 10;
+
+/// Variables and instance count of a scenario template
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct Template {
+    /// Number of scenarios to generate; when above 1 their ids get a
+    /// `_1`, `_2`, ... suffix
+    #[serde(default = "default_instances")]
+    pub instances: usize,
+    /// Fixed seed for the same instances on every load; a fresh seed is
+    /// used each load when left out
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+    /// Variables by the name used in `{{name}}` placeholders
+    pub variables: BTreeMap<String, Variable>,
+}
+
+fn default_instances() -> usize {
+    1
+}
+
+/// Values a template variable takes
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Variable {
+    /// One of the listed words, e.g. `{ words = ["foo", "bar"] }`
+    Words(Vec<String>),
+    /// An integer between both bounds, inclusive, e.g. `{ range = [1, 9] }`
+    Range([i64; 2]),
+}
+
+/// Turn a scenario into the scenarios it stands for
+///
+/// A scenario without a template is returned as is. Instances come back
+/// without a template and with their markers resolved, ready for the usual
+/// validation.
+///
+/// # Errors
+///
+/// Returns `SecurityError::InvalidInput` for a malformed template, an
+/// unknown placeholder, or a solution that cannot be replayed on a
+/// generated setup.
+pub(super) fn expand(scenario: Scenario) -> Result<Vec<Scenario>, SecurityError> {
+    let Some(template) = scenario.template.clone() else {
+        return Ok(vec![scenario]);
+    };
+    validate_template(&scenario.id, &template)?;
+    if !scenario.steps.is_empty() {
+        return Err(SecurityError::InvalidInput(format!(
+            "Template '{}': lessons cannot be templates",
+            scenario.id
+        )));
+    }
+    if scenario.target.is_some() {
+        return Err(SecurityError::InvalidInput(format!(
+            "Template '{}': the target comes from the solution, leave out [scenarios.target]",
+            scenario.id
+        )));
+    }
+
+    let mut rng = SeededRng::new(template.seed.unwrap_or_else(fresh_seed));
+    let mut seen = BTreeSet::new();
+    let mut instances = Vec::with_capacity(template.instances);
+    for number in 1..=template.instances {
+        // Prefer values no other instance got, as long as there are any left
+        let mut values = draw(&template, &mut rng);
+        for _ in 0..MAX_REDRAWS {
+            if !seen.contains(&values) {
+                break;
+            }
+            values = draw(&template, &mut rng);
+        }
+        seen.insert(values.clone());
+
+        let mut instance = instantiate(&scenario, &values)?;
+        if template.instances > 1 {
+            instance.id = format!("{}_{}", scenario.id, number);
+            if !is_valid_id(&instance.id) {
+                return Err(SecurityError::InvalidInput(format!(
+                    "Template id '{}' is too long for numbered instances",
+                    scenario.id
+                )));
+            }
+        }
+        instances.push(instance);
+    }
+    Ok(instances)
+}
+
+/// Check instance count, variable names and variable values
+fn validate_template(id: &str, template: &Template) -> Result<(), SecurityError> {
+    let invalid =
+        |reason: String| SecurityError::InvalidInput(format!("Template '{}': {}", id, reason));
+
+    if !(1..=MAX_TEMPLATE_INSTANCES).contains(&template.instances) {
+        return Err(invalid(format!(
+            "instances must be between 1 and {}",
+            MAX_TEMPLATE_INSTANCES
+        )));
+    }
+    if template.variables.len() > MAX_TEMPLATE_VARIABLES {
+        return Err(invalid(format!(
+            "too many variables (max {})",
+            MAX_TEMPLATE_VARIABLES
+        )));
+    }
+
+    for (name, variable) in &template.variables {
+        if name.is_empty() || !is_valid_id(name) {
+            return Err(invalid(format!("invalid variable name '{}'", name)));
+        }
+        match variable {
+            Variable::Words(words) if words.is_empty() || words.len() > MAX_TEMPLATE_WORDS => {
+                return Err(invalid(format!(
+                    "variable '{}' needs between 1 and {} words",
+                    name, MAX_TEMPLATE_WORDS
+                )));
+            }
+            Variable::Words(words) => {
+                let total: usize = words.iter().map(String::len).sum();
+                if total > MAX_FILE_CONTENT_LENGTH {
+                    return Err(SecurityError::ContentTooLarge {
+                        max: MAX_FILE_CONTENT_LENGTH,
+                        actual: total,
+                    });
+                }
+            }
+            Variable::Range([min, max]) if min > max => {
+                return Err(invalid(format!(
+                    "variable '{}' has range [{}, {}] with min above max",
+                    name, min, max
+                )));
+            }
+            Variable::Range(_) => {}
+        }
+    }
+    Ok(())
+}
+
+/// Seed from the clock, so every load brings new instances
+fn fresh_seed() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_nanos() as u64)
+}
+
+/// Pick a value for every variable
+fn draw(template: &Template, rng: &mut SeededRng) -> BTreeMap<String, String> {
+    template
+        .variables
+        .iter()
+        .map(|(name, variable)| {
+            let value = match variable {
+                Variable::Words(words) => words[rng.below(words.len() as u64) as usize].clone(),
+                Variable::Range([min, max]) => {
+                    let span = max.abs_diff(*min).saturating_add(1);
+                    min.wrapping_add_unsigned(rng.below(span)).to_string()
+                }
+            };
+            (name.clone(), value)
+        })
+        .collect()
+}
+
+/// Build one concrete scenario from the template and drawn values
+fn instantiate(
+    scenario: &Scenario,
+    values: &BTreeMap<String, String>,
+) -> Result<Scenario, SecurityError> {
+    let fill = |text: &mut String| -> Result<(), SecurityError> {
+        *text = substitute(&scenario.id, text, values)?;
+        Ok(())
+    };
+    let fill_all = |texts: &mut Vec<String>| texts.iter_mut().try_for_each(fill);
+
+    let mut instance = scenario.clone();
+    instance.template = None;
+    fill(&mut instance.name)?;
+    fill(&mut instance.description)?;
+    fill_all(&mut instance.hints)?;
+    fill(&mut instance.setup.file_content)?;
+    fill_all(&mut instance.setup.last_action)?;
+    for register in instance.setup.registers.values_mut() {
+        fill_all(register)?;
+    }
+    fill_all(&mut instance.solution.commands)?;
+    fill(&mut instance.solution.description)?;
+    for alternative in &mut instance.alternatives {
+        fill_all(&mut alternative.commands)?;
+        fill(&mut alternative.description)?;
+    }
+
+    if instance.markers {
+        apply_setup_markers(&mut instance.setup)?;
+        instance.markers = false;
+    }
+
+    replay_target(&mut instance)?;
+    Ok(instance)
+}
+
+/// Replace `{{name}}` placeholders with the drawn values
+///
+/// Fails with `ContentTooLarge` as soon as the text grows past the file
+/// content limit, so a long word repeated many times cannot blow up memory.
+fn substitute(
+    id: &str,
+    text: &str,
+    values: &BTreeMap<String, String>,
+) -> Result<String, SecurityError> {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let end = after.find("}}").ok_or_else(|| {
+            SecurityError::InvalidInput(format!("Template '{}': unclosed '{{{{'", id))
+        })?;
+        let name = after[..end].trim();
+        let value = values.get(name).ok_or_else(|| {
+            SecurityError::InvalidInput(format!("Template '{}': unknown variable '{}'", id, name))
+        })?;
+        out.push_str(value);
+        if out.len() > MAX_FILE_CONTENT_LENGTH {
+            return Err(SecurityError::ContentTooLarge {
+                max: MAX_FILE_CONTENT_LENGTH,
+                actual: out.len(),
+            });
+        }
+        rest = &after[end + 2..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+/// Set the target to the state the solution leaves the setup in
+///
+/// Keys are fed the way the UI feeds them to a session.
+fn replay_target(scenario: &mut Scenario) -> Result<(), SecurityError> {
+    let failed = |reason: String| {
+        SecurityError::InvalidInput(format!("Template '{}': {}", scenario.id, reason))
+    };
+
+    let (initial_state, mut simulator) = GameSession::initial_setup(scenario)?;
+    let mut buffer = String::new();
+    for key in &scenario.solution.commands {
+        let command = if simulator.mode() == Mode::Insert {
+            Some(key.clone())
+        } else {
+            push_key(&mut buffer, key)
+        };
+        if let Some(command) = command {
+            simulator
+                .execute_command(&command)
+                .map_err(|_| failed(format!("solution key '{}' failed", key)))?;
+        }
+    }
+    if !buffer.is_empty() {
+        return Err(failed(format!("'{}' is an incomplete command", buffer)));
+    }
+    let state = simulator
+        .to_editor_state()
+        .map_err(|_| failed("the solution leaves an invalid state".to_string()))?;
+    // A target equal to the setup would count as solved before any key
+    if state.matches(&initial_state) {
+        return Err(failed(
+            "the solution leaves the setup unchanged".to_string(),
+        ));
+    }
+
+    let cursor = state.cursor_position();
+    scenario.target = Some(TargetState {
+        file_content: state.content().to_string(),
+        cursor_position: Some((cursor.row, cursor.col)),
+        ..TargetState::default()
+    });
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_substitute_fills_placeholders() {
+        let values = BTreeMap::from([("word".to_string(), "foo".to_string())]);
+        assert_eq!(
+            substitute("t", "say {{word}} and {{ word }}!", &values).unwrap(),
+            "say foo and foo!"
+        );
+        assert!(substitute("t", "{{other}}", &values).is_err());
+        assert!(substitute("t", "{{word", &values).is_err());
+    }
+
+    #[test]
+    fn test_substitute_limits_output_size() {
+        let word = "x".repeat(MAX_FILE_CONTENT_LENGTH / 4);
+        let values = BTreeMap::from([("word".to_string(), word)]);
+        assert!(substitute("t", &"{{word}}".repeat(4), &values).is_ok());
+        assert!(matches!(
+            substitute("t", &"{{word}}".repeat(5), &values),
+            Err(SecurityError::ContentTooLarge { .. })
+        ));
+    }
+
+    #[test]
+    fn test_draw_is_seeded_and_in_range() {
+        let template = Template {
+            instances: 1,
+            seed: Some(7),
+            variables: BTreeMap::from([
+                ("n".to_string(), Variable::Range([-2, 2])),
+                (
+                    "w".to_string(),
+                    Variable::Words(vec!["a".to_string(), "b".to_string()]),
+                ),
+            ]),
+        };
+        let (mut first, mut second) = (SeededRng::new(7), SeededRng::new(7));
+        for _ in 0..50 {
+            let values = draw(&template, &mut first);
+            assert_eq!(values, draw(&template, &mut second));
+            let n: i64 = values["n"].parse().unwrap();
+            assert!((-2..=2).contains(&n));
+            assert!(["a", "b"].contains(&values["w"].as_str()));
+        }
+    }
+}
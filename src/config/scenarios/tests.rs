@@ -1,6 +1,8 @@
 //! Tests for scenario loading and validation
 
+use super::fixtures::{ScenarioToml, section, write_files};
 use super::*;
+use crate::game::SearchBudget;
 use std::io::Write;
 use tempfile::NamedTempFile;
 
@@ -13,18 +15,18 @@ description = "A test scenario"
 
 [scenarios.setup]
 file_content = "Hello, World!"
-cursor_position = [0, 0]
+cursor_position = [0, 7]
 
 [scenarios.target]
-file_content = "Hello, Rust!"
-cursor_position = [0, 7]
+file_content = "Hello, Rust World!"
+cursor_position = [0, 12]
 
 [scenarios.solution]
-commands = ["w", "cw", "Rust", "Esc"]
-description = "Change 'World' to 'Rust'"
+commands = ["i", "Rust ", "Escape"]
+description = "Insert 'Rust ' before 'World'"
 
 [scenarios.scoring]
-optimal_count = 4
+optimal_count = 3
 max_points = 100
 tolerance = 1
         "#
@@ -140,10 +142,10 @@ cursor_position = [0, 0]
 
 [scenarios.target]
 file_content = "test1"
-cursor_position = [0, 0]
+cursor_position = [0, 1]
 
 [scenarios.solution]
-commands = ["test1"]
+commands = ["l"]
 description = "test1"
 
 [scenarios.scoring]
@@ -162,10 +164,10 @@ cursor_position = [0, 0]
 
 [scenarios.target]
 file_content = "test2"
-cursor_position = [0, 0]
+cursor_position = [0, 1]
 
 [scenarios.solution]
-commands = ["test2"]
+commands = ["l"]
 description = "test2"
 
 [scenarios.scoring]
@@ -443,14 +445,14 @@ commands = ["d", "d"]
 description = "Delete line 2"
 
 [[scenarios.alternatives]]
-commands = ["j", "d", "d"]
+commands = ["k", "j", "d", "d"]
 points_multiplier = 0.9
-description = "Move down then delete"
+description = "Move away and back, then delete"
 
 [[scenarios.alternatives]]
-commands = ["ctrl-k"]
-points_multiplier = 0.95
-description = "Using Ctrl+K shortcut"
+commands = ["x", "x", "x", "x", "x", "x", "x"]
+points_multiplier = 0.5
+description = "Delete the line character by character"
 
 [scenarios.scoring]
 optimal_count = 2
@@ -509,3 +511,1439 @@ fn test_path_traversal_attack_rejected() {
         "Should reject path outside allowed directories"
     );
 }
+
+/// Default setup section for `load_with_target`
+const FOO_BAR_SETUP: &str = r#"file_content = "foo bar"
+cursor_position = [0, 0]"#;
+
+/// Scenario with the given setup and target sections, solved by `ve`
+fn selection_scenario(setup: &str, target: &str) -> ScenarioToml {
+    ScenarioToml::new("selection_test_001")
+        .setup(setup)
+        .target(target)
+        .solution(r#"["v", "e"]"#, 2)
+}
+
+/// Parse a single scenario with the given setup and target, without validating it
+fn parse_with_sections(setup: &str, target: &str) -> Scenario {
+    selection_scenario(setup, target).parse()
+}
+
+/// Parse a single scenario whose target section is `target`
+fn parse_with_target(target: &str) -> Scenario {
+    parse_with_sections(FOO_BAR_SETUP, target)
+}
+
+/// Load a single scenario whose target section is `target`
+fn load_with_target(target: &str) -> Result<Vec<Scenario>, UserError> {
+    load_with_sections(FOO_BAR_SETUP, target)
+}
+
+/// Load a single scenario with the given setup and target sections
+fn load_with_sections(setup: &str, target: &str) -> Result<Vec<Scenario>, UserError> {
+    load_toml(&selection_scenario(setup, target).build())
+}
+
+/// Load a single scenario file with the given content
+fn load_toml(toml: &str) -> Result<Vec<Scenario>, UserError> {
+    let (_dir, base, loader) = write_files(&[("a.toml", toml.to_string())]);
+    loader.load(&base.join("a.toml"))
+}
+
+#[test]
+fn test_target_selections_parsed() {
+    let scenario = parse_with_target(
+        r#"file_content = "foo bar"
+cursor_position = [0, 3]
+selections = [
+    { anchor = [0, 0], head = [0, 3] },
+    { anchor = [0, 7], head = [0, 4] },
+]
+primary_selection = 1
+match_selection = true"#,
+    );
+
+    let target = scenario.target.as_ref().unwrap();
+    assert_eq!(
+        target.selections,
+        vec![
+            SelectionRange {
+                anchor: (0, 0),
+                head: (0, 3)
+            },
+            SelectionRange {
+                anchor: (0, 7),
+                head: (0, 4)
+            },
+        ]
+    );
+    assert_eq!(target.primary_selection, 1);
+    assert!(target.match_selection);
+}
+
+#[test]
+fn test_target_selection_defaults() {
+    let scenario = parse_with_target(
+        r#"file_content = "foo bar"
+cursor_position = [0, 3]"#,
+    );
+
+    let target = scenario.target.as_ref().unwrap();
+    assert!(target.selections.is_empty());
+    assert_eq!(target.primary_selection, 0);
+    assert!(!target.match_selection);
+}
+
+#[test]
+fn test_target_primary_selection_out_of_range() {
+    let result = load_with_target(
+        r#"file_content = "foo bar"
+cursor_position = [0, 3]
+selections = [{ anchor = [0, 0], head = [0, 3] }]
+primary_selection = 1"#,
+    );
+    assert!(
+        result.is_err(),
+        "Should reject out-of-range primary selection"
+    );
+}
+
+#[test]
+fn test_target_selection_out_of_bounds() {
+    let result = load_with_target(
+        r#"file_content = "foo bar"
+cursor_position = [0, 3]
+selections = [{ anchor = [0, 0], head = [20000, 0] }]"#,
+    );
+    assert!(
+        result.is_err(),
+        "Should reject out-of-range selection position"
+    );
+}
+
+#[test]
+fn test_match_selection_without_selections() {
+    let result = load_with_target(
+        r#"file_content = "foo bar"
+cursor_position = [0, 3]
+match_selection = true"#,
+    );
+    assert!(
+        result.is_err(),
+        "Should reject match_selection without target selections"
+    );
+}
+
+#[test]
+fn test_setup_state_parsed() {
+    let scenario = parse_with_sections(
+        r#"file_content = "foo bar"
+cursor_position = [0, 7]
+selections = [
+    { anchor = [0, 0], head = [0, 3] },
+    { anchor = [0, 4], head = [0, 7] },
+]
+primary_selection = 1
+mode = "select"
+registers = { '"' = ["baz"], a = ["one", "two"] }
+last_action = ["dd"]"#,
+        r#"file_content = "foo bar"
+cursor_position = [0, 7]"#,
+    );
+
+    let setup = &scenario.setup;
+    assert_eq!(setup.selections.len(), 2);
+    assert_eq!(setup.primary_selection, 1);
+    assert_eq!(setup.mode, SetupMode::Select);
+    assert_eq!(setup.registers[&'"'], vec!["baz".to_string()]);
+    assert_eq!(setup.registers[&'a'].len(), 2);
+    assert_eq!(setup.last_action, vec!["dd".to_string()]);
+}
+
+#[test]
+fn test_setup_state_defaults() {
+    let scenario = parse_with_target(
+        r#"file_content = "foo bar"
+cursor_position = [0, 0]"#,
+    );
+
+    let setup = &scenario.setup;
+    assert!(setup.selections.is_empty());
+    assert_eq!(setup.mode, SetupMode::Normal);
+    assert!(setup.registers.is_empty());
+    assert!(setup.last_action.is_empty());
+}
+
+#[test]
+fn test_setup_cursor_must_be_primary_head() {
+    let result = load_with_sections(
+        r#"file_content = "foo bar"
+cursor_position = [0, 0]
+selections = [{ anchor = [0, 0], head = [0, 3] }]"#,
+        r#"file_content = "foo bar"
+cursor_position = [0, 0]"#,
+    );
+    assert!(
+        result.is_err(),
+        "Should reject a cursor that is not the primary head"
+    );
+}
+
+#[test]
+fn test_setup_indent_unit_checked() {
+    let setup = |unit: &str| format!("{FOO_BAR_SETUP}\nindent_unit = \"{unit}\"");
+    assert_eq!(
+        parse_with_sections(&setup("    "), FOO_BAR_SETUP)
+            .setup
+            .indent_unit
+            .as_deref(),
+        Some("    ")
+    );
+    for unit in ["", " \\t", "         "] {
+        assert!(
+            load_with_sections(&setup(unit), FOO_BAR_SETUP).is_err(),
+            "{:?}",
+            unit
+        );
+    }
+}
+
+#[test]
+fn test_setup_unknown_mode_rejected() {
+    let result = load_with_sections(
+        r#"file_content = "foo bar"
+cursor_position = [0, 0]
+mode = "visual""#,
+        r#"file_content = "foo bar"
+cursor_position = [0, 0]"#,
+    );
+    assert!(result.is_err(), "Should reject an unknown setup mode");
+}
+
+#[test]
+fn test_parse_markers_cursor() {
+    let marked = parse_markers("foo\nb#[|]#ar").unwrap();
+    assert_eq!(marked.content, "foo\nbar");
+    assert_eq!(marked.cursor_position(), (1, 1));
+    assert_eq!(
+        marked.selections,
+        vec![SelectionRange {
+            anchor: (1, 1),
+            head: (1, 1)
+        }]
+    );
+}
+
+#[test]
+fn test_parse_markers_direction() {
+    let forward = parse_markers("#[foo|]# bar").unwrap();
+    assert_eq!(forward.content, "foo bar");
+    assert_eq!(forward.selections[0].anchor, (0, 0));
+    assert_eq!(forward.selections[0].head, (0, 3));
+
+    let backward = parse_markers("#[|foo]# bar").unwrap();
+    assert_eq!(backward.selections[0].anchor, (0, 3));
+    assert_eq!(backward.selections[0].head, (0, 0));
+}
+
+#[test]
+fn test_parse_markers_multiple_selections() {
+    let marked = parse_markers("#(a|)#\n#[b|]#\n#(c|)#").unwrap();
+    assert_eq!(marked.content, "a\nb\nc");
+    assert_eq!(marked.selections.len(), 3);
+    assert_eq!(marked.primary_selection, 1);
+    assert_eq!(marked.cursor_position(), (1, 1));
+}
+
+#[test]
+fn test_parse_markers_keeps_plain_pipe() {
+    let marked = parse_markers("a | b #[|]#").unwrap();
+    assert_eq!(marked.content, "a | b ");
+    assert_eq!(marked.cursor_position(), (0, 6));
+}
+
+#[test]
+fn test_parse_markers_rejects_invalid_markers() {
+    for text in [
+        "#[foo|",
+        "foo|]#",
+        "#[foo]#",
+        "#[f|o|o]#",
+        "#[f|oo]#",
+        "#[foo|)#",
+        "#[#(a|)#|]#",
+        "no markers",
+        "#(a|)#",
+        "#[a|]# #[b|]#",
+    ] {
+        assert!(parse_markers(text).is_err(), "Should reject {:?}", text);
+    }
+}
+
+/// Scenario with `markers = true` and the given setup and target contents
+fn markers_scenario(id: &str, setup: &str, target: &str) -> ScenarioToml {
+    ScenarioToml::new(id)
+        .fields("markers = true")
+        .setup(&format!("file_content = {setup:?}"))
+        .target(&format!("file_content = {target:?}"))
+}
+
+#[test]
+fn test_load_scenario_with_markers() {
+    let toml = markers_scenario(
+        "markers_001",
+        "foo #(bar|)#\nfoo #[bar|]#",
+        "foo \nfoo #[|]#",
+    )
+    .solution(r#"["alt-d"]"#, 1)
+    .build();
+    let scenarios = load_toml(&toml).unwrap();
+
+    let scenario = &scenarios[0];
+    assert_eq!(scenario.setup.file_content, "foo bar\nfoo bar");
+    assert_eq!(scenario.setup.cursor_position, Some((1, 7)));
+    assert_eq!(scenario.setup.selections.len(), 2);
+    assert_eq!(scenario.setup.primary_selection, 1);
+    assert_eq!(scenario.target.as_ref().unwrap().file_content, "foo \nfoo ");
+    assert_eq!(
+        scenario.target.as_ref().unwrap().cursor_position,
+        Some((1, 4))
+    );
+}
+
+#[test]
+fn test_cursor_position_required_without_markers() {
+    let target = target_with_completion(r#"{ rule = "cursor" }"#);
+    assert!(load_with_target(&target).is_ok());
+
+    let no_cursor = target.replace("cursor_position = [0, 2]\n", "");
+    assert!(load_with_target(&no_cursor).is_err());
+    assert!(load_with_sections(r#"file_content = "foo bar""#, &target).is_err());
+}
+
+#[test]
+fn test_cursor_position_rejected_with_markers() {
+    let scenario = markers_scenario("markers_003", "#[f|]#oo", "#[o|]#o");
+    assert!(load_toml(&scenario.build()).is_ok());
+
+    // The markers already place the cursor
+    let with_cursor = scenario.setup("file_content = \"#[f|]#oo\"\ncursor_position = [0, 0]");
+    assert!(load_toml(&with_cursor.build()).is_err());
+}
+
+#[test]
+fn test_load_scenario_with_unbalanced_markers() {
+    let result = load_with_sections(
+        r#"file_content = "a #[b|"
+cursor_position = [0, 0]"#,
+        r#"file_content = "a #[b|"
+cursor_position = [0, 4]"#,
+    );
+    // Without `markers = true` the text is taken literally
+    assert!(result.is_ok());
+
+    let toml = markers_scenario("markers_002", "foo #[bar|", "#[foo|]# bar").build();
+    assert!(
+        load_toml(&toml).is_err(),
+        "Should reject unbalanced markers"
+    );
+}
+
+#[test]
+fn test_verify_reports_diverging_step() {
+    let mut scenario = parse_with_target(
+        r#"file_content = "oo bar"
+cursor_position = [0, 0]"#,
+    );
+
+    scenario.solution.commands = vec!["x".to_string()];
+    assert!(super::verify::verify_solutions(&scenario).is_ok());
+
+    scenario.solution.commands = vec!["l".to_string(), "x".to_string()];
+    match super::verify::verify_solutions(&scenario) {
+        Err(SecurityError::SolutionMismatch {
+            id, solution, step, ..
+        }) => {
+            assert_eq!(id, "selection_test_001");
+            assert_eq!(solution, "solution");
+            assert_eq!(step, 2);
+        }
+        other => panic!("Expected SolutionMismatch, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_verify_rejects_invalid_key_sequences() {
+    let mut scenario = parse_with_target(
+        r#"file_content = "oo bar"
+cursor_position = [0, 0]"#,
+    );
+
+    for (keys, expected_step) in [(vec!["[", "x"], 2), (vec!["l", "d"], 2)] {
+        scenario.solution.commands = keys.iter().map(|k| k.to_string()).collect();
+        match super::verify::verify_solutions(&scenario) {
+            Err(SecurityError::SolutionMismatch { step, reason, .. }) => {
+                assert_eq!(step, expected_step, "{}", reason);
+            }
+            other => panic!("Expected SolutionMismatch for {:?}, got {:?}", keys, other),
+        }
+    }
+}
+
+#[test]
+fn test_verify_checks_alternatives() {
+    let mut scenario = parse_with_target(
+        r#"file_content = "oo bar"
+cursor_position = [0, 0]"#,
+    );
+    scenario.solution.commands = vec!["x".to_string()];
+    scenario.alternatives = vec![AlternativeSolution {
+        commands: vec!["w".to_string(), "x".to_string()],
+        points_multiplier: 0.5,
+        description: "test".to_string(),
+    }];
+
+    match super::verify::verify_solutions(&scenario) {
+        Err(SecurityError::SolutionMismatch { solution, .. }) => {
+            assert_eq!(solution, "alternative 1");
+        }
+        other => panic!("Expected SolutionMismatch, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_loader_rejects_wrong_solution() {
+    // `ve` selects "foo" but leaves the content unchanged
+    let result = load_with_target(
+        r#"file_content = "oo bar"
+cursor_position = [0, 0]"#,
+    );
+    assert!(matches!(result, Err(UserError::ScenarioLoadError)));
+}
+
+/// Lint the given files, written to a fresh directory
+fn lint_files(files: &[(&str, String)]) -> LintReport {
+    let (_dir, base, loader) = write_files(files);
+    loader.lint(&[base], &LintOptions::default())
+}
+
+#[test]
+fn test_lint_clean_files() {
+    let report = lint_files(&[("a.toml", ScenarioToml::new("lint_001").build())]);
+    assert!(report.is_clean(), "{:?}", report.diagnostics);
+    assert_eq!(report.files, 1);
+    assert_eq!(report.scenarios, 1);
+}
+
+#[test]
+fn test_lint_reports_every_problem() {
+    let report = lint_files(&[
+        (
+            "a.toml",
+            ScenarioToml::new("lint_001")
+                .solution(r#"["x"]"#, 2)
+                .solution_fields(r#"hints = ["never shown"]"#)
+                .build(),
+        ),
+        ("b.toml", ScenarioToml::new("lint_001").build()),
+        ("c.toml", "not toml [".to_string()),
+    ]);
+
+    let codes: Vec<(String, DiagnosticCode)> = report
+        .diagnostics
+        .iter()
+        .map(|d| {
+            let file = Path::new(&d.file).file_name().unwrap().to_string_lossy();
+            (file.into_owned(), d.code)
+        })
+        .collect();
+    assert_eq!(
+        codes,
+        [
+            ("a.toml".to_string(), DiagnosticCode::UnusedHints),
+            ("a.toml".to_string(), DiagnosticCode::OptimalCount),
+            ("b.toml".to_string(), DiagnosticCode::DuplicateId),
+            ("c.toml".to_string(), DiagnosticCode::InvalidFile),
+        ]
+    );
+    assert_eq!(report.files, 3);
+    assert_eq!(report.scenarios, 2);
+}
+
+#[test]
+fn test_lint_classifies_load_errors() {
+    let wrong_solution = ScenarioToml::new("lint_001")
+        .solution(r#"["l"]"#, 1)
+        .build();
+    let unreachable = ScenarioToml::new("lint_002")
+        .target(&section("oo bar", [3, 0]))
+        .build();
+
+    let report = lint_files(&[("a.toml", wrong_solution), ("b.toml", unreachable)]);
+    let codes: Vec<DiagnosticCode> = report.diagnostics.iter().map(|d| d.code).collect();
+    assert_eq!(
+        codes,
+        [
+            DiagnosticCode::SolutionMismatch,
+            DiagnosticCode::UnreachableTarget
+        ]
+    );
+    assert_eq!(report.diagnostics[0].scenario.as_deref(), Some("lint_001"));
+}
+
+#[test]
+fn test_lint_report_json() {
+    let report = lint_files(&[("a.toml", "not toml [".to_string())]);
+    let json = serde_json::to_value(&report).unwrap();
+
+    assert_eq!(json["files"], 1);
+    assert_eq!(json["diagnostics"][0]["code"], "invalid-file");
+    // Diagnostics without a scenario leave the field out
+    assert!(json["diagnostics"][0].get("scenario").is_none());
+}
+
+#[test]
+fn test_missing_optimal_count_is_rejected() {
+    let toml = ScenarioToml::new("lint_001")
+        .build()
+        .replace("optimal_count = 1\n", "");
+    assert!(load_toml(&toml).is_err());
+}
+
+#[test]
+fn test_lint_search_suggests_shorter_solution() {
+    let (dir, base, loader) = write_files(&[("a.toml", ScenarioToml::new("lint_001").build())]);
+    let options = LintOptions {
+        search: Some(SearchBudget::default()),
+    };
+
+    // Already optimal: searching finds nothing to report
+    assert!(
+        loader
+            .lint(std::slice::from_ref(&base), &options)
+            .is_clean()
+    );
+
+    // `optimal_count` claims more keys than the search needs
+    let toml = ScenarioToml::new("lint_001")
+        .solution(r#"["l", "h", "x"]"#, 2)
+        .build();
+    std::fs::write(dir.path().join("a.toml"), toml).unwrap();
+    let report = loader.lint(&[base], &options);
+    let shorter = report
+        .diagnostics
+        .iter()
+        .find(|d| d.code == DiagnosticCode::ShorterSolution)
+        .expect("shorter-solution diagnostic");
+    assert_eq!(shorter.suggestion, Some(vec!["x".to_string()]));
+}
+
+/// Scenario TOML solved by `x` with metadata lines after the description
+fn metadata_toml(id: &str, metadata: &str) -> String {
+    ScenarioToml::new(id).fields(metadata).build()
+}
+
+#[test]
+fn test_scenario_metadata_parsed() {
+    let toml = metadata_toml(
+        "meta_002",
+        r#"category = "basic-editing"
+difficulty = 2
+tags = ["delete", "char"]
+requires = ["meta_001"]
+commands_taught = ["x"]
+estimated_seconds = 30"#,
+    );
+    let scenario = load_toml(&toml).unwrap().remove(0);
+
+    assert_eq!(scenario.category.as_deref(), Some("basic-editing"));
+    assert_eq!(scenario.difficulty, Some(2));
+    assert_eq!(scenario.tags, ["delete", "char"]);
+    assert_eq!(scenario.requires, ["meta_001"]);
+    assert_eq!(scenario.commands_taught, ["x"]);
+    assert_eq!(scenario.estimated_seconds, Some(30));
+}
+
+#[test]
+fn test_scenario_metadata_defaults() {
+    let scenario = load_toml(&metadata_toml("meta_001", "")).unwrap().remove(0);
+
+    assert_eq!(scenario.category, None);
+    assert_eq!(scenario.difficulty, None);
+    assert!(scenario.tags.is_empty());
+    assert!(scenario.requires.is_empty());
+    assert!(scenario.commands_taught.is_empty());
+    assert_eq!(scenario.estimated_seconds, None);
+}
+
+#[test]
+fn test_invalid_metadata_rejected() {
+    for metadata in [
+        "difficulty = 0",
+        "difficulty = 6",
+        r#"category = "Basic Editing""#,
+        r#"tags = [""]"#,
+        r#"requires = ["meta_001"]"#,
+        r#"requires = ["not-an-id"]"#,
+        r#"commands_taught = [""]"#,
+        "estimated_seconds = 0",
+    ] {
+        let result = load_toml(&metadata_toml("meta_001", metadata));
+        assert!(result.is_err(), "{metadata} should be rejected");
+    }
+
+    // Fields outside the format are still rejected
+    let result = load_toml(&metadata_toml("meta_001", "level = 3"));
+    assert!(matches!(result, Err(UserError::ScenarioLoadError)));
+}
+
+#[test]
+fn test_load_directory_checks_requirements() {
+    let (dir, base, loader) = write_files(&[(
+        "a.toml",
+        metadata_toml("meta_002", r#"requires = ["meta_001"]"#),
+    )]);
+
+    assert!(matches!(
+        loader.load_directory(&base),
+        Err(UserError::ScenarioLoadError)
+    ));
+
+    std::fs::write(dir.path().join("b.toml"), metadata_toml("meta_001", "")).unwrap();
+    assert_eq!(loader.load_directory(&base).unwrap().len(), 2);
+}
+
+#[test]
+fn test_requirements_order_scenarios_and_reject_cycles() {
+    // Files load in name order, which puts the dependent scenario first
+    let (dir, base, loader) = write_files(&[
+        (
+            "a.toml",
+            metadata_toml("meta_002", r#"requires = ["meta_001"]"#),
+        ),
+        ("b.toml", metadata_toml("meta_001", "")),
+    ]);
+
+    let ids: Vec<String> = loader
+        .load_directory(&base)
+        .unwrap()
+        .into_iter()
+        .map(|s| s.id)
+        .collect();
+    assert_eq!(ids, ["meta_001", "meta_002"]);
+
+    std::fs::write(
+        dir.path().join("b.toml"),
+        metadata_toml("meta_001", r#"requires = ["meta_002"]"#),
+    )
+    .unwrap();
+    assert!(matches!(
+        loader.load_directory(&base),
+        Err(UserError::ScenarioLoadError)
+    ));
+
+    let report = loader.lint(&[base], &LintOptions::default());
+    let codes: Vec<DiagnosticCode> = report.diagnostics.iter().map(|d| d.code).collect();
+    assert_eq!(codes, [DiagnosticCode::RequirementCycle]);
+    assert!(
+        report.diagnostics[0]
+            .message
+            .contains("meta_002 -> meta_001 -> meta_002")
+    );
+}
+
+#[test]
+fn test_lint_reports_unknown_requirement() {
+    let report = lint_files(&[
+        ("a.toml", metadata_toml("meta_001", "")),
+        (
+            "b.toml",
+            metadata_toml("meta_002", r#"requires = ["meta_001", "meta_009"]"#),
+        ),
+    ]);
+
+    assert_eq!(report.diagnostics.len(), 1, "{:?}", report.diagnostics);
+    let diagnostic = &report.diagnostics[0];
+    assert_eq!(diagnostic.code, DiagnosticCode::UnknownRequirement);
+    assert_eq!(diagnostic.scenario.as_deref(), Some("meta_002"));
+    assert!(diagnostic.file.ends_with("b.toml"));
+}
+
+/// Pack manifest with the given extra lines
+fn pack_toml(extra: &str) -> String {
+    format!("schema_version = 1\nname = \"Test Pack\"\nversion = \"1.0.0\"\n{extra}\n")
+}
+
+/// Write files (paths relative to a fresh directory) and load it as packs
+fn load_pack_files(files: &[(&str, String)]) -> Result<Vec<ScenarioPack>, UserError> {
+    let (_dir, base, loader) = write_files(files);
+    loader.load_packs(&base)
+}
+
+#[test]
+fn test_load_packs_groups_by_manifest() {
+    let packs = load_pack_files(&[
+        ("loose.toml", metadata_toml("loose_001", "")),
+        ("basics/pack.toml", pack_toml(r#"order = ["pack_002"]"#)),
+        ("basics/a.toml", metadata_toml("pack_001", "")),
+        ("basics/more/b.toml", metadata_toml("pack_002", "")),
+    ])
+    .unwrap();
+
+    assert_eq!(packs.len(), 2);
+    assert!(packs[0].manifest.is_none());
+    assert_eq!(packs[0].scenarios[0].id, "loose_001");
+
+    assert_eq!(packs[1].title().as_deref(), Some("Test Pack 1.0.0"));
+    let ids: Vec<&str> = packs[1].scenarios.iter().map(|s| s.id.as_str()).collect();
+    assert_eq!(ids, ["pack_002", "pack_001"]);
+}
+
+#[test]
+fn test_load_packs_rejects_incompatible_packs() {
+    for manifest in [
+        pack_toml("").replace("schema_version = 1", "schema_version = 2"),
+        pack_toml(r#"min_trainer_version = "99.0.0""#),
+    ] {
+        let result = load_pack_files(&[
+            ("pack.toml", manifest),
+            ("a.toml", metadata_toml("pack_001", "")),
+        ]);
+        assert!(
+            matches!(result, Err(UserError::IncompatiblePack)),
+            "{:?}",
+            result
+        );
+    }
+}
+
+#[test]
+fn test_load_packs_rejects_invalid_manifests() {
+    for manifest in [
+        pack_toml(r#"order = ["missing_001"]"#),
+        pack_toml(r#"locale = "english""#),
+        pack_toml("").replace("1.0.0", "one"),
+        pack_toml("").replace("Test Pack", ""),
+        pack_toml("homepage = \"x\""),
+    ] {
+        let result = load_pack_files(&[
+            ("pack.toml", manifest.clone()),
+            ("a.toml", metadata_toml("pack_001", "")),
+        ]);
+        assert!(result.is_err(), "{manifest} should be rejected");
+    }
+}
+
+#[test]
+fn test_lint_checks_pack_manifests() {
+    let report = lint_files(&[
+        (
+            "pack.toml",
+            pack_toml("").replace("schema_version = 1", "schema_version = 2"),
+        ),
+        ("a.toml", metadata_toml("pack_001", "")),
+    ]);
+
+    assert_eq!(report.packs, 1);
+    assert_eq!(report.scenarios, 1);
+    assert_eq!(report.diagnostics.len(), 1, "{:?}", report.diagnostics);
+    assert_eq!(report.diagnostics[0].code, DiagnosticCode::InvalidPack);
+    assert!(report.diagnostics[0].message.contains("schema version 2"));
+}
+
+#[test]
+fn test_allow_directory_adds_canonical_path() {
+    let (dir, _, _) = write_files(&[("a.toml", metadata_toml("user_001", ""))]);
+
+    let mut loader = ScenarioLoader::with_allowed_paths(Vec::new());
+    assert!(loader.load_directory(dir.path()).is_err());
+
+    let canonical = loader.allow_directory(dir.path()).unwrap();
+    assert_eq!(canonical, dir.path().canonicalize().unwrap());
+    assert_eq!(loader.load_directory(dir.path()).unwrap().len(), 1);
+
+    // Files are not directories
+    assert!(loader.allow_directory(&dir.path().join("a.toml")).is_err());
+    assert!(loader.allow_directory(&dir.path().join("missing")).is_err());
+}
+
+#[test]
+fn test_load_sources_merges_directories() {
+    let (builtin, _, _) = write_files(&[("a.toml", metadata_toml("base_001", ""))]);
+    let (user, _, _) = write_files(&[(
+        "b.toml",
+        metadata_toml("user_001", r#"requires = ["base_001"]"#),
+    )]);
+
+    let mut loader = ScenarioLoader::with_allowed_paths(Vec::new());
+    let sources = [
+        ScenarioSource::Directory(loader.allow_directory(builtin.path()).unwrap()),
+        ScenarioSource::Directory(loader.allow_directory(user.path()).unwrap()),
+    ];
+
+    // Requirements resolve across directories
+    let packs = loader.load_sources(&sources).unwrap();
+    let ids: Vec<&str> = packs
+        .iter()
+        .flat_map(|pack| &pack.scenarios)
+        .map(|s| s.id.as_str())
+        .collect();
+    assert_eq!(ids, ["base_001", "user_001"]);
+
+    // Alone, the user directory misses the required scenario
+    assert!(loader.load_sources(&sources[1..]).is_err());
+}
+
+#[test]
+fn test_base_directory_skips_locale_subdirectories() {
+    let (dir, _, _) = write_files(&[
+        ("a.toml", metadata_toml("base_001", "")),
+        ("ru/a.toml", metadata_toml("ru_001", "")),
+        ("basics/b.toml", metadata_toml("base_002", "")),
+    ]);
+
+    let mut loader = ScenarioLoader::with_allowed_paths(Vec::new());
+    let canonical = loader.allow_directory(dir.path()).unwrap();
+    let ids = |source: ScenarioSource| -> Vec<String> {
+        loader.load_sources(&[source]).unwrap()[0]
+            .scenarios
+            .iter()
+            .map(|s| s.id.clone())
+            .collect()
+    };
+    assert_eq!(
+        ids(ScenarioSource::BaseDirectory(canonical.clone())),
+        ["base_001", "base_002"]
+    );
+    assert_eq!(
+        ids(ScenarioSource::Directory(canonical)),
+        ["base_001", "base_002", "ru_001"]
+    );
+}
+
+#[test]
+fn test_available_locales_from_scenario_dirs() {
+    let dir = tempfile::tempdir().unwrap();
+    for subdir in ["de", "basics"] {
+        std::fs::create_dir(dir.path().join(subdir)).unwrap();
+    }
+
+    let locales =
+        ScenarioLoader::available_locales(&[dir.path().to_path_buf(), dir.path().join("missing")]);
+    assert!(locales.contains(&"de".to_string()));
+    assert!(locales.contains(&"en".to_string()));
+    assert!(!locales.contains(&"basics".to_string()));
+}
+
+#[test]
+fn test_load_embedded_scenarios() {
+    let files = crate::config::embedded::embedded_locale_files("en");
+    let loader = ScenarioLoader::with_allowed_paths(Vec::new());
+    let packs = loader
+        .load_sources(&[ScenarioSource::Memory(files)])
+        .unwrap();
+
+    assert_eq!(packs.len(), 1);
+    assert_eq!(
+        packs[0].manifest.as_ref().map(|m| m.name.as_str()),
+        Some("Helix Essentials")
+    );
+    assert!(!packs[0].scenarios.is_empty());
+}
+
+#[test]
+fn test_load_memory_packs_like_directories() {
+    let files = vec![
+        ("loose.toml".to_string(), metadata_toml("loose_001", "")),
+        (
+            "basics/pack.toml".to_string(),
+            pack_toml(r#"order = ["pack_002"]"#),
+        ),
+        ("basics/a.toml".to_string(), metadata_toml("pack_001", "")),
+        (
+            "basics/more/b.toml".to_string(),
+            metadata_toml("pack_002", ""),
+        ),
+        ("notes.txt".to_string(), "not a scenario".to_string()),
+    ];
+    let loader = ScenarioLoader::with_allowed_paths(Vec::new());
+    let packs = loader
+        .load_sources(&[ScenarioSource::Memory(files)])
+        .unwrap();
+
+    assert_eq!(packs.len(), 2);
+    assert!(packs[0].manifest.is_none());
+    let ids: Vec<&str> = packs[1].scenarios.iter().map(|s| s.id.as_str()).collect();
+    assert_eq!(ids, ["pack_002", "pack_001"]);
+
+    // Memory files get the same checks as files on disk
+    let invalid = vec![(
+        "a.toml".to_string(),
+        metadata_toml("meta_001", "difficulty = 9"),
+    )];
+    assert!(
+        loader
+            .load_sources(&[ScenarioSource::Memory(invalid)])
+            .is_err()
+    );
+}
+
+#[test]
+fn test_later_sources_override_by_id() {
+    let builtin = vec![
+        ("a.toml".to_string(), metadata_toml("base_001", "")),
+        ("b.toml".to_string(), metadata_toml("base_002", "")),
+    ];
+    let (user, _, _) = write_files(&[("a.toml", metadata_toml("base_001", "difficulty = 3"))]);
+
+    let mut loader = ScenarioLoader::with_allowed_paths(Vec::new());
+    let user_dir = loader.allow_directory(user.path()).unwrap();
+    let packs = loader
+        .load_sources(&[
+            ScenarioSource::Memory(builtin),
+            ScenarioSource::Directory(user_dir),
+        ])
+        .unwrap();
+
+    // The override takes the built-in scenario's place
+    assert_eq!(packs.len(), 1);
+    let scenarios = &packs[0].scenarios;
+    assert_eq!(scenarios.len(), 2);
+    assert_eq!(scenarios[0].id, "base_001");
+    assert_eq!(scenarios[0].difficulty, Some(3));
+    assert_eq!(scenarios[1].id, "base_002");
+}
+
+/// Template deleting the first line, leaving the number
+fn template_scenario(template: &str) -> ScenarioToml {
+    ScenarioToml::new("template_001")
+        .name("Delete {{word}}")
+        .setup(&section("{{word}}\n{{n}}", [0, 0]))
+        .without_target()
+        .solution(r#"["d", "d"]"#, 2)
+        .sections(template)
+}
+
+/// Template TOML deleting the first line, leaving the number
+fn template_toml(template: &str) -> String {
+    template_scenario(template).build()
+}
+
+const WORDS_AND_RANGE: &str = r#"[scenarios.template]
+instances = 3
+seed = 42
+
+[scenarios.template.variables]
+word = { words = ["alpha", "beta", "gamma"] }
+n = { range = [10, 99] }"#;
+
+#[test]
+fn test_template_generates_instances() {
+    let scenarios = load_toml(&template_toml(WORDS_AND_RANGE)).unwrap();
+
+    let ids: Vec<&str> = scenarios.iter().map(|s| s.id.as_str()).collect();
+    assert_eq!(ids, ["template_001_1", "template_001_2", "template_001_3"]);
+    for scenario in &scenarios {
+        assert!(scenario.template.is_none());
+        let (word, n) = scenario.setup.file_content.split_once('\n').unwrap();
+        assert!(["alpha", "beta", "gamma"].contains(&word));
+        assert!((10..=99).contains(&n.parse::<i64>().unwrap()));
+        assert_eq!(scenario.name, format!("Delete {word}"));
+        // The target is what the solution leaves behind
+        assert_eq!(scenario.target.as_ref().unwrap().file_content, n);
+        assert_eq!(
+            scenario.target.as_ref().unwrap().cursor_position,
+            Some((0, 0))
+        );
+    }
+
+    // Instances differ from each other, and a fixed seed repeats them
+    let setups: BTreeSet<&str> = scenarios
+        .iter()
+        .map(|s| s.setup.file_content.as_str())
+        .collect();
+    assert_eq!(setups.len(), 3);
+    let again = load_toml(&template_toml(WORDS_AND_RANGE)).unwrap();
+    assert_eq!(again[0].setup.file_content, scenarios[0].setup.file_content);
+}
+
+#[test]
+fn test_single_instance_template_keeps_id() {
+    let template = "[scenarios.template]\nvariables = { word = { words = [\"foo\"] }, n = { range = [5, 5] } }";
+    let scenarios = load_toml(&template_toml(template)).unwrap();
+
+    assert_eq!(scenarios.len(), 1);
+    assert_eq!(scenarios[0].id, "template_001");
+    assert_eq!(scenarios[0].setup.file_content, "foo\n5");
+    assert_eq!(scenarios[0].target.as_ref().unwrap().file_content, "5");
+}
+
+#[test]
+fn test_invalid_templates_rejected() {
+    for template in [
+        // Placeholder without a variable
+        "[scenarios.template]\nvariables = { word = { words = [\"foo\"] } }",
+        "[scenarios.template]\nvariables = { word = { words = [] }, n = { range = [1, 2] } }",
+        "[scenarios.template]\nvariables = { word = { words = [\"foo\"] }, n = { range = [2, 1] } }",
+        "[scenarios.template]\ninstances = 0\nvariables = { word = { words = [\"foo\"] }, n = { range = [1, 2] } }",
+        "[scenarios.template]\nvariables = { word = { list = [\"foo\"] }, n = { range = [1, 2] } }",
+    ] {
+        assert!(load_toml(&template_toml(template)).is_err(), "{template}");
+    }
+
+    // A solution that leaves the setup as it is would solve itself
+    let unchanged = template_scenario(WORDS_AND_RANGE).solution(r#"["h"]"#, 2);
+    assert!(load_toml(&unchanged.build()).is_err());
+
+    // The target comes from the solution, so a template may not give one
+    let with_target = template_scenario(WORDS_AND_RANGE).target(&section("5", [0, 0]));
+    assert!(load_toml(&with_target.build()).is_err());
+}
+
+#[test]
+fn test_missing_target_rejected() {
+    let toml = ScenarioToml::new("test_001").without_target().build();
+    assert!(!toml.contains("[scenarios.target]"));
+    assert!(load_toml(&toml).is_err());
+}
+
+#[test]
+fn test_lint_checks_template_instances() {
+    let report = lint_files(&[("a.toml", template_toml(WORDS_AND_RANGE))]);
+    assert!(report.is_clean(), "{:?}", report.diagnostics);
+    assert_eq!(report.scenarios, 3);
+
+    let broken = template_toml(WORDS_AND_RANGE).replace("{{n}}", "{{m}}");
+    let report = lint_files(&[("a.toml", broken)]);
+    let codes: Vec<DiagnosticCode> = report.diagnostics.iter().map(|d| d.code).collect();
+    assert_eq!(codes, [DiagnosticCode::InvalidScenario]);
+    assert_eq!(
+        report.diagnostics[0].scenario.as_deref(),
+        Some("template_001")
+    );
+}
+
+/// Lesson deleting the "f" of "foo bar", then moving down with `second_step_keys`
+fn lesson_toml(second_step_keys: &str) -> String {
+    let step = format!(
+        r#"[[scenarios.steps]]
+instructions = "Move to the next line"
+hints = ["Press j"]
+
+[scenarios.steps.target]
+file_content = "oo bar\nbaz"
+cursor_position = [1, 0]
+
+[scenarios.steps.solution]
+commands = {second_step_keys}
+description = "Move down"
+
+[scenarios.steps.scoring]
+optimal_count = 1
+max_points = 50
+tolerance = 0"#
+    );
+    ScenarioToml::new("lesson_001")
+        .setup(&section("foo bar\nbaz", [0, 0]))
+        .target(&section("oo bar\nbaz", [0, 0]))
+        .sections(&step)
+        .build()
+}
+
+#[test]
+fn test_lesson_steps_parsed() {
+    let scenarios = load_toml(&lesson_toml(r#"["j"]"#)).unwrap();
+    let steps = &scenarios[0].steps;
+    assert_eq!(steps.len(), 1);
+    assert_eq!(steps[0].instructions, "Move to the next line");
+    assert_eq!(steps[0].target.cursor_position, Some((1, 0)));
+    assert_eq!(steps[0].hints, ["Press j"]);
+    assert_eq!(steps[0].scoring.max_points, 50);
+}
+
+#[test]
+fn test_verify_replays_lesson_steps_in_order() {
+    let toml = lesson_toml(r#"["k"]"#);
+    let file: ScenariosFile = toml::from_str(&toml).unwrap();
+    match super::verify::verify_solutions(&file.scenarios[0]) {
+        Err(SecurityError::SolutionMismatch { solution, step, .. }) => {
+            assert_eq!(solution, "step 2 solution");
+            assert_eq!(step, 1);
+        }
+        other => panic!("Expected SolutionMismatch, got {:?}", other),
+    }
+    assert!(load_toml(&toml).is_err());
+}
+
+#[test]
+fn test_invalid_lesson_steps_rejected() {
+    let valid = lesson_toml(r#"["j"]"#);
+    for invalid in [
+        valid.replace("Move to the next line", " "),
+        valid.replace(
+            "optimal_count = 1\nmax_points = 50",
+            "optimal_count = 0\nmax_points = 50",
+        ),
+        valid.replace("cursor_position = [1, 0]", "cursor_position = [5, 0]"),
+        format!("{}\n[scenarios.template.variables]\n", valid),
+    ] {
+        assert!(load_toml(&invalid).is_err(), "{}", invalid);
+    }
+}
+
+#[test]
+fn test_lesson_step_solved_at_start_rejected() {
+    // The second step wants the cursor where the first step leaves it
+    let toml =
+        lesson_toml(r#"["j"]"#).replace("cursor_position = [1, 0]", "cursor_position = [0, 0]");
+    let file: ScenariosFile = toml::from_str(&toml).unwrap();
+    match super::verify::verify_solutions(&file.scenarios[0]) {
+        Err(SecurityError::InvalidInput(message)) => {
+            assert!(message.contains("step 2 is already solved"), "{}", message);
+        }
+        other => panic!("Expected InvalidInput, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_lint_accepts_lesson_step_hints() {
+    let report = lint_files(&[("a.toml", lesson_toml(r#"["j"]"#))]);
+    assert!(report.is_clean(), "{:?}", report.diagnostics);
+}
+
+#[test]
+fn test_lint_flags_hints_in_step_tables() {
+    let toml = lesson_toml(r#"["j"]"#).replace(
+        "description = \"Move down\"",
+        "description = \"Move down\"\nhints = [\"never shown\"]",
+    );
+    let report = lint_files(&[("a.toml", toml)]);
+    let codes: Vec<DiagnosticCode> = report.diagnostics.iter().map(|d| d.code).collect();
+    assert_eq!(codes, [DiagnosticCode::UnusedHints]);
+    assert!(
+        report.diagnostics[0]
+            .message
+            .contains("[scenarios.steps.solution]")
+    );
+}
+
+/// Scenario deleting "oo" from "foo bar" with `xx` (or `x.`), under `constraints`
+fn constraints_toml(commands: &str, constraints: &str) -> String {
+    ScenarioToml::new("constraints_001")
+        .fields(constraints)
+        .setup(&section("foo bar", [0, 1]))
+        .target(&section("f bar", [0, 1]))
+        .solution(commands, 2)
+        .build()
+}
+
+#[test]
+fn test_command_constraints_parsed() {
+    let scenarios = load_toml(&constraints_toml(
+        r#"["x", "."]"#,
+        "forbidden_commands = [\"h\", \"l\"]\nrequired_commands = [\".\"]",
+    ))
+    .unwrap();
+    assert_eq!(scenarios[0].forbidden_commands, ["h", "l"]);
+    assert_eq!(scenarios[0].required_commands, ["."]);
+}
+
+#[test]
+fn test_verify_enforces_command_constraints() {
+    let verify = |commands: &str, constraints: &str| {
+        let file: ScenariosFile = toml::from_str(&constraints_toml(commands, constraints)).unwrap();
+        super::verify::verify_solutions(&file.scenarios[0])
+    };
+
+    assert!(verify(r#"["x", "x"]"#, "").is_ok());
+    match verify(r#"["x", "x"]"#, "forbidden_commands = [\"x\"]") {
+        Err(SecurityError::SolutionMismatch { step, reason, .. }) => {
+            assert_eq!(step, 1);
+            assert!(reason.contains("forbidden"), "{}", reason);
+        }
+        other => panic!("Expected SolutionMismatch, got {:?}", other),
+    }
+    match verify(r#"["x", "x"]"#, "required_commands = [\".\"]") {
+        Err(SecurityError::SolutionMismatch { step, reason, .. }) => {
+            assert_eq!(step, 2);
+            assert!(reason.contains("'.'"), "{}", reason);
+        }
+        other => panic!("Expected SolutionMismatch, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_invalid_command_constraints_rejected() {
+    for constraints in [
+        "forbidden_commands = [\"\"]",
+        "required_commands = [\".\"]\nforbidden_commands = [\".\"]",
+    ] {
+        assert!(load_toml(&constraints_toml(r#"["x", "."]"#, constraints)).is_err());
+    }
+}
+
+/// Target section for "foo bar" with the given completion rule
+fn target_with_completion(completion: &str) -> String {
+    format!("file_content = \"foo bar\"\ncursor_position = [0, 2]\ncompletion = {completion}")
+}
+
+#[test]
+fn test_completion_rules_parsed() {
+    let scenario = parse_with_target(&target_with_completion(
+        r#"{ rule = "line", line = 0, text = "foo bar" }"#,
+    ));
+    assert_eq!(
+        scenario.target.as_ref().unwrap().completion,
+        Completion::Line {
+            line: 0,
+            text: "foo bar".to_string()
+        }
+    );
+    assert!(load_with_target(&target_with_completion(r#"{ rule = "cursor" }"#)).is_ok());
+
+    let scenario = parse_with_target(&target_with_completion(
+        r#"{ rule = "regex", pattern = "^foo" }"#,
+    ));
+    match &scenario.target.as_ref().unwrap().completion {
+        Completion::Regex { pattern } => assert_eq!(pattern.as_str(), "^foo"),
+        other => panic!("Expected a regex rule, got {:?}", other),
+    }
+    assert_eq!(
+        parse_with_target(FOO_BAR_SETUP)
+            .target
+            .as_ref()
+            .unwrap()
+            .completion,
+        Completion::Exact
+    );
+}
+
+#[test]
+fn test_invalid_completion_rules_rejected() {
+    for completion in [
+        r#"{ rule = "regex", pattern = "(" }"#,
+        r#"{ rule = "fuzzy" }"#,
+        r#"{ rule = "line", line = 0, text = "foo", col = 1 }"#,
+        r#"{ rule = "line", line = 0, text = "foo\nbar" }"#,
+        r#"{ rule = "selection" }"#,
+    ] {
+        assert!(
+            load_with_target(&target_with_completion(completion)).is_err(),
+            "{}",
+            completion
+        );
+    }
+}
+
+#[test]
+fn test_setup_meeting_completion_rule_rejected() {
+    // The setup content already is "foo bar"
+    for completion in [
+        r#"{ rule = "content" }"#,
+        r#"{ rule = "line", line = 0, text = "foo bar" }"#,
+    ] {
+        let scenario = parse_with_target(&target_with_completion(completion));
+        match super::verify::verify_solutions(&scenario) {
+            Err(SecurityError::InvalidInput(message)) => {
+                assert!(message.contains("setup already meets"), "{}", message);
+            }
+            other => panic!("Expected InvalidInput, got {:?}", other),
+        }
+    }
+}
+
+#[test]
+fn test_verify_names_unmet_completion_rule() {
+    let scenario = parse_with_target(&target_with_completion(
+        r#"{ rule = "line", line = 0, text = "nope" }"#,
+    ));
+    match super::verify::verify_solutions(&scenario) {
+        Err(SecurityError::SolutionMismatch { reason, .. }) => {
+            assert!(reason.contains("'line' completion rule"), "{}", reason);
+        }
+        other => panic!("Expected SolutionMismatch, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_exported_scenarios_load_again() {
+    let scenarios = load_toml(&template_toml(WORDS_AND_RANGE)).unwrap();
+    let exported = ScenariosFile {
+        scenarios: scenarios.clone(),
+        translations: Vec::new(),
+    };
+
+    let toml = toml::to_string(&exported).unwrap();
+    assert!(!toml.contains("[scenarios.template"), "{}", toml);
+    let reloaded = ScenarioLoader::new().load_content(&toml).unwrap();
+    assert_eq!(
+        serde_json::to_value(&reloaded).unwrap(),
+        serde_json::to_value(&scenarios).unwrap()
+    );
+
+    let json = serde_json::to_value(&exported).unwrap();
+    assert_eq!(json["scenarios"][0]["id"], "template_001_1");
+    assert_eq!(
+        json["scenarios"][0]["target"]["completion"]["rule"],
+        "exact"
+    );
+    assert!(json["scenarios"][0].get("category").is_none());
+}
+
+/// Translations file with one translation table per entry
+fn translations_toml(translations: &[&str]) -> String {
+    translations
+        .iter()
+        .map(|translation| format!("[[translations]]\n{translation}\n"))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[test]
+fn test_translations_overlay_base_scenarios() {
+    let base = vec![
+        ("a.toml".to_string(), metadata_toml("base_001", "")),
+        ("b.toml".to_string(), metadata_toml("base_002", "")),
+    ];
+    let locale = vec![(
+        "basic/a.toml".to_string(),
+        translations_toml(&[
+            r#"id = "base_001"
+name = "Übersetzt"
+hints = ["Erster Hinweis"]
+solution = { description = "Zeichen löschen" }"#,
+            r#"id = "missing_001"
+name = "Nirgends""#,
+        ]),
+    )];
+
+    let loader = ScenarioLoader::with_allowed_paths(Vec::new());
+    let packs = loader
+        .load_sources(&[ScenarioSource::Memory(base), ScenarioSource::Memory(locale)])
+        .unwrap();
+
+    // Translation-only files add no packs or scenarios
+    assert_eq!(packs.len(), 1);
+    let scenarios = &packs[0].scenarios;
+    assert_eq!(scenarios.len(), 2);
+    assert_eq!(scenarios[0].name, "Übersetzt");
+    assert_eq!(scenarios[0].description, "Test");
+    assert_eq!(scenarios[0].hints, ["Erster Hinweis"]);
+    assert_eq!(scenarios[0].solution.description, "Zeichen löschen");
+    assert_eq!(scenarios[0].solution.commands, ["x"]);
+    assert_eq!(scenarios[1].name, "Test");
+}
+
+#[test]
+fn test_translations_replace_lesson_step_texts() {
+    let base = vec![("a.toml".to_string(), lesson_toml(r#"["j"]"#))];
+    let locale = vec![(
+        "a.toml".to_string(),
+        translations_toml(&[r#"id = "lesson_001"
+
+[[translations.steps]]
+instructions = "Gehe zur nächsten Zeile"
+hints = ["Drücke j"]"#]),
+    )];
+
+    let loader = ScenarioLoader::with_allowed_paths(Vec::new());
+    let packs = loader
+        .load_sources(&[ScenarioSource::Memory(base), ScenarioSource::Memory(locale)])
+        .unwrap();
+    let steps = &packs[0].scenarios[0].steps;
+    assert_eq!(steps[0].instructions, "Gehe zur nächsten Zeile");
+    assert_eq!(steps[0].hints, ["Drücke j"]);
+}
+
+#[test]
+fn test_translations_apply_to_templates_before_expansion() {
+    let base = vec![("a.toml".to_string(), template_toml(WORDS_AND_RANGE))];
+    let locale = vec![(
+        "a.toml".to_string(),
+        translations_toml(&[r#"id = "template_001"
+name = "Lösche {{word}}""#]),
+    )];
+
+    let loader = ScenarioLoader::with_allowed_paths(Vec::new());
+    let packs = loader
+        .load_sources(&[ScenarioSource::Memory(base), ScenarioSource::Memory(locale)])
+        .unwrap();
+    let scenarios = &packs[0].scenarios;
+    assert_eq!(scenarios.len(), 3);
+    for scenario in scenarios {
+        let (word, _) = scenario.setup.file_content.split_once('\n').unwrap();
+        assert_eq!(scenario.name, format!("Lösche {word}"));
+    }
+
+    // Lint counts the template id as translated, not its instances
+    let report = lint_files(&[
+        ("a.toml", template_toml(WORDS_AND_RANGE)),
+        (
+            "b.toml",
+            translations_toml(&[r#"id = "template_001"
+name = "Lösche {{word}}""#]),
+        ),
+    ]);
+    assert!(report.is_clean(), "{:?}", report.diagnostics);
+}
+
+#[test]
+fn test_invalid_translations_rejected() {
+    let loader = ScenarioLoader::new();
+    for translation in [
+        r#"id = "base 001""#,
+        r#"id = "base_001"
+setup = { file_content = "foo" }"#,
+        r#"id = "base_001"
+hints = ["1", "2", "3", "4", "5", "6", "7", "8", "9", "10", "11"]"#,
+        r#"id = "base_001"
+steps = [{ hints = ["1", "2", "3", "4", "5", "6", "7", "8", "9", "10", "11"] }]"#,
+        r#"id = "base_001"
+steps = [{ description = "Schritt" }]"#,
+    ] {
+        assert!(
+            loader
+                .load_content(&translations_toml(&[translation]))
+                .is_err(),
+            "{}",
+            translation
+        );
+    }
+    assert!(loader.load_content("").is_err());
+}
+
+#[test]
+fn test_lint_reports_untranslated_scenarios() {
+    let report = lint_files(&[
+        ("a.toml", metadata_toml("meta_001", "")),
+        ("b.toml", metadata_toml("meta_002", "")),
+        (
+            "c.toml",
+            translations_toml(&[
+                r#"id = "meta_001"
+name = "Traduit""#,
+                r#"id = "meta_009"
+name = "Inconnu""#,
+            ]),
+        ),
+    ]);
+
+    let found: Vec<(DiagnosticCode, Option<&str>)> = report
+        .diagnostics
+        .iter()
+        .map(|d| (d.code, d.scenario.as_deref()))
+        .collect();
+    assert_eq!(
+        found,
+        [
+            (DiagnosticCode::Untranslated, Some("meta_002")),
+            (DiagnosticCode::UnknownTranslation, Some("meta_009")),
+        ]
+    );
+    assert!(report.diagnostics[0].file.ends_with("b.toml"));
+    assert!(report.diagnostics[1].file.ends_with("c.toml"));
+
+    // Without translations among the files nothing is reported
+    let report = lint_files(&[("a.toml", metadata_toml("meta_001", ""))]);
+    assert!(report.is_clean(), "{:?}", report.diagnostics);
+}
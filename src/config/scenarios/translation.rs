@@ -0,0 +1,155 @@
+//! Scenario translations
+//!
+//! The English scenarios are the base set for every locale. A locale's
+//! directory adds `[[translations]]` tables that replace the texts of base
+//! scenarios by id, so a translation never repeats the setup, target or
+//! solution keys and cannot drift from them. Fields a translation leaves out
+//! keep their English text. A template is translated by its own id before
+//! its instances are generated, so the translated texts may use its
+//! `{{name}}` placeholders.
+//!
+//! # Examples
+//!
+//! ```toml
+//! [[translations]]
+//! id = "delete_line_001"
+//! name = "Удалить строку"
+//! description = "Удалите строку под курсором."
+//! hints = ["Нажмите 'd' дважды"]
+//! solution = { description = "Удалить строку" }
+//!
+//! [[translations.steps]]
+//! instructions = "Вставьте строку обратно"
+//! ```
+
+use super::{Scenario, is_valid_id};
+use crate::security::SecurityError;
+use crate::security::limits::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+/// Locale whose scenarios every other locale translates
+pub const BASE_LOCALE: &str = "en";
+
+/// Whether a directory name is a locale code: a 2-letter ISO code
+pub fn is_locale_code(name: &str) -> bool {
+    name.len() == 2 && name.chars().all(|c| c.is_ascii_lowercase())
+}
+
+/// Translated texts of one scenario
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct Translation {
+    /// Id of the scenario the texts belong to
+    pub id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Replaces all hints, in the same order as the scenario's
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hints: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub solution: Option<TranslatedSolution>,
+    /// Texts of the lesson steps after the first, in the same order
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub steps: Vec<TranslatedStep>,
+}
+
+/// Translated texts of a scenario's solution
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct TranslatedSolution {
+    pub description: String,
+}
+
+/// Translated texts of a lesson step
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct TranslatedStep {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub instructions: Option<String>,
+    /// Replaces all hints of the step
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hints: Option<Vec<String>>,
+}
+
+impl Translation {
+    /// Replace the scenario's texts with the translated ones
+    pub fn apply(&self, scenario: &mut Scenario) {
+        if let Some(name) = &self.name {
+            scenario.name.clone_from(name);
+        }
+        if let Some(description) = &self.description {
+            scenario.description.clone_from(description);
+        }
+        if let Some(hints) = &self.hints {
+            scenario.hints.clone_from(hints);
+        }
+        if let Some(solution) = &self.solution {
+            scenario
+                .solution
+                .description
+                .clone_from(&solution.description);
+        }
+        for (step, translated) in scenario.steps.iter_mut().zip(&self.steps) {
+            if let Some(instructions) = &translated.instructions {
+                step.instructions.clone_from(instructions);
+            }
+            if let Some(hints) = &translated.hints {
+                step.hints.clone_from(hints);
+            }
+        }
+    }
+}
+
+/// Check a translation's id, step count and hint counts
+pub(super) fn validate_translation(translation: &Translation) -> Result<(), SecurityError> {
+    if translation.id.is_empty() || !is_valid_id(&translation.id) {
+        return Err(SecurityError::InvalidScenarioId);
+    }
+    if translation.steps.len() > MAX_LESSON_STEPS {
+        return Err(SecurityError::InvalidInput(format!(
+            "Too many lesson steps (max {})",
+            MAX_LESSON_STEPS + 1
+        )));
+    }
+    let hints = std::iter::once(&translation.hints)
+        .chain(translation.steps.iter().map(|step| &step.hints))
+        .flatten();
+    for hints in hints {
+        if hints.len() > MAX_HINTS {
+            return Err(SecurityError::TooManyHints { max: MAX_HINTS });
+        }
+    }
+    Ok(())
+}
+
+/// Apply translations to the scenarios with their ids
+///
+/// A later translation of the same scenario wins, and every scenario with
+/// the id gets it. Returns the ids of translations that match no scenario.
+pub(super) fn apply_translations<'a>(
+    scenarios: impl IntoIterator<Item = &'a mut Scenario>,
+    translations: &[Translation],
+) -> Vec<String> {
+    let by_id: HashMap<&str, &Translation> = translations
+        .iter()
+        .map(|translation| (translation.id.as_str(), translation))
+        .collect();
+    let mut used = HashSet::new();
+    for scenario in scenarios {
+        if let Some(translation) = by_id.get(scenario.id.as_str()) {
+            translation.apply(scenario);
+            used.insert(translation.id.as_str());
+        }
+    }
+
+    let mut unknown: Vec<String> = by_id
+        .into_keys()
+        .filter(|id| !used.contains(id))
+        .map(str::to_string)
+        .collect();
+    unknown.sort();
+    unknown
+}
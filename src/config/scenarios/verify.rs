@@ -0,0 +1,213 @@
+//! Solution verification
+//!
+//! Replays a scenario's solution and alternatives key by key, the way the UI
+//! feeds them to a session, and checks that each one reaches the target.
+//! The solutions of a lesson's steps are replayed one after another on the
+//! same session, and no step may already be solved when it begins. Solutions
+//! must also keep to the scenario's forbidden and required commands.
+
+use super::{Completion, Scenario};
+use crate::game::GameSession;
+use crate::helix::commands::CMD_ESCAPE;
+use crate::helix::keys::push_key;
+use crate::security::SecurityError;
+
+/// Check that the solution and every alternative turn the setup into the target
+///
+/// # Errors
+///
+/// Returns `SecurityError::UnreachableTarget` if the target state does not
+/// fit its content, `SecurityError::InvalidInput` if the setup cannot be
+/// turned into a session or the setup or a lesson step is solved before any
+/// key is pressed, and `SecurityError::SolutionMismatch` naming the first key
+/// sequence and step that goes wrong.
+pub(super) fn verify_solutions(scenario: &Scenario) -> Result<(), SecurityError> {
+    let targets = scenario
+        .target
+        .iter()
+        .chain(scenario.steps.iter().map(|step| &step.target));
+    let mut target_states = Vec::new();
+    for target in targets.clone() {
+        let state =
+            GameSession::build_target(target).map_err(|err| SecurityError::UnreachableTarget {
+                id: scenario.id.clone(),
+                reason: err.to_string(),
+            })?;
+        target_states.push(state);
+    }
+
+    // Each step starts where the previous target leaves the buffer
+    let (mut start, _) = GameSession::initial_setup(scenario).map_err(|_| setup_error(scenario))?;
+    for (index, (target, target_state)) in targets.zip(target_states).enumerate() {
+        if GameSession::target_reached(target, &start, &target_state) {
+            let what = match index {
+                0 => "its setup already meets the target".to_string(),
+                _ => format!("lesson step {} is already solved when it begins", index + 1),
+            };
+            return Err(SecurityError::InvalidInput(format!(
+                "Scenario '{}': {}",
+                scenario.id, what
+            )));
+        }
+        start = target_state;
+    }
+
+    let mut session = new_session(scenario)?;
+    verify_keys(
+        scenario,
+        &mut session,
+        "solution",
+        &scenario.solution.commands,
+    )?;
+    let mut label = "solution".to_string();
+    let mut keys = &scenario.solution.commands;
+    for (index, step) in scenario.steps.iter().enumerate() {
+        // A step reached along with the previous one would score without a key
+        if session.is_completed() || session.current_step() != index + 1 {
+            return Err(SecurityError::InvalidInput(format!(
+                "Scenario '{}': lesson step {} is already solved when it begins",
+                scenario.id,
+                index + 2
+            )));
+        }
+        label = format!("step {} solution", index + 2);
+        keys = &step.solution.commands;
+        verify_keys(scenario, &mut session, &label, keys)?;
+    }
+    verify_required(scenario, &session, &label, keys)?;
+
+    for (index, alternative) in scenario.alternatives.iter().enumerate() {
+        let label = format!("alternative {}", index + 1);
+        let mut session = new_session(scenario)?;
+        verify_keys(scenario, &mut session, &label, &alternative.commands)?;
+        if scenario.steps.is_empty() {
+            verify_required(scenario, &session, &label, &alternative.commands)?;
+        }
+    }
+    Ok(())
+}
+
+/// Check that a finished replay used every required command
+fn verify_required(
+    scenario: &Scenario,
+    session: &GameSession,
+    label: &str,
+    keys: &[String],
+) -> Result<(), SecurityError> {
+    match session.missing_commands().first() {
+        Some(command) => Err(SecurityError::SolutionMismatch {
+            id: scenario.id.clone(),
+            solution: label.to_string(),
+            step: keys.len(),
+            key: keys.last().cloned().unwrap_or_default(),
+            reason: format!("never uses the required command '{}'", command),
+        }),
+        None => Ok(()),
+    }
+}
+
+/// Start a session on the scenario setup
+fn new_session(scenario: &Scenario) -> Result<GameSession, SecurityError> {
+    GameSession::new(scenario.clone()).map_err(|_| setup_error(scenario))
+}
+
+/// Error for a setup that cannot be turned into a session
+fn setup_error(scenario: &Scenario) -> SecurityError {
+    SecurityError::InvalidInput(format!(
+        "Scenario '{}': setup state does not fit its content",
+        scenario.id
+    ))
+}
+
+/// Replay one key sequence and report the first step that diverges
+///
+/// A sequence passes as soon as it reaches the target of the session's
+/// current step, as it does in the UI.
+fn verify_keys(
+    scenario: &Scenario,
+    session: &mut GameSession,
+    label: &str,
+    keys: &[String],
+) -> Result<(), SecurityError> {
+    let mismatch = |step: usize, key: &str, reason: String| SecurityError::SolutionMismatch {
+        id: scenario.id.clone(),
+        solution: label.to_string(),
+        step,
+        key: key.to_string(),
+        reason,
+    };
+
+    let lesson_step = session.current_step();
+    let reached =
+        |session: &GameSession| session.is_completed() || session.current_step() != lesson_step;
+
+    let mut buffer = String::new();
+    for (index, key) in keys.iter().enumerate() {
+        let step = index + 1;
+        if reached(session) {
+            // The UI finishes the scenario or step here, so remaining keys (like a
+            // final Escape after typing) never run
+            return Ok(());
+        }
+
+        let command = if session.is_insert_mode() {
+            Some(key.clone())
+        } else {
+            push_key(&mut buffer, key)
+        };
+
+        let blocked = session.blocked_commands().len();
+        match command {
+            Some(command) => session
+                .record_action(command.clone())
+                .map_err(|_| mismatch(step, key, format!("'{}' failed", command)))?,
+            None if buffer.is_empty() && key != CMD_ESCAPE => {
+                return Err(mismatch(step, key, "not a command".to_string()));
+            }
+            None => {}
+        }
+        if let Some(command) = session.blocked_commands().get(blocked) {
+            return Err(mismatch(
+                step,
+                key,
+                format!("'{}' is a forbidden command", command),
+            ));
+        }
+    }
+
+    let (step, last_key) = (keys.len(), keys.last().map_or("", String::as_str));
+    if !buffer.is_empty() {
+        return Err(mismatch(
+            step,
+            last_key,
+            format!("'{}' is an incomplete command", buffer),
+        ));
+    }
+    if !reached(session) {
+        let state = session.current_state();
+        let cursor = state.cursor_position();
+        let target = session.target_state().cursor_position();
+        let completion = &session.current_target().completion;
+        let reason = if *completion != Completion::Exact {
+            format!(
+                "ends without meeting the target's '{}' completion rule",
+                completion.name()
+            )
+        } else if !session.check_content_matches() {
+            format!(
+                "ends with content {:?} instead of the target",
+                state.content()
+            )
+        } else if (cursor.row, cursor.col) != (target.row, target.col) {
+            format!(
+                "ends with the cursor at [{}, {}] instead of the target",
+                cursor.row, cursor.col
+            )
+        } else {
+            "ends with selections that differ from the target".to_string()
+        };
+        return Err(mismatch(step, last_key, reason));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,597 @@
+//! Trainer configuration
+//!
+//! The user's configuration lives in `config.toml` in the configuration
+//! directory (see [`super::dirs::config_file`]). The settings screen saves
+//! its choices there, and the file can also be edited by hand for settings
+//! the screen does not offer. A missing file means the defaults, and a file
+//! that fails validation is reported rather than replaced. Command line flags
+//! override the file for one run through [`ConfigOverrides`].
+//!
+//! # Examples
+//!
+//! ```toml
+//! locale = "en"
+//! keymap = "lenient"
+//! theme = "light"
+//! hints = "automatic"
+//! show_timer = false
+//! mode = "motion_drill"
+//! scenario_dirs = ["/home/me/helix-scenarios"]
+//! success_delay_ms = 800
+//! indent_unit = "    "
+//! hint_penalty = 10
+//!
+//! [keys]
+//! "C-j" = "j"
+//! "ö" = "h"
+//! ```
+
+use super::scenarios::{BASE_LOCALE, is_locale_code};
+use crate::helix::{DEFAULT_INDENT_UNIT, is_valid_indent_unit};
+use crate::security::SecurityError;
+use crate::security::limits::*;
+use crate::security::path_validator;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// How strictly key presses must match the Helix keymap
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum KeymapMode {
+    /// Keys must arrive with exactly the modifiers Helix expects
+    #[default]
+    Exact,
+    /// A key that matches no command is retried with Shift toggled, for
+    /// terminals that report shifted characters inconsistently
+    Lenient,
+}
+
+/// Colour theme of the interface
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Theme {
+    /// Colours for dark terminal backgrounds
+    #[default]
+    Dark,
+    /// Colours for light terminal backgrounds
+    Light,
+    /// No colours, only bold and reversed text
+    Monochrome,
+}
+
+/// When scenario hints are shown
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum HintMode {
+    /// The next hint is shown when F1 is pressed
+    #[default]
+    OnRequest,
+    /// The first hint of each scenario and lesson step is shown right away
+    Automatic,
+    /// Hints are never shown
+    Off,
+}
+
+/// What the trainer starts with
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum GameMode {
+    /// The main menu with the scenario list
+    #[default]
+    Scenarios,
+    /// The endless motion drill
+    MotionDrill,
+}
+
+/// A key with its modifiers, named like in Helix: `x`, `C-d`, `A-d`, `space`
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(try_from = "String", into = "String")]
+pub struct KeyNotation {
+    /// The character typed
+    pub key: char,
+    /// Whether Ctrl is held
+    pub ctrl: bool,
+    /// Whether Alt is held
+    pub alt: bool,
+}
+
+impl FromStr for KeyNotation {
+    type Err = SecurityError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let invalid = || SecurityError::InvalidInput(format!("invalid key '{text}'"));
+        let (mut ctrl, mut alt) = (false, false);
+        let mut rest = text;
+        loop {
+            if let Some(after) = rest.strip_prefix("C-").filter(|after| !after.is_empty()) {
+                if ctrl {
+                    return Err(invalid());
+                }
+                ctrl = true;
+                rest = after;
+            } else if let Some(after) = rest.strip_prefix("A-").filter(|after| !after.is_empty()) {
+                if alt {
+                    return Err(invalid());
+                }
+                alt = true;
+                rest = after;
+            } else {
+                break;
+            }
+        }
+
+        let key = if rest == "space" {
+            ' '
+        } else {
+            let mut chars = rest.chars();
+            match (chars.next(), chars.next()) {
+                (Some(key), None) if !key.is_control() && !key.is_whitespace() => key,
+                _ => return Err(invalid()),
+            }
+        };
+        Ok(Self { key, ctrl, alt })
+    }
+}
+
+impl TryFrom<String> for KeyNotation {
+    type Error = SecurityError;
+
+    fn try_from(text: String) -> Result<Self, Self::Error> {
+        text.parse()
+    }
+}
+
+impl From<KeyNotation> for String {
+    fn from(key: KeyNotation) -> Self {
+        key.to_string()
+    }
+}
+
+impl fmt::Display for KeyNotation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.ctrl {
+            f.write_str("C-")?;
+        }
+        if self.alt {
+            f.write_str("A-")?;
+        }
+        match self.key {
+            ' ' => f.write_str("space"),
+            key => write!(f, "{key}"),
+        }
+    }
+}
+
+impl KeymapMode {
+    /// The mode after this one on the settings screen
+    pub fn next(self) -> Self {
+        match self {
+            Self::Exact => Self::Lenient,
+            Self::Lenient => Self::Exact,
+        }
+    }
+
+    /// Translation key of the mode's name
+    pub fn label_key(self) -> &'static str {
+        match self {
+            Self::Exact => "settings.keymap_exact",
+            Self::Lenient => "settings.keymap_lenient",
+        }
+    }
+}
+
+impl Theme {
+    /// The theme after this one on the settings screen
+    pub fn next(self) -> Self {
+        match self {
+            Self::Dark => Self::Light,
+            Self::Light => Self::Monochrome,
+            Self::Monochrome => Self::Dark,
+        }
+    }
+
+    /// Translation key of the theme's name
+    pub fn label_key(self) -> &'static str {
+        match self {
+            Self::Dark => "settings.theme_dark",
+            Self::Light => "settings.theme_light",
+            Self::Monochrome => "settings.theme_monochrome",
+        }
+    }
+}
+
+impl HintMode {
+    /// The mode after this one on the settings screen
+    pub fn next(self) -> Self {
+        match self {
+            Self::OnRequest => Self::Automatic,
+            Self::Automatic => Self::Off,
+            Self::Off => Self::OnRequest,
+        }
+    }
+
+    /// Translation key of the mode's name
+    pub fn label_key(self) -> &'static str {
+        match self {
+            Self::OnRequest => "settings.hints_on_request",
+            Self::Automatic => "settings.hints_automatic",
+            Self::Off => "settings.hints_off",
+        }
+    }
+}
+
+/// User configuration saved between runs
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct TrainerConfig {
+    /// Locale of the interface and the scenarios, a 2-letter code
+    pub locale: String,
+    pub keymap: KeymapMode,
+    pub theme: Theme,
+    pub hints: HintMode,
+    /// Whether the elapsed time is shown during and after a scenario
+    pub show_timer: bool,
+    /// What the trainer starts with
+    pub mode: GameMode,
+    /// Extra scenario directories, absolute paths loaded after the data
+    /// directory and before `HELIX_TRAINER_SCENARIOS`
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub scenario_dirs: Vec<PathBuf>,
+    /// How long the success screen shows before the results, in milliseconds
+    pub success_delay_ms: u64,
+    /// Indentation `>` adds and `<` removes: a tab or up to 8 spaces, for
+    /// scenarios that do not pin their own
+    pub indent_unit: String,
+    /// Points deducted from the score for each hint asked for; hints shown
+    /// automatically are free
+    pub hint_penalty: u32,
+    /// Keys typed in Normal mode that act as other keys
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub keys: BTreeMap<KeyNotation, KeyNotation>,
+}
+
+impl Default for TrainerConfig {
+    fn default() -> Self {
+        Self {
+            locale: BASE_LOCALE.to_string(),
+            keymap: KeymapMode::default(),
+            theme: Theme::default(),
+            hints: HintMode::default(),
+            show_timer: true,
+            mode: GameMode::default(),
+            scenario_dirs: Vec::new(),
+            success_delay_ms: 1500,
+            indent_unit: DEFAULT_INDENT_UNIT.to_string(),
+            hint_penalty: 0,
+            keys: BTreeMap::new(),
+        }
+    }
+}
+
+impl TrainerConfig {
+    /// Read the settings file, or the defaults if there is none
+    ///
+    /// # Errors
+    ///
+    /// Returns `SecurityError` if the file is too large, cannot be read or
+    /// holds invalid settings.
+    pub fn load(path: &Path) -> Result<Self, SecurityError> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        path_validator::validate_file_size(path, MAX_CONFIG_FILE_SIZE)?;
+        let content = fs::read_to_string(path).map_err(|e| {
+            tracing::error!("Failed to read config file: {}", e);
+            SecurityError::InvalidPath
+        })?;
+        Self::parse(&content)
+    }
+
+    /// Parse and validate settings from TOML
+    pub fn parse(content: &str) -> Result<Self, SecurityError> {
+        let config: Self =
+            toml::from_str(content).map_err(|e| SecurityError::InvalidToml(e.to_string()))?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Check the settings that serde cannot
+    ///
+    /// # Errors
+    ///
+    /// Returns `SecurityError` naming the first invalid setting.
+    pub fn validate(&self) -> Result<(), SecurityError> {
+        let invalid = |message: String| Err(SecurityError::InvalidInput(message));
+
+        if !is_locale_code(&self.locale) {
+            return invalid(format!(
+                "locale must be a 2-letter lowercase code, got '{}'",
+                self.locale
+            ));
+        }
+        if self.scenario_dirs.len() > MAX_CONFIG_SCENARIO_DIRS {
+            return invalid(format!(
+                "at most {MAX_CONFIG_SCENARIO_DIRS} scenario_dirs are allowed"
+            ));
+        }
+        if let Some(dir) = self.scenario_dirs.iter().find(|dir| !dir.is_absolute()) {
+            return invalid(format!(
+                "scenario_dirs must be absolute paths, got '{}'",
+                dir.display()
+            ));
+        }
+        if self.success_delay_ms > MAX_SUCCESS_DELAY_MS {
+            return invalid(format!(
+                "success_delay_ms must be at most {MAX_SUCCESS_DELAY_MS}"
+            ));
+        }
+        if !is_valid_indent_unit(&self.indent_unit) {
+            return invalid(format!(
+                "indent_unit must be a tab or 1 to {MAX_INDENT_WIDTH} spaces"
+            ));
+        }
+        if self.hint_penalty > MAX_HINT_PENALTY {
+            return invalid(format!("hint_penalty must be at most {MAX_HINT_PENALTY}"));
+        }
+        if self.keys.len() > MAX_KEY_REMAPS {
+            return invalid(format!("at most {MAX_KEY_REMAPS} keys can be remapped"));
+        }
+        Ok(())
+    }
+
+    /// Write the settings file, creating its directory if needed
+    pub fn save(&self, path: &Path) -> Result<(), SecurityError> {
+        let content =
+            toml::to_string(self).map_err(|e| SecurityError::InvalidToml(e.to_string()))?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|e| {
+                tracing::error!("Failed to create config directory: {}", e);
+                SecurityError::InvalidPath
+            })?;
+        }
+        fs::write(path, content).map_err(|e| {
+            tracing::error!("Failed to write config file: {}", e);
+            SecurityError::InvalidPath
+        })
+    }
+}
+
+/// Settings given on the command line, which win over the file for one run
+///
+/// Overridden values are not saved unless they are changed on the settings
+/// screen.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConfigOverrides {
+    /// From `--locale`
+    pub locale: Option<String>,
+    /// From `--theme`
+    pub theme: Option<Theme>,
+    /// From `--mode`
+    pub mode: Option<GameMode>,
+    /// From `--scenario-dir`, replacing the file's list
+    pub scenario_dirs: Option<Vec<PathBuf>>,
+    /// From `--success-delay-ms`
+    pub success_delay_ms: Option<u64>,
+    /// From `--indent-unit`
+    pub indent_unit: Option<String>,
+    /// From `--hint-penalty`
+    pub hint_penalty: Option<u32>,
+}
+
+impl ConfigOverrides {
+    /// Replace the file's values with the command line's and validate
+    pub fn apply(&self, config: &mut TrainerConfig) -> Result<(), SecurityError> {
+        if let Some(locale) = &self.locale {
+            config.locale.clone_from(locale);
+        }
+        if let Some(theme) = self.theme {
+            config.theme = theme;
+        }
+        if let Some(mode) = self.mode {
+            config.mode = mode;
+        }
+        if let Some(dirs) = &self.scenario_dirs {
+            config.scenario_dirs.clone_from(dirs);
+        }
+        if let Some(delay) = self.success_delay_ms {
+            config.success_delay_ms = delay;
+        }
+        if let Some(unit) = &self.indent_unit {
+            config.indent_unit.clone_from(unit);
+        }
+        if let Some(points) = self.hint_penalty {
+            config.hint_penalty = points;
+        }
+        config.validate()
+    }
+
+    /// The configuration to save: overridden values that are still in
+    /// effect go back to the file's
+    pub fn to_save(&self, config: &TrainerConfig, file: &TrainerConfig) -> TrainerConfig {
+        let mut saved = config.clone();
+        if self.locale.as_ref() == Some(&config.locale) {
+            saved.locale.clone_from(&file.locale);
+        }
+        if self.theme == Some(config.theme) {
+            saved.theme = file.theme;
+        }
+        if self.mode == Some(config.mode) {
+            saved.mode = file.mode;
+        }
+        if self.scenario_dirs.as_ref() == Some(&config.scenario_dirs) {
+            saved.scenario_dirs.clone_from(&file.scenario_dirs);
+        }
+        if self.success_delay_ms == Some(config.success_delay_ms) {
+            saved.success_delay_ms = file.success_delay_ms;
+        }
+        if self.indent_unit.as_ref() == Some(&config.indent_unit) {
+            saved.indent_unit.clone_from(&file.indent_unit);
+        }
+        if self.hint_penalty == Some(config.hint_penalty) {
+            saved.hint_penalty = file.hint_penalty;
+        }
+        saved
+    }
+}
+
+/// Parse a command line value of a setting, like `light` for `--theme`
+pub fn parse_setting<'de, T: Deserialize<'de>>(value: &'de str) -> Result<T, SecurityError> {
+    T::deserialize(serde::de::value::BorrowedStrDeserializer::<
+        serde::de::value::Error,
+    >::new(value))
+    .map_err(|e| SecurityError::InvalidInput(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_missing_file_gives_defaults() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = TrainerConfig::load(&dir.path().join("config.toml")).unwrap();
+        assert_eq!(config, TrainerConfig::default());
+        assert!(config.show_timer);
+    }
+
+    #[test]
+    fn test_saved_settings_load_again() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("helix-trainer").join("config.toml");
+        let config = TrainerConfig {
+            locale: "ru".to_string(),
+            keymap: KeymapMode::Lenient,
+            theme: Theme::Monochrome,
+            hints: HintMode::Automatic,
+            show_timer: false,
+            mode: GameMode::MotionDrill,
+            scenario_dirs: vec![PathBuf::from("/srv/scenarios")],
+            success_delay_ms: 0,
+            indent_unit: "\t".to_string(),
+            hint_penalty: 25,
+            keys: BTreeMap::from([("C-j".parse().unwrap(), "j".parse().unwrap())]),
+        };
+        config.save(&path).unwrap();
+
+        let content = fs::read_to_string(&path).unwrap();
+        assert!(content.contains("hints = \"automatic\""));
+        assert_eq!(TrainerConfig::load(&path).unwrap(), config);
+    }
+
+    #[test]
+    fn test_partial_file_keeps_other_defaults() {
+        let config = TrainerConfig::parse("theme = \"light\"").unwrap();
+        assert_eq!(config.theme, Theme::Light);
+        assert_eq!(config.locale, BASE_LOCALE);
+        assert_eq!(config.hints, HintMode::OnRequest);
+    }
+
+    #[test]
+    fn test_invalid_settings_rejected() {
+        for content in [
+            "locale = \"../en\"",
+            "locale = \"EN\"",
+            "theme = \"neon\"",
+            "colour = \"dark\"",
+            "show_timer = \"yes\"",
+            "mode = \"race\"",
+            "scenario_dirs = [\"scenarios\"]",
+            "success_delay_ms = 60000",
+            "success_delay_ms = -1",
+            "indent_unit = \"\"",
+            "indent_unit = \"  \\t\"",
+            "indent_unit = \"         \"",
+            "hint_penalty = 5000",
+            "[keys]\n\"jj\" = \"j\"",
+            "[keys]\n\"C-\" = \"j\"",
+            "[keys]\n\"x\" = \"S-x\"",
+        ] {
+            assert!(TrainerConfig::parse(content).is_err(), "{content}");
+        }
+    }
+
+    #[test]
+    fn test_key_notation() {
+        let key: KeyNotation = "C-A-d".parse().unwrap();
+        assert_eq!(
+            key,
+            KeyNotation {
+                key: 'd',
+                ctrl: true,
+                alt: true
+            }
+        );
+        assert_eq!(key.to_string(), "C-A-d");
+        assert_eq!("space".parse::<KeyNotation>().unwrap().key, ' ');
+        assert_eq!("-".parse::<KeyNotation>().unwrap().key, '-');
+        assert_eq!("C--".parse::<KeyNotation>().unwrap().to_string(), "C--");
+        for text in ["", "C-C-d", "dd", "\t", "C-"] {
+            assert!(text.parse::<KeyNotation>().is_err(), "{text:?}");
+        }
+
+        let config = TrainerConfig::parse("[keys]\n\"A-ö\" = \"C-d\"").unwrap();
+        let typed = KeyNotation {
+            key: 'ö',
+            ctrl: false,
+            alt: true,
+        };
+        assert_eq!(config.keys[&typed].to_string(), "C-d");
+    }
+
+    #[test]
+    fn test_overrides_win_but_are_not_saved() {
+        let file = TrainerConfig::parse("theme = \"light\"\nhint_penalty = 5").unwrap();
+        let overrides = ConfigOverrides {
+            theme: Some(parse_setting("monochrome").unwrap()),
+            mode: Some(parse_setting("motion_drill").unwrap()),
+            scenario_dirs: Some(vec![PathBuf::from("/srv/scenarios")]),
+            success_delay_ms: Some(0),
+            indent_unit: Some("\t".to_string()),
+            ..ConfigOverrides::default()
+        };
+        let mut config = file.clone();
+        overrides.apply(&mut config).unwrap();
+        assert_eq!(config.theme, Theme::Monochrome);
+        assert_eq!(config.mode, GameMode::MotionDrill);
+        assert_eq!(config.scenario_dirs, [PathBuf::from("/srv/scenarios")]);
+        assert_eq!(config.success_delay_ms, 0);
+        assert_eq!(config.indent_unit, "\t");
+        assert_eq!(config.hint_penalty, 5);
+
+        // A setting changed on the settings screen is saved; the others
+        // keep the file's values
+        config.theme = Theme::Dark;
+        let saved = overrides.to_save(&config, &file);
+        assert_eq!(saved.theme, Theme::Dark);
+        assert_eq!(saved.mode, GameMode::Scenarios);
+        assert_eq!(saved.scenario_dirs, file.scenario_dirs);
+        assert_eq!(saved.success_delay_ms, file.success_delay_ms);
+        assert_eq!(saved.indent_unit, file.indent_unit);
+
+        assert!(parse_setting::<Theme>("neon").is_err());
+        let bad_locale = ConfigOverrides {
+            locale: Some("english".to_string()),
+            ..ConfigOverrides::default()
+        };
+        assert!(bad_locale.apply(&mut config).is_err());
+        let bad_penalty = ConfigOverrides {
+            hint_penalty: Some(MAX_HINT_PENALTY + 1),
+            ..ConfigOverrides::default()
+        };
+        assert!(bad_penalty.apply(&mut config).is_err());
+    }
+
+    #[test]
+    fn test_options_cycle_back_to_the_first() {
+        assert_eq!(KeymapMode::Exact.next().next(), KeymapMode::Exact);
+        assert_eq!(Theme::Dark.next().next().next(), Theme::Dark);
+        assert_eq!(
+            HintMode::OnRequest.next().next().next(),
+            HintMode::OnRequest
+        );
+    }
+}
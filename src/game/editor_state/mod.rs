@@ -1,7 +1,7 @@
 //! Editor state representation and management.
 //!
 //! This module defines the EditorState type which represents the state of a text editor
-//! at a given moment, including file content, cursor position, and optional selection.
+//! at a given moment, including file content, cursor position, and selections.
 //!
 //! All operations validate against security limits and ensure bounds correctness.
 //!
@@ -36,8 +36,10 @@ pub struct EditorState {
     content: String,
     /// Cursor position as (row, col) - both 0-indexed
     cursor_pos: CursorPosition,
-    /// Optional selection range (start_row, start_col, end_row, end_col)
-    selection: Option<Selection>,
+    /// Selection ranges in document order (empty when no selection is tracked)
+    selections: Vec<Selection>,
+    /// Index of the primary selection in `selections`
+    primary_selection: usize,
 }
 
 /// Cursor position with validated bounds.
@@ -114,7 +116,8 @@ impl CursorPosition {
 /// Text selection range.
 ///
 /// Represents a selection of text between two cursor positions.
-/// The selection is stored as-is (start position may come after end position).
+/// The selection is stored as-is (start position may come after end position):
+/// `start` is the anchor and `end` is the head, so the direction is kept.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Selection {
     /// Starting position of the selection
@@ -244,10 +247,51 @@ impl EditorState {
         Ok(Self {
             content,
             cursor_pos,
-            selection,
+            selections: selection.into_iter().collect(),
+            primary_selection: 0,
         })
     }
 
+    /// Replace the selections with a list of ranges and a primary index.
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidInput` if any selection is out of bounds or if the
+    /// primary index does not point into a non-empty list.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use helix_trainer::game::{EditorState, CursorPosition, Selection};
+    ///
+    /// let a = Selection::new(CursorPosition::new(0, 0)?, CursorPosition::new(0, 1)?);
+    /// let b = Selection::new(CursorPosition::new(1, 0)?, CursorPosition::new(1, 1)?);
+    /// let state = EditorState::new("a\nb".to_string(), CursorPosition::new(1, 1)?, None)?
+    ///     .with_selections(vec![a, b], 1)?;
+    /// assert_eq!(state.selection(), Some(b));
+    /// # Ok::<(), helix_trainer::security::SecurityError>(())
+    /// ```
+    pub fn with_selections(
+        mut self,
+        selections: Vec<Selection>,
+        primary_selection: usize,
+    ) -> Result<Self, SecurityError> {
+        if !selections.is_empty() && primary_selection >= selections.len() {
+            return Err(SecurityError::InvalidInput(format!(
+                "Primary selection {} out of range for {} selections",
+                primary_selection,
+                selections.len()
+            )));
+        }
+        for sel in &selections {
+            Self::validate_selection_bounds(&self.content, sel)?;
+        }
+
+        self.selections = selections;
+        self.primary_selection = primary_selection;
+        Ok(self)
+    }
+
     /// Create from scenario setup data.
     ///
     /// Convenience constructor that takes setup data from scenario TOML format.
@@ -330,7 +374,7 @@ impl EditorState {
         self.cursor_pos
     }
 
-    /// Get the selection.
+    /// Get the primary selection.
     ///
     /// # Examples
     ///
@@ -343,7 +387,17 @@ impl EditorState {
     /// # Ok::<(), helix_trainer::security::SecurityError>(())
     /// ```
     pub fn selection(&self) -> Option<Selection> {
-        self.selection
+        self.selections.get(self.primary_selection).copied()
+    }
+
+    /// Get all selections in document order.
+    pub fn selections(&self) -> &[Selection] {
+        &self.selections
+    }
+
+    /// Get the index of the primary selection.
+    pub fn primary_selection(&self) -> usize {
+        self.primary_selection
     }
 
     /// Get number of lines in the content.
@@ -427,11 +481,14 @@ impl EditorState {
         // Adjust cursor if it's now out of bounds
         self.clamp_cursor_to_bounds()?;
 
-        // Clear selection if it's now invalid
-        if let Some(sel) = self.selection
-            && Self::validate_selection_bounds(&self.content, &sel).is_err()
+        // Clear selections if any is now invalid
+        if self
+            .selections
+            .iter()
+            .any(|sel| Self::validate_selection_bounds(&self.content, sel).is_err())
         {
-            self.selection = None;
+            self.selections.clear();
+            self.primary_selection = 0;
         }
 
         Ok(())
@@ -479,7 +536,7 @@ impl EditorState {
         Ok(())
     }
 
-    /// Set the selection.
+    /// Set a single selection (or clear all selections with `None`).
     ///
     /// Validates that selection bounds are within content bounds.
     ///
@@ -505,7 +562,8 @@ impl EditorState {
         if let Some(sel) = selection {
             Self::validate_selection_bounds(&self.content, &sel)?;
         }
-        self.selection = selection;
+        self.selections = selection.into_iter().collect();
+        self.primary_selection = 0;
         Ok(())
     }
 
@@ -546,6 +604,26 @@ impl EditorState {
         self.content == other.content
     }
 
+    /// Check if selections match another state, including direction and primary.
+    ///
+    /// Used by scenarios that opt into selection checks on top of [`Self::matches`].
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use helix_trainer::game::{EditorState, CursorPosition, Selection};
+    ///
+    /// let cursor = CursorPosition::new(0, 4)?;
+    /// let sel = Selection::new(CursorPosition::new(0, 0)?, cursor);
+    /// let state1 = EditorState::new("test\n".to_string(), cursor, Some(sel))?;
+    /// let state2 = EditorState::new("test\n".to_string(), cursor, Some(sel))?;
+    /// assert!(state1.selections_match(&state2));
+    /// # Ok::<(), helix_trainer::security::SecurityError>(())
+    /// ```
+    pub fn selections_match(&self, other: &EditorState) -> bool {
+        self.selections == other.selections && self.primary_selection == other.primary_selection
+    }
+
     /// Clamp cursor to valid bounds after content change.
     fn clamp_cursor_to_bounds(&mut self) -> Result<(), SecurityError> {
         let line_count = self.line_count();
@@ -605,7 +683,8 @@ impl Default for EditorState {
         Self {
             content: String::new(),
             cursor_pos: CursorPosition { row: 0, col: 0 },
-            selection: None,
+            selections: Vec::new(),
+            primary_selection: 0,
         }
     }
 }
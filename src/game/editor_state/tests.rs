@@ -170,3 +170,60 @@ fn test_default_editor_state() {
     assert_eq!(state.cursor_position().col, 0);
     assert!(state.selection().is_none());
 }
+
+#[test]
+fn test_with_selections_sets_primary() {
+    let cursor = CursorPosition::new(0, 3).unwrap();
+    let first = Selection::new(
+        CursorPosition::new(0, 0).unwrap(),
+        CursorPosition::new(0, 3).unwrap(),
+    );
+    let second = Selection::new(
+        CursorPosition::new(0, 4).unwrap(),
+        CursorPosition::new(0, 7).unwrap(),
+    );
+
+    let state = EditorState::new("foo bar".to_string(), cursor, None)
+        .unwrap()
+        .with_selections(vec![first, second], 1)
+        .unwrap();
+
+    assert_eq!(state.selections().len(), 2);
+    assert_eq!(state.primary_selection(), 1);
+    assert_eq!(state.selection(), Some(second));
+}
+
+#[test]
+fn test_with_selections_rejects_invalid_primary() {
+    let cursor = CursorPosition::new(0, 0).unwrap();
+    let sel = Selection::new(cursor, CursorPosition::new(0, 3).unwrap());
+
+    let result = EditorState::new("foo bar".to_string(), cursor, None)
+        .unwrap()
+        .with_selections(vec![sel], 1);
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_selections_match_checks_direction() {
+    let cursor = CursorPosition::new(0, 0).unwrap();
+    let start = CursorPosition::new(0, 0).unwrap();
+    let end = CursorPosition::new(0, 3).unwrap();
+
+    let forward = EditorState::new(
+        "foo bar".to_string(),
+        cursor,
+        Some(Selection::new(start, end)),
+    )
+    .unwrap();
+    let backward = EditorState::new(
+        "foo bar".to_string(),
+        cursor,
+        Some(Selection::new(end, start)),
+    )
+    .unwrap();
+
+    assert!(forward.matches(&backward));
+    assert!(forward.selections_match(&forward.clone()));
+    assert!(!forward.selections_match(&backward));
+}
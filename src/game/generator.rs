@@ -0,0 +1,326 @@
+//! Procedural motion drills
+//!
+//! Generates "move the cursor to the target" scenarios from random,
+//! code-like text. The target is found by a random walk of allowed motions
+//! from a random start, so it is always reachable with them, and the
+//! optimal key count comes from a search restricted to the same motions.
+//!
+//! # Examples
+//!
+//! ```ignore
+//! use helix_trainer::game::generator::{DrillOptions, generate_motion_drill};
+//!
+//! let scenario = generate_motion_drill(&DrillOptions::default(), 42)?;
+//! assert!(scenario.scoring.optimal_count > 0);
+//! # Ok::<(), helix_trainer::security::SecurityError>(())
+//! ```
+
+use super::EditorState;
+use super::solver::{SearchBudget, SearchOutcome, find_shortest_solution_using};
+use crate::config::{Scenario, ScoringConfig, Setup, SetupMode, Solution, TargetState};
+use crate::helix::HelixSimulator;
+use crate::helix::commands::*;
+use crate::security::SecurityError;
+use rust_i18n::t;
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+/// Motions a drill may use, with the keys that type them
+pub const DRILL_MOTIONS: [(&str, &[&str]); 14] = [
+    (CMD_MOVE_LEFT, &["h"]),
+    (CMD_MOVE_DOWN, &["j"]),
+    (CMD_MOVE_UP, &["k"]),
+    (CMD_MOVE_RIGHT, &["l"]),
+    (CMD_MOVE_WORD_FORWARD, &["w"]),
+    (CMD_MOVE_WORD_BACKWARD, &["b"]),
+    (CMD_MOVE_WORD_END, &["e"]),
+    (CMD_MOVE_LONG_WORD_FORWARD, &["W"]),
+    (CMD_MOVE_LONG_WORD_BACKWARD, &["B"]),
+    (CMD_MOVE_LONG_WORD_END, &["E"]),
+    (CMD_MOVE_LINE_START, &["0"]),
+    (CMD_MOVE_LINE_END, &["$"]),
+    (CMD_GOTO_FILE_START, &["g", "g"]),
+    (CMD_GOTO_FILE_END, &["G"]),
+];
+
+/// Start positions tried before giving up on a walk that goes nowhere
+const MAX_ATTEMPTS: usize = 20;
+
+const NAMES: [&str; 12] = [
+    "count", "total", "items", "value", "index", "buffer", "result", "config", "parser", "token",
+    "line", "offset",
+];
+
+const CALLS: [&str; 8] = [
+    "parse", "load", "render", "update", "compute", "split", "trim", "push",
+];
+
+/// Settings for generated motion drills
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DrillOptions {
+    /// Simulator commands the drill practises, e.g. `["w", "b", "e"]`
+    pub motions: Vec<String>,
+    /// Lines of generated text
+    pub lines: usize,
+    /// Motions in the random walk that picks the target
+    pub steps: usize,
+}
+
+impl Default for DrillOptions {
+    fn default() -> Self {
+        Self {
+            motions: ["w", "b", "e"].iter().map(|m| m.to_string()).collect(),
+            lines: 4,
+            steps: 3,
+        }
+    }
+}
+
+/// SplitMix64, a small seeded generator that is plenty for picking words
+pub(crate) struct SeededRng(u64);
+
+impl SeededRng {
+    pub(crate) fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Value below `bound`; the modulo bias is negligible for these sizes
+    pub(crate) fn below(&mut self, bound: u64) -> u64 {
+        self.next_u64() % bound
+    }
+
+    /// A random element of a non-empty slice
+    fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len() as u64) as usize]
+    }
+}
+
+/// Generate one motion drill
+///
+/// The same options and seed always give the same drill.
+///
+/// # Errors
+///
+/// Returns `SecurityError::InvalidInput` if a motion is not one of
+/// `DRILL_MOTIONS`, if there are no motions, lines or steps, or if no walk
+/// from any tried start moves the cursor.
+pub fn generate_motion_drill(options: &DrillOptions, seed: u64) -> Result<Scenario, SecurityError> {
+    if options.motions.is_empty() || options.lines == 0 || options.steps == 0 {
+        return Err(SecurityError::InvalidInput(
+            "A drill needs motions, lines and steps".to_string(),
+        ));
+    }
+    let motions = options
+        .motions
+        .iter()
+        .map(|motion| {
+            DRILL_MOTIONS
+                .iter()
+                .find(|(command, _)| command == motion)
+                .copied()
+                .ok_or_else(|| {
+                    SecurityError::InvalidInput(format!("'{}' is not a drill motion", motion))
+                })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut rng = SeededRng::new(seed);
+    let content = generate_text(&mut rng, options.lines);
+    let lines: Vec<&str> = content.lines().collect();
+
+    for _ in 0..MAX_ATTEMPTS {
+        let row = rng.below(lines.len() as u64) as usize;
+        let col = rng.below(lines[row].chars().count() as u64) as usize;
+        let start = EditorState::from_setup(&content, [row, col])?;
+        let mut simulator = HelixSimulator::from_editor_state(&start);
+
+        let mut walk = Vec::new();
+        for _ in 0..options.steps {
+            let (command, keys) = *rng.pick(&motions);
+            simulator
+                .execute_command(command)
+                .map_err(|_| SecurityError::InvalidInput(format!("'{}' failed", command)))?;
+            walk.extend(keys.iter().map(|key| key.to_string()));
+        }
+        let end = simulator
+            .get_state()
+            .map_err(|_| SecurityError::InvalidInput("Invalid drill state".to_string()))?
+            .cursor_position();
+        if (end.row, end.col) == (row, col) {
+            continue;
+        }
+
+        let mut scenario = drill_scenario(seed, options, &content, (row, col), (end.row, end.col));
+        let commands: Vec<&str> = motions.iter().map(|(command, _)| *command).collect();
+        let budget = SearchBudget {
+            max_keys: walk.len(),
+            max_states: 20_000,
+            max_duration: Duration::from_secs(1),
+        };
+        // The walk itself is a solution when the search gives up
+        let solution = match find_shortest_solution_using(&scenario, &budget, &commands) {
+            SearchOutcome::Found(keys) => keys,
+            SearchOutcome::NotFound | SearchOutcome::OutOfBudget => walk,
+        };
+        scenario.scoring.optimal_count = solution.len();
+        scenario.solution.commands = solution;
+        return Ok(scenario);
+    }
+
+    Err(SecurityError::InvalidInput(
+        "No drill target found for these motions".to_string(),
+    ))
+}
+
+/// Random lines that look like code
+fn generate_text(rng: &mut SeededRng, lines: usize) -> String {
+    (0..lines)
+        .map(|_| {
+            let a = rng.pick(&NAMES);
+            let b = rng.pick(&NAMES);
+            let call = rng.pick(&CALLS);
+            let n = rng.below(100);
+            match rng.below(5) {
+                0 => format!("let {a} = {call}({b}, {n});"),
+                1 => format!("if {a} > {n} {{ return {b}; }}"),
+                2 => format!("{a}.{call}({b});"),
+                3 => format!("fn {call}_{a}({b}: usize) -> usize {{"),
+                _ => format!("for {a} in {b}.iter() {{ {call}({a}); }}"),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Drill scenario without its solution
+fn drill_scenario(
+    seed: u64,
+    options: &DrillOptions,
+    content: &str,
+    start: (usize, usize),
+    target: (usize, usize),
+) -> Scenario {
+    let motions = options.motions.join(" ");
+    Scenario {
+        id: format!("motion_drill_{}", seed % 1_000_000),
+        name: t!("drill.name").to_string(),
+        description: t!("drill.description", motions = motions).to_string(),
+        category: Some("movement".to_string()),
+        difficulty: None,
+        tags: vec!["drill".to_string()],
+        requires: Vec::new(),
+        commands_taught: options.motions.clone(),
+        forbidden_commands: Vec::new(),
+        required_commands: Vec::new(),
+        estimated_seconds: None,
+        setup: Setup {
+            file_content: content.to_string(),
+            cursor_position: Some(start),
+            selections: Vec::new(),
+            primary_selection: 0,
+            mode: SetupMode::Normal,
+            registers: BTreeMap::new(),
+            last_action: Vec::new(),
+            indent_unit: None,
+        },
+        target: Some(TargetState {
+            file_content: content.to_string(),
+            cursor_position: Some(target),
+            ..TargetState::default()
+        }),
+        solution: Solution {
+            commands: Vec::new(),
+            description: t!("drill.solution").to_string(),
+        },
+        alternatives: Vec::new(),
+        hints: vec![t!("drill.hint", motions = motions).to_string()],
+        scoring: ScoringConfig {
+            optimal_count: 0,
+            max_points: 100,
+            tolerance: 2,
+        },
+        markers: false,
+        template: None,
+        steps: Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::GameSession;
+    use crate::helix::keys::push_key;
+
+    #[test]
+    fn test_drill_is_seeded() {
+        let options = DrillOptions::default();
+        let first = generate_motion_drill(&options, 7).unwrap();
+        let again = generate_motion_drill(&options, 7).unwrap();
+        assert_eq!(first.setup.file_content, again.setup.file_content);
+        assert_eq!(
+            first.target.as_ref().unwrap().cursor_position,
+            again.target.as_ref().unwrap().cursor_position
+        );
+        assert_eq!(first.solution.commands, again.solution.commands);
+    }
+
+    #[test]
+    fn test_drill_solution_uses_motions_and_reaches_target() {
+        let options = DrillOptions {
+            motions: vec!["w".to_string(), "b".to_string(), "j".to_string()],
+            ..DrillOptions::default()
+        };
+        for seed in 0..20 {
+            let scenario = generate_motion_drill(&options, seed).unwrap();
+            assert_eq!(
+                scenario.setup.file_content,
+                scenario.target.as_ref().unwrap().file_content
+            );
+            assert_ne!(
+                scenario.setup.cursor_position,
+                scenario.target.as_ref().unwrap().cursor_position
+            );
+            assert_eq!(
+                scenario.scoring.optimal_count,
+                scenario.solution.commands.len()
+            );
+            assert!(
+                scenario.solution.commands.len() <= options.steps,
+                "{:?}",
+                scenario.solution.commands
+            );
+
+            let mut session = GameSession::new(scenario.clone()).unwrap();
+            let mut buffer = String::new();
+            for key in &scenario.solution.commands {
+                assert!(["w", "b", "j"].contains(&key.as_str()));
+                if let Some(command) = push_key(&mut buffer, key) {
+                    session.record_action(command).unwrap();
+                }
+            }
+            assert!(session.is_completed(), "seed {seed}");
+        }
+    }
+
+    #[test]
+    fn test_drill_rejects_unknown_motions() {
+        let options = DrillOptions {
+            motions: vec!["x".to_string()],
+            ..DrillOptions::default()
+        };
+        assert!(generate_motion_drill(&options, 1).is_err());
+        let options = DrillOptions {
+            motions: Vec::new(),
+            ..DrillOptions::default()
+        };
+        assert!(generate_motion_drill(&options, 1).is_err());
+    }
+}
@@ -4,9 +4,13 @@
 //! user action tracking, and scoring.
 
 pub mod editor_state;
+pub mod generator;
 pub mod scorer;
 pub mod session;
+pub mod solver;
 
 pub use editor_state::{CursorPosition, EditorState, Selection};
+pub use generator::{DrillOptions, generate_motion_drill};
 pub use scorer::{PerformanceRating, Scorer};
-pub use session::{Feedback, GameSession, SessionState, UserAction};
+pub use session::{Feedback, GameSession, SessionState, StepFeedback, UserAction};
+pub use solver::{SearchBudget, SearchOutcome, find_shortest_solution};
@@ -30,9 +30,10 @@
 //! # Ok::<(), helix_trainer::security::UserError>(())
 //! ```
 
-use crate::config::Scenario;
-use crate::game::{EditorState, PerformanceRating, Scorer};
-use crate::helix::{HelixSimulator, Mode};
+use crate::config::{Completion, Scenario, ScoringConfig, SelectionRange, SetupMode, TargetState};
+use crate::game::{CursorPosition, EditorState, PerformanceRating, Scorer, Selection};
+use crate::helix::commands::CMD_REPLACE;
+use crate::helix::{HelixSimulator, Mode, Viewport};
 use crate::security::{self, SecurityError, UserError};
 use serde::{Deserialize, Serialize};
 use std::cell::Cell;
@@ -95,8 +96,10 @@ pub enum SessionState {
 pub struct Feedback {
     /// Whether the scenario was completed successfully
     pub success: bool,
-    /// Score earned (0 to max_points)
+    /// Score earned (0 to max_points), after the hint penalty
     pub score: u32,
+    /// Points deducted for the hints shown
+    pub hint_penalty: u32,
     /// Maximum possible points for this scenario
     pub max_points: u32,
     /// Performance rating (Perfect, Excellent, Good, Fair, Poor)
@@ -111,6 +114,29 @@ pub struct Feedback {
     pub hint: Option<String>,
     /// Whether user achieved optimal solution
     pub is_optimal: bool,
+    /// Results of each completed lesson step, in order
+    pub steps: Vec<StepFeedback>,
+    /// Forbidden commands that were tried, in order
+    pub blocked_commands: Vec<String>,
+    /// Required commands that were never used
+    pub missing_commands: Vec<String>,
+}
+
+/// Result of one completed step of a lesson
+///
+/// Scenarios without further steps have a single step.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StepFeedback {
+    /// Instructions the step was played with
+    pub instructions: String,
+    /// Score earned for the step (0 to max_points)
+    pub score: u32,
+    /// Maximum possible points for the step
+    pub max_points: u32,
+    /// Actions taken during the step
+    pub actions_taken: usize,
+    /// Optimal number of actions for the step
+    pub optimal_actions: usize,
 }
 
 impl Feedback {
@@ -128,6 +154,7 @@ impl Feedback {
     /// let feedback = Feedback {
     ///     success: true,
     ///     score: 100,
+    ///     hint_penalty: 0,
     ///     max_points: 100,
     ///     rating: PerformanceRating::Perfect,
     ///     actions_taken: 2,
@@ -135,6 +162,9 @@ impl Feedback {
     ///     duration: Duration::from_secs(5),
     ///     hint: None,
     ///     is_optimal: true,
+    ///     steps: Vec::new(),
+    ///     blocked_commands: Vec::new(),
+    ///     missing_commands: Vec::new(),
     /// };
     /// let summary = feedback.summary();
     /// assert!(summary.contains("100/100"));
@@ -172,8 +202,16 @@ pub struct GameSession {
     scenario: Scenario,
     /// Initial state from scenario setup
     initial_state: EditorState,
-    /// Target state to achieve
+    /// Target of the current step
+    target: TargetState,
+    /// Editor state built from `target`
     target_state: EditorState,
+    /// Index of the current lesson step (0 for the scenario itself)
+    step: usize,
+    /// Number of actions taken before the current step began
+    step_started: usize,
+    /// Results of the completed steps
+    step_results: Vec<StepFeedback>,
     /// Current editor state
     current_state: EditorState,
     /// Helix editor simulator for command execution
@@ -186,8 +224,16 @@ pub struct GameSession {
     completed_at: Option<Instant>,
     /// Current session state (Active, Completed, or Abandoned)
     state: SessionState,
-    /// Number of hints shown to user
+    /// Number of hints of the current step shown to user
     hints_shown: usize,
+    /// Number of hints requested in all steps, which the penalty applies to
+    hints_used: usize,
+    /// Points deducted per hint shown
+    hint_penalty: u32,
+    /// Forbidden commands the user tried, which were not executed
+    blocked_commands: Vec<String>,
+    /// Whether each of the scenario's required commands has been used
+    required_used: Vec<bool>,
     /// Cached completion progress percentage (0-100)
     cached_progress: Cell<Option<u8>>,
     /// Flag indicating if progress cache needs update
@@ -218,37 +264,31 @@ impl GameSession {
     /// # Ok::<(), helix_trainer::security::UserError>(())
     /// ```
     pub fn new(scenario: Scenario) -> Result<Self, UserError> {
-        // Create initial state from scenario setup
-        let initial_state = EditorState::from_setup(
-            &scenario.setup.file_content,
-            [
-                scenario.setup.cursor_position.0,
-                scenario.setup.cursor_position.1,
-            ],
-        )
-        .map_err(|_| UserError::ScenarioTooComplex)?;
+        // Create initial state and simulator from scenario setup
+        let (initial_state, simulator) =
+            Self::initial_setup(&scenario).map_err(|_| UserError::ScenarioTooComplex)?;
 
         // Create target state with optional selection
-        let target_state = EditorState::from_target(
-            &scenario.target.file_content,
-            [
-                scenario.target.cursor_position.0,
-                scenario.target.cursor_position.1,
-            ],
-            scenario.target.selection,
-        )
-        .map_err(|_| UserError::ScenarioTooComplex)?;
+        let target = scenario
+            .target
+            .clone()
+            .ok_or(UserError::ScenarioTooComplex)?;
+        let target_state =
+            Self::build_target(&target).map_err(|_| UserError::ScenarioTooComplex)?;
 
         // Clone initial state as current state
         let current_state = initial_state.clone();
 
-        // Initialize Helix simulator from initial state (includes cursor position)
-        let simulator = HelixSimulator::from_editor_state(&initial_state);
+        let required_used = vec![false; scenario.required_commands.len()];
 
         Ok(Self {
             scenario,
             initial_state,
+            target,
             target_state,
+            step: 0,
+            step_started: 0,
+            step_results: Vec::new(),
             current_state,
             simulator,
             user_actions: Vec::new(),
@@ -256,11 +296,96 @@ impl GameSession {
             completed_at: None,
             state: SessionState::Active,
             hints_shown: 0,
+            hints_used: 0,
+            hint_penalty: 0,
+            blocked_commands: Vec::new(),
+            required_used,
             cached_progress: Cell::new(None),
             progress_needs_update: Cell::new(true),
         })
     }
 
+    /// Build the editor state described by the scenario target
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the scenario has no target, or the target cursor
+    /// or selections fall outside the target content.
+    pub fn build_target_state(scenario: &Scenario) -> Result<EditorState, SecurityError> {
+        let target = scenario
+            .target
+            .as_ref()
+            .ok_or_else(|| SecurityError::InvalidInput("Scenario has no target".to_string()))?;
+        Self::build_target(target)
+    }
+
+    /// Build the editor state described by a scenario or lesson step target
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the target cursor or selections fall outside the
+    /// target content.
+    pub fn build_target(target: &TargetState) -> Result<EditorState, SecurityError> {
+        let (row, col) = target
+            .cursor_position
+            .ok_or(SecurityError::InvalidCursorPosition)?;
+        let state = EditorState::from_target(&target.file_content, [row, col], target.selection)?;
+        if target.selections.is_empty() {
+            return Ok(state);
+        }
+        state.with_selections(to_selections(&target.selections)?, target.primary_selection)
+    }
+
+    /// Build the editor state and simulator described by the scenario setup
+    ///
+    /// Applies the setup's selections, registers, pre-recorded `.` action,
+    /// indent unit and starting mode on top of its content and cursor.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the setup does not fit its content or its
+    /// `last_action` cannot be replayed.
+    pub fn initial_setup(
+        scenario: &Scenario,
+    ) -> Result<(EditorState, HelixSimulator), SecurityError> {
+        let setup = &scenario.setup;
+        let (row, col) = setup
+            .cursor_position
+            .ok_or(SecurityError::InvalidCursorPosition)?;
+        let mut initial_state = EditorState::from_setup(&setup.file_content, [row, col])?;
+        if !setup.selections.is_empty() {
+            initial_state = initial_state
+                .with_selections(to_selections(&setup.selections)?, setup.primary_selection)?;
+        }
+
+        let invalid_setup =
+            |what: &str| SecurityError::InvalidInput(format!("Invalid setup {what}"));
+        let mut simulator = HelixSimulator::from_editor_state(&initial_state);
+        if !setup.selections.is_empty() {
+            simulator
+                .set_selections(initial_state.selections(), setup.primary_selection)
+                .map_err(|_| invalid_setup("selections"))?;
+        }
+        for (name, values) in &setup.registers {
+            simulator.set_register(*name, values.clone());
+        }
+        if !setup.last_action.is_empty() {
+            simulator
+                .seed_last_action(&setup.last_action)
+                .map_err(|_| invalid_setup("last_action"))?;
+        }
+        if let Some(unit) = &setup.indent_unit {
+            simulator.set_indent_unit(unit);
+        }
+        simulator.set_mode(match setup.mode {
+            SetupMode::Normal => Mode::Normal,
+            SetupMode::Select => Mode::Select,
+            SetupMode::Insert => Mode::Insert,
+        });
+
+        Ok((initial_state, simulator))
+    }
+
     /// Get reference to the scenario being played
     ///
     /// # Examples
@@ -308,6 +433,75 @@ impl GameSession {
         &self.target_state
     }
 
+    /// Number of steps in the scenario, 1 unless it is a lesson
+    pub fn step_count(&self) -> usize {
+        self.scenario.steps.len() + 1
+    }
+
+    /// Index of the step being played, starting at 0
+    ///
+    /// Stays on the last step once the session is completed.
+    pub fn current_step(&self) -> usize {
+        self.step
+    }
+
+    /// Instructions for the current step
+    ///
+    /// The first step uses the scenario description.
+    pub fn instructions(&self) -> &str {
+        match self.step {
+            0 => &self.scenario.description,
+            step => &self.scenario.steps[step - 1].instructions,
+        }
+    }
+
+    /// Scoring configuration of the current step
+    pub fn step_scoring(&self) -> &ScoringConfig {
+        match self.step {
+            0 => &self.scenario.scoring,
+            step => &self.scenario.steps[step - 1].scoring,
+        }
+    }
+
+    /// Get the number of actions taken in the current step
+    pub fn step_action_count(&self) -> usize {
+        self.user_actions.len() - self.step_started
+    }
+
+    /// Results of the steps completed so far
+    pub fn step_results(&self) -> &[StepFeedback] {
+        &self.step_results
+    }
+
+    /// Forbidden commands tried so far
+    pub fn blocked_commands(&self) -> &[String] {
+        &self.blocked_commands
+    }
+
+    /// Required commands not used so far
+    pub fn missing_commands(&self) -> Vec<&str> {
+        self.scenario
+            .required_commands
+            .iter()
+            .zip(&self.required_used)
+            .filter(|(_, used)| !**used)
+            .map(|(command, _)| command.as_str())
+            .collect()
+    }
+
+    /// Target of the current step
+    pub fn current_target(&self) -> &TargetState {
+        &self.target
+    }
+
+    /// Hints of the current step
+    fn step_hints(&self) -> &[String] {
+        match self.step {
+            0 => &self.scenario.hints,
+            step => &self.scenario.steps[step - 1].hints,
+        }
+    }
+
     /// Get the current session state
     ///
     /// Returns Active, Completed, or Abandoned.
@@ -330,11 +524,41 @@ impl GameSession {
         self.simulator.mode() == Mode::Insert
     }
 
+    /// Check if the simulator is in Select mode
+    pub fn is_select_mode(&self) -> bool {
+        self.simulator.mode() == Mode::Select
+    }
+
+    /// Get the simulator's viewport (visible window of the current state)
+    pub fn viewport(&self) -> Viewport {
+        self.simulator.viewport()
+    }
+
+    /// Resize the viewport to the editor pane height reported by the UI
+    pub fn set_viewport_height(&mut self, height: usize) {
+        self.simulator.set_viewport_height(height);
+    }
+
+    /// Set the indentation `>` adds and `<` removes
+    ///
+    /// Does nothing when the scenario setup pins its own unit.
+    pub fn set_indent_unit(&mut self, unit: &str) {
+        if self.scenario.setup.indent_unit.is_none() {
+            self.simulator.set_indent_unit(unit);
+        }
+    }
+
+    /// Set the points deducted from the score for each hint shown
+    pub fn set_hint_penalty(&mut self, points: u32) {
+        self.hint_penalty = points;
+    }
+
     /// Get current editor mode as string for UI display
     pub fn mode_name(&self) -> &str {
         match self.simulator.mode() {
             Mode::Normal => "NORMAL",
             Mode::Insert => "INSERT",
+            Mode::Select => "SELECT",
         }
     }
 
@@ -389,6 +613,10 @@ impl GameSession {
     /// executes the command through the Helix simulator, and synchronizes
     /// the editor state with the simulator's internal state.
     ///
+    /// Outside insert mode, a command the scenario forbids is not executed
+    /// but still counts as an action, and required commands are marked as
+    /// used.
+    ///
     /// # Errors
     ///
     /// Returns `SecurityError::TooManyActions` if action count would
@@ -409,8 +637,33 @@ impl GameSession {
         security::arithmetic::validate_action_count(self.user_actions.len() + 1)
             .map_err(UserError::from)?;
 
+        // Constraints apply to commands, not to text typed in insert mode
+        let constrained = self.simulator.mode() != Mode::Insert;
+        if constrained
+            && self
+                .scenario
+                .forbidden_commands
+                .iter()
+                .any(|name| is_command(&command, name))
+        {
+            self.blocked_commands.push(command.clone());
+            let elapsed = self.elapsed();
+            self.user_actions.push(UserAction::new(command, elapsed));
+            return Ok(());
+        }
+
         // Execute command through simulator
         self.simulator.execute_command(&command)?;
+        if constrained {
+            for (name, used) in self
+                .scenario
+                .required_commands
+                .iter()
+                .zip(&mut self.required_used)
+            {
+                *used |= is_command(&command, name);
+            }
+        }
 
         // Sync current state with simulator
         self.current_state = self.simulator.to_editor_state()?;
@@ -423,11 +676,8 @@ impl GameSession {
         let action = UserAction::new(command, elapsed);
         self.user_actions.push(action);
 
-        // Check if scenario is completed
-        if self.check_completion() {
-            self.state = SessionState::Completed;
-            self.completed_at = Some(Instant::now());
-        }
+        // Check if the step, and with the last step the scenario, is completed
+        self.advance_steps()?;
 
         Ok(())
     }
@@ -456,19 +706,53 @@ impl GameSession {
     pub fn update_state(&mut self, new_state: EditorState) -> Result<(), SecurityError> {
         self.current_state = new_state;
 
-        // Check if scenario is completed
-        if self.check_completion() {
-            self.state = SessionState::Completed;
-            self.completed_at = Some(Instant::now());
-        }
+        // Check if the step, and with the last step the scenario, is completed
+        self.advance_steps()
+    }
 
+    /// Score every step whose target is reached and move on to the next
+    ///
+    /// The simulator carries over between steps, so registers, selections
+    /// and undo history persist. After the last step the session is
+    /// marked as completed.
+    fn advance_steps(&mut self) -> Result<(), SecurityError> {
+        while self.is_active() && self.check_completion() {
+            let scoring = self.step_scoring();
+            let actions_taken = self.step_action_count();
+            // A step already solved when it begins needs no keys at all
+            let score = match actions_taken {
+                0 => scoring.max_points,
+                _ => Scorer::score_with_config(scoring, actions_taken)?,
+            };
+            let result = StepFeedback {
+                instructions: self.instructions().to_string(),
+                score,
+                max_points: scoring.max_points,
+                actions_taken,
+                optimal_actions: scoring.optimal_count,
+            };
+            self.step_results.push(result);
+
+            if self.step + 1 < self.step_count() {
+                self.step += 1;
+                self.step_started = self.user_actions.len();
+                self.hints_shown = 0;
+                self.target = self.scenario.steps[self.step - 1].target.clone();
+                self.target_state = Self::build_target(&self.target)?;
+                self.progress_needs_update.set(true);
+            } else {
+                self.state = SessionState::Completed;
+                self.completed_at = Some(Instant::now());
+            }
+        }
         Ok(())
     }
 
-    /// Check if the scenario is completed successfully
+    /// Check if the current step's target is reached
     ///
-    /// Compares current state with target state (both content and cursor).
-    /// Returns true only if they match exactly.
+    /// Applies the target's completion rule, exact content and cursor
+    /// unless the scenario picks another. Targets with `match_selection`
+    /// also require the selections to match.
     ///
     /// # Examples
     ///
@@ -480,7 +764,35 @@ impl GameSession {
     /// # Ok::<(), helix_trainer::security::UserError>(())
     /// ```
     pub fn check_completion(&self) -> bool {
-        self.current_state.matches(&self.target_state)
+        Self::target_reached(
+            self.current_target(),
+            &self.current_state,
+            &self.target_state,
+        )
+    }
+
+    /// Check a state against a target under the target's completion rule
+    ///
+    /// `target_state` is the editor state built from `target`.
+    pub fn target_reached(
+        target: &TargetState,
+        state: &EditorState,
+        target_state: &EditorState,
+    ) -> bool {
+        let reached = match &target.completion {
+            Completion::Exact => state.matches(target_state),
+            Completion::Content => state.content_matches(target_state),
+            Completion::Cursor => state.cursor_position() == target_state.cursor_position(),
+            Completion::Selection => state.selections_match(target_state),
+            Completion::Regex { pattern } => pattern.is_match(state.content()),
+            Completion::Line { line, text } => {
+                state.content().lines().nth(*line) == Some(text.as_str())
+            }
+            Completion::TrimmedContent => {
+                trimmed_lines(state.content()).eq(trimmed_lines(target_state.content()))
+            }
+        };
+        reached && (!target.match_selection || state.selections_match(target_state))
     }
 
     /// Check if content matches target (ignoring cursor position)
@@ -505,7 +817,7 @@ impl GameSession {
 
     /// Get the next available hint
     ///
-    /// Returns hints in order from the scenario. Once all hints are
+    /// Returns hints of the current step in order. Once all hints are
     /// shown, subsequent calls return None.
     ///
     /// # Examples
@@ -520,13 +832,18 @@ impl GameSession {
     /// # Ok::<(), helix_trainer::security::UserError>(())
     /// ```
     pub fn get_hint(&mut self) -> Option<String> {
-        if self.hints_shown < self.scenario.hints.len() {
-            let hint = self.scenario.hints[self.hints_shown].clone();
-            self.hints_shown += 1;
-            Some(hint)
-        } else {
-            None
-        }
+        let hint = self.get_free_hint()?;
+        self.hints_used += 1;
+        Some(hint)
+    }
+
+    /// Get the next available hint without a score penalty
+    ///
+    /// For hints the trainer shows on its own rather than on request.
+    pub fn get_free_hint(&mut self) -> Option<String> {
+        let hint = self.step_hints().get(self.hints_shown)?.clone();
+        self.hints_shown += 1;
+        Some(hint)
     }
 
     /// Abandon the session (give up)
@@ -551,7 +868,8 @@ impl GameSession {
     /// Calculate the final score for this session
     ///
     /// Applies the scenario's scoring configuration to the actual
-    /// number of actions taken. Returns 0 if session is not completed.
+    /// number of actions taken; a lesson scores the sum of its steps.
+    /// Returns 0 if session is not completed.
     ///
     /// # Errors
     ///
@@ -573,13 +891,19 @@ impl GameSession {
             return Ok(0);
         }
 
-        Scorer::score_with_config(&self.scenario.scoring, self.user_actions.len())
+        self.step_results.iter().try_fold(0u32, |total, step| {
+            total
+                .checked_add(step.score)
+                .ok_or(SecurityError::ScoreOverflow)
+        })
     }
 
     /// Get detailed feedback for the session
     ///
     /// Generates comprehensive feedback including score, performance
-    /// rating, hint if needed, and optimality assessment.
+    /// rating, hint if needed, and optimality assessment. A completed
+    /// session that never used a required command counts as failed, and
+    /// tried forbidden commands rule out an optimal result.
     ///
     /// # Errors
     ///
@@ -597,12 +921,30 @@ impl GameSession {
     /// # Ok::<(), helix_trainer::security::UserError>(())
     /// ```
     pub fn get_feedback(&self) -> Result<Feedback, SecurityError> {
-        let success = self.state == SessionState::Completed;
+        let missing_commands: Vec<String> = self
+            .missing_commands()
+            .into_iter()
+            .map(String::from)
+            .collect();
+        let success = self.state == SessionState::Completed && missing_commands.is_empty();
         let actions_taken = self.user_actions.len();
-        let optimal_actions = self.scenario.scoring.optimal_count;
-        let max_points = self.scenario.scoring.max_points;
+        let scorings = std::iter::once(&self.scenario.scoring)
+            .chain(self.scenario.steps.iter().map(|step| &step.scoring));
+        let (mut optimal_actions, mut max_points, mut tolerance) = (0usize, 0u32, 0usize);
+        for scoring in scorings {
+            optimal_actions += scoring.optimal_count;
+            max_points = max_points
+                .checked_add(scoring.max_points)
+                .ok_or(SecurityError::ScoreOverflow)?;
+            tolerance += scoring.tolerance;
+        }
 
         let score = if success { self.calculate_score()? } else { 0 };
+        let hint_penalty = u32::try_from(self.hints_used)
+            .unwrap_or(u32::MAX)
+            .saturating_mul(self.hint_penalty)
+            .min(score);
+        let score = score - hint_penalty;
 
         let rating = Scorer::get_rating(score, max_points);
         let duration = if let Some(completed_at) = self.completed_at {
@@ -612,7 +954,9 @@ impl GameSession {
         };
 
         // Provide hint if user struggled (took >2x optimal actions)
-        let hint = if success && actions_taken > optimal_actions * 2 {
+        let hint = if !missing_commands.is_empty() && self.is_completed() {
+            Some(format!("Required: {}", missing_commands.join(", ")))
+        } else if success && actions_taken > optimal_actions * 2 {
             Some(format!(
                 "Try using: {}. {}",
                 self.scenario.solution.commands.join(", "),
@@ -622,11 +966,13 @@ impl GameSession {
             None
         };
 
-        let is_optimal = actions_taken <= optimal_actions + self.scenario.scoring.tolerance;
+        let is_optimal =
+            actions_taken <= optimal_actions + tolerance && self.blocked_commands.is_empty();
 
         Ok(Feedback {
             success,
             score,
+            hint_penalty,
             max_points,
             rating,
             actions_taken,
@@ -634,6 +980,9 @@ impl GameSession {
             duration,
             hint,
             is_optimal,
+            steps: self.step_results.clone(),
+            blocked_commands: self.blocked_commands.clone(),
+            missing_commands,
         })
     }
 
@@ -660,14 +1009,27 @@ impl GameSession {
     /// # Ok::<(), helix_trainer::security::UserError>(())
     /// ```
     pub fn reset(&mut self) -> Result<(), SecurityError> {
+        // Rebuild the simulator from the setup, keeping the UI-provided view
+        // height and the configured indent unit
+        let (initial_state, mut simulator) = Self::initial_setup(&self.scenario)?;
+        simulator.set_viewport_height(self.simulator.viewport().height());
+        simulator.set_indent_unit(self.simulator.indent_unit());
+        self.initial_state = initial_state;
         self.current_state = self.initial_state.clone();
-        // Reset simulator to initial content
-        self.simulator = HelixSimulator::new(self.scenario.setup.file_content.clone());
+        self.target_state = Self::build_target_state(&self.scenario)?;
+        self.target = self.scenario.target.clone().unwrap_or_default();
+        self.step = 0;
+        self.step_started = 0;
+        self.step_results.clear();
+        self.simulator = simulator;
         self.user_actions.clear();
         self.started_at = Instant::now();
         self.completed_at = None;
         self.state = SessionState::Active;
         self.hints_shown = 0;
+        self.hints_used = 0;
+        self.blocked_commands.clear();
+        self.required_used.fill(false);
         // Reset progress cache
         self.cached_progress.set(None);
         self.progress_needs_update.set(true);
@@ -729,5 +1091,34 @@ impl GameSession {
     }
 }
 
+/// Lines without trailing whitespace, leaving out trailing blank lines
+fn trimmed_lines(content: &str) -> impl Iterator<Item = &str> {
+    content.trim_end().lines().map(str::trim_end)
+}
+
+/// Whether a simulator command is the constraint `name`
+///
+/// Replace commands carry their character (`rx`), so they match `r`.
+pub(crate) fn is_command(command: &str, name: &str) -> bool {
+    command == name
+        || (name == CMD_REPLACE
+            && command
+                .strip_prefix(CMD_REPLACE)
+                .is_some_and(|c| c.chars().count() == 1))
+}
+
+/// Convert scenario selection ranges to editor selections (anchor to start, head to end)
+fn to_selections(ranges: &[SelectionRange]) -> Result<Vec<Selection>, SecurityError> {
+    ranges
+        .iter()
+        .map(|sel| {
+            Ok(Selection::new(
+                CursorPosition::new(sel.anchor.0, sel.anchor.1)?,
+                CursorPosition::new(sel.head.0, sel.head.1)?,
+            ))
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests;
@@ -1,22 +1,43 @@
 //! Tests for GameSession
 
 use super::*;
-use crate::config::{ScoringConfig, Setup, Solution, TargetState};
+use crate::config::{
+    Completion, LessonStep, ScoringConfig, SelectionRange, Setup, SetupMode, Solution, TargetState,
+};
+use std::collections::BTreeMap;
 
 fn create_test_scenario() -> Scenario {
     Scenario {
         id: "test_001".to_string(),
         name: "Test Scenario".to_string(),
         description: "A test scenario".to_string(),
+        category: None,
+        difficulty: None,
+        tags: Vec::new(),
+        requires: Vec::new(),
+        commands_taught: Vec::new(),
+        forbidden_commands: Vec::new(),
+        required_commands: Vec::new(),
+        estimated_seconds: None,
         setup: Setup {
             file_content: "line 1\nline 2\nline 3\n".to_string(),
-            cursor_position: (0, 0),
+            cursor_position: Some((0, 0)),
+            selections: Vec::new(),
+            primary_selection: 0,
+            mode: SetupMode::Normal,
+            registers: BTreeMap::new(),
+            last_action: Vec::new(),
+            indent_unit: None,
         },
-        target: TargetState {
+        target: Some(TargetState {
             file_content: "line 2\nline 3\n".to_string(),
-            cursor_position: (0, 0),
+            cursor_position: Some((0, 0)),
             selection: None,
-        },
+            selections: Vec::new(),
+            primary_selection: 0,
+            match_selection: false,
+            completion: Completion::Exact,
+        }),
         solution: Solution {
             commands: vec!["d".to_string(), "d".to_string()],
             description: "Delete first line".to_string(),
@@ -28,6 +49,9 @@ fn create_test_scenario() -> Scenario {
             max_points: 100,
             tolerance: 0,
         },
+        markers: false,
+        template: None,
+        steps: Vec::new(),
     }
 }
 
@@ -200,6 +224,7 @@ fn test_feedback_summary() {
     let feedback = Feedback {
         success: true,
         score: 100,
+        hint_penalty: 0,
         max_points: 100,
         rating: PerformanceRating::Perfect,
         actions_taken: 2,
@@ -207,6 +232,9 @@ fn test_feedback_summary() {
         duration: Duration::from_secs(5),
         hint: None,
         is_optimal: true,
+        steps: Vec::new(),
+        blocked_commands: Vec::new(),
+        missing_commands: Vec::new(),
     };
 
     let summary = feedback.summary();
@@ -214,6 +242,29 @@ fn test_feedback_summary() {
     assert!(summary.contains("2 actions"));
 }
 
+#[test]
+fn test_hint_penalty_reduces_score() {
+    let mut session = GameSession::new(create_test_scenario()).unwrap();
+    session.set_hint_penalty(30);
+    assert!(session.get_hint().is_some());
+    session.record_action("dd".to_string()).unwrap();
+
+    let feedback = session.get_feedback().unwrap();
+    assert_eq!(feedback.hint_penalty, 30);
+    assert_eq!(feedback.score, feedback.max_points - 30);
+
+    // Retrying forgets the hints of the previous attempt
+    session.reset().unwrap();
+    session.record_action("dd".to_string()).unwrap();
+    assert_eq!(session.get_feedback().unwrap().hint_penalty, 0);
+
+    // Hints shown without being asked for cost nothing
+    session.reset().unwrap();
+    assert!(session.get_free_hint().is_some());
+    session.record_action("dd".to_string()).unwrap();
+    assert_eq!(session.get_feedback().unwrap().hint_penalty, 0);
+}
+
 #[test]
 fn test_timer_fixed_on_completion() {
     let scenario = create_test_scenario();
@@ -240,3 +291,354 @@ fn test_timer_fixed_on_completion() {
         "Timer should be fixed after completion"
     );
 }
+
+fn create_selection_scenario() -> Scenario {
+    let mut scenario = create_test_scenario();
+    scenario.setup = Setup {
+        file_content: "foo bar".to_string(),
+        cursor_position: Some((0, 0)),
+        selections: Vec::new(),
+        primary_selection: 0,
+        mode: SetupMode::Normal,
+        registers: BTreeMap::new(),
+        last_action: Vec::new(),
+        indent_unit: None,
+    };
+    scenario.target = Some(TargetState {
+        file_content: "foo bar".to_string(),
+        cursor_position: Some((0, 3)),
+        selection: None,
+        selections: vec![SelectionRange {
+            anchor: (0, 0),
+            head: (0, 3),
+        }],
+        primary_selection: 0,
+        match_selection: true,
+        completion: Completion::Exact,
+    });
+    scenario
+}
+
+#[test]
+fn test_match_selection_requires_selection() {
+    let mut session = GameSession::new(create_selection_scenario()).unwrap();
+
+    // Cursor reaches the target position without selecting anything
+    for _ in 0..3 {
+        session.record_action("l".to_string()).unwrap();
+    }
+    assert!(session.check_content_matches());
+    assert!(!session.is_completed());
+}
+
+#[test]
+fn test_match_selection_completes_with_selection() {
+    let mut session = GameSession::new(create_selection_scenario()).unwrap();
+
+    session.record_action("v".to_string()).unwrap();
+    session.record_action("e".to_string()).unwrap();
+    assert!(session.is_completed());
+}
+
+#[test]
+fn test_setup_with_multiple_selections() {
+    let mut scenario = create_test_scenario();
+    scenario.setup.file_content = "a1\nb2\nc3".to_string();
+    scenario.setup.selections = (0..3)
+        .map(|row| SelectionRange {
+            anchor: (row, 1),
+            head: (row, 2),
+        })
+        .collect();
+    scenario.setup.cursor_position = Some((0, 2));
+    let target = scenario.target.as_mut().unwrap();
+    target.file_content = "a\nb\nc".to_string();
+    target.cursor_position = Some((0, 1));
+
+    let mut session = GameSession::new(scenario).unwrap();
+    assert_eq!(session.current_state().selections().len(), 3);
+
+    session.record_action("x".to_string()).unwrap();
+    assert!(session.is_completed());
+}
+
+#[test]
+fn test_setup_starts_in_insert_mode() {
+    let mut scenario = create_test_scenario();
+    scenario.setup.file_content = "bar".to_string();
+    scenario.setup.mode = SetupMode::Insert;
+    let target = scenario.target.as_mut().unwrap();
+    target.file_content = "foobar".to_string();
+    target.cursor_position = Some((0, 3));
+
+    let mut session = GameSession::new(scenario).unwrap();
+    assert!(session.is_insert_mode());
+
+    session.record_action("foo".to_string()).unwrap();
+    assert!(session.is_completed());
+}
+
+#[test]
+fn test_setup_registers_and_last_action() {
+    let mut scenario = create_test_scenario();
+    scenario.setup.file_content = "foo".to_string();
+    scenario
+        .setup
+        .registers
+        .insert('"', vec!["bar".to_string()]);
+    scenario.setup.last_action = vec!["x".to_string()];
+    let target = scenario.target.as_mut().unwrap();
+    target.file_content = "fbaroo".to_string();
+    target.cursor_position = Some((0, 4));
+
+    let mut session = GameSession::new(scenario.clone()).unwrap();
+    session.record_action("p".to_string()).unwrap();
+    assert!(session.is_completed());
+
+    let target = scenario.target.as_mut().unwrap();
+    target.file_content = "oo".to_string();
+    target.cursor_position = Some((0, 0));
+    let mut session = GameSession::new(scenario).unwrap();
+    session.record_action(".".to_string()).unwrap();
+    assert!(session.is_completed());
+}
+
+#[test]
+fn test_setup_invalid_last_action_rejected() {
+    let mut scenario = create_test_scenario();
+    scenario.setup.last_action = vec!["i".to_string()];
+    assert!(GameSession::new(scenario).is_err());
+}
+
+#[test]
+fn test_reset_restores_setup() {
+    let mut scenario = create_test_scenario();
+    scenario.setup.cursor_position = Some((1, 2));
+    scenario.setup.mode = SetupMode::Select;
+    let mut session = GameSession::new(scenario).unwrap();
+
+    session.record_action("Escape".to_string()).unwrap();
+    session.record_action("gg".to_string()).unwrap();
+    session.reset().unwrap();
+
+    assert!(session.is_select_mode());
+    let state = session.simulator.get_state().unwrap();
+    assert_eq!(state.cursor_position().row, 1);
+    assert_eq!(state.cursor_position().col, 2);
+}
+
+/// Test scenario extended into a lesson: paste the deleted line back, then undo
+fn create_test_lesson() -> Scenario {
+    let step = |instructions: &str, content: &str, key: &str| LessonStep {
+        instructions: instructions.to_string(),
+        target: TargetState {
+            file_content: content.to_string(),
+            cursor_position: Some((1, 0)),
+            ..TargetState::default()
+        },
+        solution: Solution {
+            commands: vec![key.to_string()],
+            description: String::new(),
+        },
+        hints: vec![format!("Press {}", key)],
+        scoring: ScoringConfig {
+            optimal_count: 1,
+            max_points: 50,
+            tolerance: 0,
+        },
+    };
+    Scenario {
+        steps: vec![
+            step("Paste it back", "line 1\nline 2\nline 3\n", "P"),
+            step("Undo the paste", "line 2\nline 3\n", "u"),
+        ],
+        ..create_test_scenario()
+    }
+}
+
+#[test]
+fn test_lesson_steps_share_the_simulator() {
+    let mut session = GameSession::new(create_test_lesson()).unwrap();
+    assert_eq!(session.step_count(), 3);
+    assert_eq!(session.instructions(), "A test scenario");
+
+    session.record_action("dd".to_string()).unwrap();
+    assert!(session.is_active());
+    assert_eq!(session.current_step(), 1);
+    assert_eq!(session.instructions(), "Paste it back");
+    assert_eq!(session.step_action_count(), 0);
+    assert_eq!(session.get_hint().as_deref(), Some("Press P"));
+
+    // The register filled by step 1 and the undo history carry over
+    session.record_action("P".to_string()).unwrap();
+    assert_eq!(session.current_step(), 2);
+    session.record_action("u".to_string()).unwrap();
+    assert!(session.is_completed());
+    assert_eq!(session.current_step(), 2);
+}
+
+#[test]
+fn test_lesson_scores_each_step() {
+    let mut session = GameSession::new(create_test_lesson()).unwrap();
+    for command in ["dd", "j", "k", "P", "u"] {
+        session.record_action(command.to_string()).unwrap();
+    }
+
+    let feedback = session.get_feedback().unwrap();
+    assert!(feedback.success);
+    assert_eq!(feedback.max_points, 200);
+    assert_eq!(feedback.optimal_actions, 4);
+    let scores: Vec<(u32, usize)> = feedback
+        .steps
+        .iter()
+        .map(|step| (step.score, step.actions_taken))
+        .collect();
+    assert_eq!(scores, [(100, 1), (16, 3), (50, 1)]);
+    assert_eq!(feedback.score, 166);
+
+    session.reset().unwrap();
+    assert_eq!(session.current_step(), 0);
+    assert!(session.step_results().is_empty());
+    assert_eq!(session.target_state().content(), "line 2\nline 3\n");
+}
+
+#[test]
+fn test_forbidden_commands_are_blocked() {
+    let scenario = Scenario {
+        forbidden_commands: vec!["j".to_string(), "r".to_string()],
+        ..create_test_scenario()
+    };
+    let mut session = GameSession::new(scenario).unwrap();
+
+    session.record_action("j".to_string()).unwrap();
+    session.record_action("rx".to_string()).unwrap();
+    assert_eq!(session.current_state().cursor_position().row, 0);
+    assert_eq!(
+        session.current_state().content(),
+        "line 1\nline 2\nline 3\n"
+    );
+    assert_eq!(session.blocked_commands(), ["j", "rx"]);
+    assert_eq!(session.action_count(), 2);
+
+    // Typed text is not a command
+    session.record_action("i".to_string()).unwrap();
+    session.record_action("j".to_string()).unwrap();
+    assert_eq!(
+        session.current_state().content(),
+        "jline 1\nline 2\nline 3\n"
+    );
+    assert_eq!(session.blocked_commands().len(), 2);
+
+    session.reset().unwrap();
+    assert!(session.blocked_commands().is_empty());
+}
+
+#[test]
+fn test_forbidden_commands_rule_out_optimal() {
+    let scenario = Scenario {
+        forbidden_commands: vec!["j".to_string()],
+        scoring: ScoringConfig {
+            optimal_count: 2,
+            max_points: 100,
+            tolerance: 1,
+        },
+        ..create_test_scenario()
+    };
+    let mut session = GameSession::new(scenario).unwrap();
+    session.record_action("j".to_string()).unwrap();
+    session.record_action("dd".to_string()).unwrap();
+
+    let feedback = session.get_feedback().unwrap();
+    assert!(feedback.success);
+    assert_eq!(feedback.blocked_commands, ["j"]);
+    assert!(!feedback.is_optimal);
+}
+
+#[test]
+fn test_missing_required_command_fails() {
+    let scenario = Scenario {
+        required_commands: vec![".".to_string()],
+        ..create_test_scenario()
+    };
+    let mut session = GameSession::new(scenario.clone()).unwrap();
+    assert_eq!(session.missing_commands(), ["."]);
+    session.record_action("dd".to_string()).unwrap();
+    assert!(session.is_completed());
+
+    let feedback = session.get_feedback().unwrap();
+    assert!(!feedback.success);
+    assert_eq!(feedback.score, 0);
+    assert_eq!(feedback.missing_commands, ["."]);
+
+    // Repeating the last action counts, even when it changes nothing
+    let mut session = GameSession::new(scenario).unwrap();
+    session.record_action(".".to_string()).unwrap();
+    session.record_action("dd".to_string()).unwrap();
+    assert!(session.missing_commands().is_empty());
+    assert!(session.get_feedback().unwrap().success);
+}
+
+/// Whether `content` with the cursor at `cursor` completes the test scenario
+/// under `completion`
+fn completes_with(completion: Completion, content: &str, cursor: [usize; 2]) -> bool {
+    let mut scenario = create_test_scenario();
+    scenario.target.as_mut().unwrap().completion = completion;
+    let mut session = GameSession::new(scenario).unwrap();
+    session
+        .update_state(EditorState::from_setup(content, cursor).unwrap())
+        .unwrap();
+    session.is_completed()
+}
+
+#[test]
+fn test_completion_rules() {
+    let target = "line 2\nline 3\n";
+    assert!(!completes_with(Completion::Exact, target, [1, 0]));
+    assert!(completes_with(Completion::Content, target, [1, 0]));
+    assert!(!completes_with(Completion::Content, "line 3\n", [0, 0]));
+    assert!(completes_with(Completion::Cursor, "anything\n", [0, 0]));
+    assert!(!completes_with(Completion::Cursor, target, [1, 0]));
+
+    let line = |line: usize, text: &str| Completion::Line {
+        line,
+        text: text.to_string(),
+    };
+    assert!(completes_with(line(1, "line 3"), "x\nline 3\n", [0, 0]));
+    assert!(!completes_with(line(0, "line 3"), "x\nline 3\n", [0, 0]));
+    assert!(!completes_with(line(5, "line 3"), "x\nline 3\n", [0, 0]));
+
+    let regex = |pattern: &str| Completion::Regex {
+        pattern: crate::config::ContentPattern::new(pattern).unwrap(),
+    };
+    assert!(completes_with(regex(r"(?m)^line 3$"), "line 3\n", [0, 0]));
+    assert!(!completes_with(regex(r"^line 2"), "line 1\n", [0, 0]));
+
+    assert!(completes_with(
+        Completion::TrimmedContent,
+        "line 2  \nline 3\t\n\n",
+        [1, 2]
+    ));
+    assert!(!completes_with(
+        Completion::TrimmedContent,
+        "line  2\nline 3\n",
+        [0, 0]
+    ));
+}
+
+#[test]
+fn test_selection_completion_rule() {
+    let mut scenario = create_test_scenario();
+    let target = scenario.target.as_mut().unwrap();
+    target.selection = Some([0, 0, 0, 4]);
+    target.cursor_position = Some((0, 4));
+    target.completion = Completion::Selection;
+
+    // Only the selection counts, not the deleted line the content still has
+    let mut session = GameSession::new(scenario.clone()).unwrap();
+    session.record_action("w".to_string()).unwrap();
+    assert!(!session.is_completed());
+
+    let mut session = GameSession::new(scenario).unwrap();
+    session.record_action("e".to_string()).unwrap();
+    assert!(session.is_completed());
+}
@@ -0,0 +1,428 @@
+//! Shortest solution search
+//!
+//! Finds the fewest keys that turn a scenario's setup into its target by
+//! trying simulator commands in order of key count. Scenario authors use it
+//! to check `optimal_count` and to discover solutions they missed.
+//!
+//! # Examples
+//!
+//! ```ignore
+//! use helix_trainer::game::solver::{SearchBudget, SearchOutcome, find_shortest_solution};
+//!
+//! match find_shortest_solution(&scenario, &SearchBudget::default()) {
+//!     SearchOutcome::Found(keys) => println!("{} keys: {:?}", keys.len(), keys),
+//!     SearchOutcome::NotFound => println!("no solution within the key limit"),
+//!     SearchOutcome::OutOfBudget => println!("search gave up"),
+//! }
+//! ```
+
+use super::GameSession;
+use super::session::is_command;
+use crate::config::Scenario;
+use crate::helix::commands::*;
+use crate::helix::{HelixSimulator, Mode};
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+/// Normal and select mode commands the search tries, with the keys that type them
+///
+/// Undo, redo and view commands are left out: they never lead to a shorter
+/// solution, and their effect depends on history and view state the search
+/// does not track. Replace (`r`) is added separately for each character of
+/// the target.
+const COMMANDS: [(&str, &[&str]); 38] = [
+    (CMD_MOVE_LEFT, &["h"]),
+    (CMD_MOVE_DOWN, &["j"]),
+    (CMD_MOVE_UP, &["k"]),
+    (CMD_MOVE_RIGHT, &["l"]),
+    (CMD_MOVE_WORD_FORWARD, &["w"]),
+    (CMD_MOVE_WORD_BACKWARD, &["b"]),
+    (CMD_MOVE_WORD_END, &["e"]),
+    (CMD_MOVE_LONG_WORD_FORWARD, &["W"]),
+    (CMD_MOVE_LONG_WORD_BACKWARD, &["B"]),
+    (CMD_MOVE_LONG_WORD_END, &["E"]),
+    (CMD_MOVE_LINE_START, &["0"]),
+    (CMD_MOVE_LINE_END, &["$"]),
+    (CMD_GOTO_FILE_END, &["G"]),
+    (CMD_GOTO_FILE_START, &["g", "g"]),
+    (CMD_GOTO_NEXT_PARAGRAPH, &["]", "p"]),
+    (CMD_GOTO_PREV_PARAGRAPH, &["[", "p"]),
+    (CMD_SELECT_MODE, &["v"]),
+    (CMD_ESCAPE, &["Escape"]),
+    (CMD_DELETE_CHAR, &["x"]),
+    (CMD_DELETE_LINE, &["d", "d"]),
+    (CMD_DELETE_NOYANK, &["alt-d"]),
+    (CMD_INSERT, &["i"]),
+    (CMD_APPEND, &["a"]),
+    (CMD_INSERT_LINE_START, &["I"]),
+    (CMD_APPEND_LINE_END, &["A"]),
+    (CMD_OPEN_BELOW, &["o"]),
+    (CMD_OPEN_ABOVE, &["O"]),
+    (CMD_CHANGE, &["c"]),
+    (CMD_CHANGE_NOYANK, &["alt-c"]),
+    (CMD_JOIN_LINES, &["J"]),
+    (CMD_INDENT, &[">"]),
+    (CMD_DEDENT, &["<"]),
+    (CMD_ADD_NEWLINE_BELOW, &["]", " "]),
+    (CMD_ADD_NEWLINE_ABOVE, &["[", " "]),
+    (CMD_YANK, &["y"]),
+    (CMD_PASTE_AFTER, &["p"]),
+    (CMD_PASTE_BEFORE, &["P"]),
+    (CMD_REPEAT, &["."]),
+];
+
+/// Limits that keep a search from running away
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SearchBudget {
+    /// Longest solution to look for, in keys
+    pub max_keys: usize,
+    /// Most distinct editor states to explore
+    pub max_states: usize,
+    /// Wall-clock time limit
+    pub max_duration: Duration,
+}
+
+impl Default for SearchBudget {
+    fn default() -> Self {
+        Self {
+            max_keys: 12,
+            max_states: 200_000,
+            max_duration: Duration::from_secs(5),
+        }
+    }
+}
+
+/// Result of a shortest solution search
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SearchOutcome {
+    /// The shortest key sequence that reaches the target
+    Found(Vec<String>),
+    /// Every sequence up to `max_keys` was tried without reaching the target
+    NotFound,
+    /// The state or time budget ran out first
+    OutOfBudget,
+}
+
+/// A state waiting to be expanded, with the index of its path in the arena
+struct Node {
+    simulator: HelixSimulator,
+    path: usize,
+    /// Bit set of the scenario's required commands used on the way
+    used: u32,
+}
+
+/// Find the fewest keys that turn the scenario setup into its target
+///
+/// Keys are counted the way scoring counts them (`dd` is two keys), and a
+/// state counts as solved as soon as it matches the target, like in a
+/// session. In insert mode the search only types the character the target
+/// has at the cursor, so solutions that type text and later move it are
+/// not considered. The scenario's forbidden commands are never tried, and
+/// a solution only counts once it has used every required command.
+pub fn find_shortest_solution(scenario: &Scenario, budget: &SearchBudget) -> SearchOutcome {
+    search(scenario, budget, &COMMANDS, true)
+}
+
+/// Find the fewest keys that reach the target using only the given commands
+///
+/// Commands are simulator commands such as `"w"` or `"gg"`; those the
+/// search does not know are ignored. Replace and typed text are left out,
+/// so this suits drills restricted to a set of motions.
+pub fn find_shortest_solution_using(
+    scenario: &Scenario,
+    budget: &SearchBudget,
+    commands: &[&str],
+) -> SearchOutcome {
+    let allowed: Vec<(&str, &[&str])> = COMMANDS
+        .iter()
+        .filter(|(command, _)| commands.contains(command))
+        .copied()
+        .collect();
+    search(scenario, budget, &allowed, false)
+}
+
+/// Breadth-first search over `commands`, plus replace when `replace` is set
+fn search(
+    scenario: &Scenario,
+    budget: &SearchBudget,
+    commands: &[(&str, &[&str])],
+    replace: bool,
+) -> SearchOutcome {
+    let (Some(scenario_target), Ok((_, start)), Ok(target)) = (
+        &scenario.target,
+        GameSession::initial_setup(scenario),
+        GameSession::build_target_state(scenario),
+    ) else {
+        return SearchOutcome::NotFound;
+    };
+
+    // Forbidden commands are left out; required ones are tracked per state
+    let forbidden = &scenario.forbidden_commands;
+    let commands: Vec<(&str, &[&str])> = commands
+        .iter()
+        .filter(|(command, _)| !forbidden.iter().any(|f| f == command))
+        .copied()
+        .collect();
+    let required = &scenario.required_commands;
+    let all_used = 1u32
+        .checked_shl(required.len() as u32)
+        .map_or(u32::MAX, |bit| bit - 1);
+    let required_bit = |command: &str| {
+        required
+            .iter()
+            .enumerate()
+            .filter(|(_, required)| is_command(command, required))
+            .fold(0u32, |bits, (index, _)| bits | 1 << index)
+    };
+
+    let goal = |simulator: &HelixSimulator| {
+        simulator
+            .get_state()
+            .is_ok_and(|state| GameSession::target_reached(scenario_target, &state, &target))
+    };
+
+    // Insert mode types the target's own characters; replace uses any of them
+    let target_chars: Vec<char> = scenario_target.file_content.chars().collect();
+    let typed_keys: Vec<String> = target_chars.iter().map(char::to_string).collect();
+    let mut replacements: Vec<char> = target_chars
+        .iter()
+        .copied()
+        .filter(|c| *c != '\n')
+        .collect();
+    replacements.sort_unstable();
+    replacements.dedup();
+    if !replace || forbidden.iter().any(|f| f == CMD_REPLACE) {
+        replacements.clear();
+    }
+    let replace_commands: Vec<(String, [String; 2])> = replacements
+        .iter()
+        .map(|c| {
+            (
+                format!("{CMD_REPLACE}{c}"),
+                [CMD_REPLACE.to_string(), c.to_string()],
+            )
+        })
+        .collect();
+
+    let started_at = Instant::now();
+
+    // Paths are stored once as (parent, key) links instead of per state
+    let mut arena: Vec<(usize, &str)> = vec![(usize::MAX, "")];
+    let mut best_cost: HashMap<(String, u32), usize> =
+        HashMap::from([((start.fingerprint(), 0), 0)]);
+
+    // One queue per key count; commands cost one or two keys, so popping
+    // the cheapest queue first yields the shortest solution
+    let mut queues: Vec<VecDeque<Node>> = (0..=budget.max_keys).map(|_| VecDeque::new()).collect();
+    queues[0].push_back(Node {
+        simulator: start,
+        path: 0,
+        used: 0,
+    });
+
+    for cost in 0..=budget.max_keys {
+        while let Some(node) = queues[cost].pop_front() {
+            if node.used == all_used && goal(&node.simulator) {
+                return SearchOutcome::Found(collect_path(&arena, node.path));
+            }
+            if best_cost.len() > budget.max_states || started_at.elapsed() > budget.max_duration {
+                return SearchOutcome::OutOfBudget;
+            }
+
+            // (simulator command, keys that type it)
+            let mut moves: Vec<(&str, Vec<&str>)> = Vec::new();
+            if node.simulator.mode() == Mode::Insert {
+                moves.push((CMD_ESCAPE, vec![CMD_ESCAPE]));
+                if let Some(key) = next_target_char(&node.simulator, &target_chars)
+                    .map(|index| typed_keys[index].as_str())
+                {
+                    moves.push((key, vec![key]));
+                }
+            } else {
+                for (command, keys) in &commands {
+                    moves.push((command, keys.to_vec()));
+                }
+                for (command, keys) in &replace_commands {
+                    moves.push((command, keys.iter().map(String::as_str).collect()));
+                }
+            }
+
+            let insert_mode = node.simulator.mode() == Mode::Insert;
+            for (command, keys) in moves {
+                let next_cost = cost + keys.len();
+                if next_cost > budget.max_keys {
+                    continue;
+                }
+
+                let mut simulator = node.simulator.clone();
+                if simulator.execute_command(command).is_err() {
+                    continue;
+                }
+
+                let used = if insert_mode {
+                    node.used
+                } else {
+                    node.used | required_bit(command)
+                };
+                let fingerprint = (simulator.fingerprint(), used);
+                if best_cost
+                    .get(&fingerprint)
+                    .is_some_and(|&seen| seen <= next_cost)
+                {
+                    continue;
+                }
+                best_cost.insert(fingerprint, next_cost);
+
+                let mut path = node.path;
+                for key in keys {
+                    arena.push((path, key));
+                    path = arena.len() - 1;
+                }
+                queues[next_cost].push_back(Node {
+                    simulator,
+                    path,
+                    used,
+                });
+            }
+        }
+    }
+
+    SearchOutcome::NotFound
+}
+
+/// Index into the target of the character at the simulator's cursor
+fn next_target_char(simulator: &HelixSimulator, target_chars: &[char]) -> Option<usize> {
+    let cursor = simulator.get_state().ok()?.cursor_position();
+    let line_start = target_chars
+        .split_inclusive(|c| *c == '\n')
+        .take(cursor.row)
+        .map(<[char]>::len)
+        .sum::<usize>();
+    let index = line_start + cursor.col;
+    (index < target_chars.len()).then_some(index)
+}
+
+/// Follow the parent links of a path back to the start
+fn collect_path(arena: &[(usize, &str)], mut index: usize) -> Vec<String> {
+    let mut keys = Vec::new();
+    while index != 0 {
+        let (parent, key) = arena[index];
+        keys.push(key.to_string());
+        index = parent;
+    }
+    keys.reverse();
+    keys
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::scenarios::fixtures::{ScenarioToml, section};
+
+    /// Scenario turning `setup` into `target`, both given with their cursor
+    fn scenario(setup: (&str, [usize; 2]), target: (&str, [usize; 2])) -> Scenario {
+        ScenarioToml::new("solver_001")
+            .setup(&section(setup.0, setup.1))
+            .target(&section(target.0, target.1))
+            .solution("[]", 1)
+            .parse()
+    }
+
+    #[test]
+    fn test_finds_single_command() {
+        let scenario = scenario(("hello", [0, 0]), ("ello", [0, 0]));
+        assert_eq!(
+            find_shortest_solution(&scenario, &SearchBudget::default()),
+            SearchOutcome::Found(vec!["x".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_already_solved() {
+        let scenario = scenario(("hello", [0, 0]), ("hello", [0, 0]));
+        assert_eq!(
+            find_shortest_solution(&scenario, &SearchBudget::default()),
+            SearchOutcome::Found(Vec::new())
+        );
+    }
+
+    #[test]
+    fn test_counts_keys_of_multi_key_commands() {
+        let scenario = scenario(("a\nb\nc", [1, 0]), ("a\nc", [1, 0]));
+        match find_shortest_solution(&scenario, &SearchBudget::default()) {
+            SearchOutcome::Found(keys) => assert_eq!(keys.len(), 2, "{:?}", keys),
+            other => panic!("Expected a solution, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_types_target_text_in_insert_mode() {
+        let scenario = scenario(("Hello World", [0, 6]), ("Hello abWorld", [0, 8]));
+        assert_eq!(
+            find_shortest_solution(&scenario, &SearchBudget::default()),
+            SearchOutcome::Found(["i", "a", "b"].iter().map(|k| k.to_string()).collect())
+        );
+    }
+
+    #[test]
+    fn test_restricted_commands() {
+        let scenario = scenario(("one two three", [0, 0]), ("one two three", [0, 8]));
+        let keys = |outcome| match outcome {
+            SearchOutcome::Found(keys) => keys,
+            other => panic!("Expected a solution, got {:?}", other),
+        };
+
+        assert_eq!(
+            keys(find_shortest_solution(&scenario, &SearchBudget::default())).len(),
+            2
+        );
+        let only_l = find_shortest_solution_using(&scenario, &SearchBudget::default(), &["l"]);
+        assert_eq!(keys(only_l), vec!["l".to_string(); 8]);
+    }
+
+    #[test]
+    fn test_command_constraints() {
+        let mut scenario = scenario(("one two three", [0, 0]), ("one two three", [0, 4]));
+        let budget = SearchBudget::default();
+        assert_eq!(
+            find_shortest_solution(&scenario, &budget),
+            SearchOutcome::Found(vec!["w".to_string()])
+        );
+
+        scenario.forbidden_commands = vec!["w".to_string()];
+        match find_shortest_solution(&scenario, &budget) {
+            SearchOutcome::Found(keys) => assert!(!keys.contains(&"w".to_string()), "{:?}", keys),
+            other => panic!("Expected a solution, got {:?}", other),
+        }
+
+        scenario.forbidden_commands.clear();
+        scenario.required_commands = vec!["e".to_string()];
+        match find_shortest_solution(&scenario, &budget) {
+            SearchOutcome::Found(keys) => assert!(keys.contains(&"e".to_string()), "{:?}", keys),
+            other => panic!("Expected a solution, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_budget_limits() {
+        let scenario = scenario(("Hello World", [0, 6]), ("Hello abWorld", [0, 8]));
+
+        let short = SearchBudget {
+            max_keys: 2,
+            ..SearchBudget::default()
+        };
+        assert_eq!(
+            find_shortest_solution(&scenario, &short),
+            SearchOutcome::NotFound
+        );
+
+        let tiny = SearchBudget {
+            max_states: 1,
+            ..SearchBudget::default()
+        };
+        assert_eq!(
+            find_shortest_solution(&scenario, &tiny),
+            SearchOutcome::OutOfBudget
+        );
+    }
+}
@@ -8,6 +8,27 @@ pub static CMD_DELETE_LINE: &str = "dd";
 pub static CMD_GOTO_FILE_START: &str = "gg";
 pub static CMD_GOTO_FILE_END: &str = "G";
 
+// Unimpaired-style bracket commands
+pub static CMD_GOTO_NEXT_PARAGRAPH: &str = "]p";
+pub static CMD_GOTO_PREV_PARAGRAPH: &str = "[p";
+pub static CMD_ADD_NEWLINE_BELOW: &str = "]Space";
+pub static CMD_ADD_NEWLINE_ABOVE: &str = "[Space";
+
+// View mode commands (z prefix, or sticky with Z)
+pub static CMD_VIEW_MODE: &str = "z";
+pub static CMD_STICKY_VIEW_MODE: &str = "Z";
+pub static CMD_ALIGN_VIEW_CENTER: &str = "zz";
+pub static CMD_ALIGN_VIEW_TOP: &str = "zt";
+pub static CMD_ALIGN_VIEW_BOTTOM: &str = "zb";
+pub static CMD_SCROLL_DOWN: &str = "zj";
+pub static CMD_SCROLL_UP: &str = "zk";
+
+// Scrolling with Ctrl
+pub static CMD_PAGE_CURSOR_HALF_DOWN: &str = "ctrl-d";
+pub static CMD_PAGE_CURSOR_HALF_UP: &str = "ctrl-u";
+pub static CMD_PAGE_DOWN: &str = "ctrl-f";
+pub static CMD_PAGE_UP: &str = "ctrl-b";
+
 // Single character commands - Movement
 pub static CMD_MOVE_LEFT: &str = "h";
 pub static CMD_MOVE_DOWN: &str = "j";
@@ -16,9 +37,15 @@ pub static CMD_MOVE_RIGHT: &str = "l";
 pub static CMD_MOVE_WORD_FORWARD: &str = "w";
 pub static CMD_MOVE_WORD_BACKWARD: &str = "b";
 pub static CMD_MOVE_WORD_END: &str = "e";
+pub static CMD_MOVE_LONG_WORD_FORWARD: &str = "W";
+pub static CMD_MOVE_LONG_WORD_BACKWARD: &str = "B";
+pub static CMD_MOVE_LONG_WORD_END: &str = "E";
 pub static CMD_MOVE_LINE_START: &str = "0";
 pub static CMD_MOVE_LINE_END: &str = "$";
 
+// Select mode (motions extend the selection instead of moving it)
+pub static CMD_SELECT_MODE: &str = "v";
+
 // Editing commands
 pub static CMD_DELETE_CHAR: &str = "x";
 pub static CMD_INSERT: &str = "i";
@@ -28,6 +55,8 @@ pub static CMD_APPEND_LINE_END: &str = "A";
 pub static CMD_OPEN_BELOW: &str = "o";
 pub static CMD_OPEN_ABOVE: &str = "O";
 pub static CMD_CHANGE: &str = "c";
+pub static CMD_DELETE_NOYANK: &str = "alt-d";
+pub static CMD_CHANGE_NOYANK: &str = "alt-c";
 pub static CMD_JOIN_LINES: &str = "J";
 pub static CMD_INDENT: &str = ">";
 pub static CMD_DEDENT: &str = "<";
@@ -40,6 +69,7 @@ pub static CMD_PASTE_BEFORE: &str = "P";
 // Undo/Redo
 pub static CMD_UNDO: &str = "u";
 pub static CMD_REDO: &str = "U";
+pub static CMD_REDO_ALT: &str = "ctrl-r";
 
 // Special commands
 pub static CMD_ESCAPE: &str = "Escape";
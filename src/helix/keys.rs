@@ -0,0 +1,170 @@
+//! Multi-key command buffering
+//!
+//! Keys arrive one at a time, but commands like `dd`, `gg` or `]p` span
+//! several keys. `push_key` collects keys in a buffer until they form a
+//! complete simulator command. Both the UI and the scenario verifier use it,
+//! so a recorded key sequence behaves the same everywhere.
+
+use crate::helix::commands::{
+    CMD_ALIGN_VIEW_BOTTOM, CMD_ALIGN_VIEW_CENTER, CMD_ALIGN_VIEW_TOP, CMD_ESCAPE,
+    CMD_PAGE_CURSOR_HALF_DOWN, CMD_PAGE_CURSOR_HALF_UP, CMD_PAGE_DOWN, CMD_PAGE_UP,
+    CMD_SCROLL_DOWN, CMD_SCROLL_UP, CMD_STICKY_VIEW_MODE, CMD_VIEW_MODE,
+};
+
+/// Commands accepted while sticky view mode (`Z`) is active
+const STICKY_VIEW_COMMANDS: [&str; 9] = [
+    CMD_ALIGN_VIEW_CENTER,
+    CMD_ALIGN_VIEW_TOP,
+    CMD_ALIGN_VIEW_BOTTOM,
+    CMD_SCROLL_DOWN,
+    CMD_SCROLL_UP,
+    CMD_PAGE_CURSOR_HALF_DOWN,
+    CMD_PAGE_CURSOR_HALF_UP,
+    CMD_PAGE_DOWN,
+    CMD_PAGE_UP,
+];
+
+/// Feed one normal or select mode key into the command buffer
+///
+/// Returns the complete command to execute, or `None` while more keys are
+/// needed or when the key was discarded. The buffer is cleared once a
+/// command completes or the sequence turns out to be invalid. Insert mode
+/// keys bypass the buffer and go straight to the simulator.
+///
+/// # Examples
+///
+/// ```ignore
+/// use helix_trainer::helix::keys::push_key;
+///
+/// let mut buffer = String::new();
+/// assert_eq!(push_key(&mut buffer, "d"), None);
+/// assert_eq!(push_key(&mut buffer, "d"), Some("dd".to_string()));
+/// assert!(buffer.is_empty());
+/// ```
+pub fn push_key(buffer: &mut String, key: &str) -> Option<String> {
+    if buffer == CMD_STICKY_VIEW_MODE {
+        // Sticky view mode: keys stay view commands until Escape
+        if key == CMD_ESCAPE {
+            buffer.clear();
+            return None;
+        }
+
+        let view_command = if key.starts_with("ctrl-") {
+            key.to_string()
+        } else if key == "c" {
+            // 'c' is an alias for centering
+            CMD_ALIGN_VIEW_CENTER.to_string()
+        } else {
+            format!("{}{}", CMD_VIEW_MODE, key)
+        };
+
+        // Keys without a view meaning are ignored while the mode is active
+        return STICKY_VIEW_COMMANDS
+            .contains(&view_command.as_str())
+            .then_some(view_command);
+    }
+
+    if key == CMD_ESCAPE && !buffer.is_empty() {
+        // Escape cancels a pending multi-key command
+        buffer.clear();
+        return None;
+    }
+
+    buffer.push_str(key);
+
+    // Try to match a complete command
+    let command = match buffer.as_str() {
+        // Multi-key commands
+        "dd" => "dd".to_string(),
+        "gg" => "gg".to_string(),
+        "]p" => "]p".to_string(),
+        "[p" => "[p".to_string(),
+        "] " => "]Space".to_string(),
+        "[ " => "[Space".to_string(),
+        "zz" | "zt" | "zb" | "zj" | "zk" => buffer.clone(),
+        "zc" => CMD_ALIGN_VIEW_CENTER.to_string(),
+
+        // Ctrl and Alt chords are complete on their own (e.g., "ctrl-d")
+        cmd if cmd.starts_with("ctrl-") || cmd.starts_with("alt-") => buffer.clone(),
+
+        // Escape leaves select mode
+        cmd if cmd == CMD_ESCAPE => CMD_ESCAPE.to_string(),
+
+        // Replace character command: r + any char
+        cmd if cmd.starts_with('r') && cmd.chars().count() == 2 => buffer.clone(),
+
+        // Partial commands - wait for more input
+        "d" | "g" | "r" | "[" | "]" | "z" | "Z" => return None,
+
+        // Single-key commands other than a lone space (which only means
+        // something after a bracket prefix)
+        cmd if cmd.len() == 1 && cmd != " " => buffer.clone(),
+
+        // Invalid sequence
+        _ => {
+            buffer.clear();
+            return None;
+        }
+    };
+
+    buffer.clear();
+    Some(command)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Feed keys one by one and collect the completed commands
+    fn commands(keys: &[&str]) -> Vec<String> {
+        let mut buffer = String::new();
+        keys.iter()
+            .filter_map(|key| push_key(&mut buffer, key))
+            .collect()
+    }
+
+    #[test]
+    fn test_single_key_commands() {
+        assert_eq!(
+            commands(&["w", "x", "ctrl-d", "alt-c"]),
+            ["w", "x", "ctrl-d", "alt-c"]
+        );
+    }
+
+    #[test]
+    fn test_multi_key_commands() {
+        assert_eq!(
+            commands(&["d", "d", "g", "g", "r", "x", "]", " ", "z", "c"]),
+            ["dd", "gg", "rx", "]Space", "zz"]
+        );
+    }
+
+    #[test]
+    fn test_invalid_sequences_are_discarded() {
+        let mut buffer = String::new();
+        assert_eq!(push_key(&mut buffer, "d"), None);
+        assert_eq!(push_key(&mut buffer, "w"), None);
+        assert!(buffer.is_empty());
+
+        assert_eq!(push_key(&mut buffer, "test"), None);
+        assert_eq!(push_key(&mut buffer, " "), None);
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    fn test_escape_cancels_pending_command() {
+        assert_eq!(commands(&["d", "Escape", "x"]), ["x"]);
+        assert_eq!(commands(&["Escape"]), ["Escape"]);
+    }
+
+    #[test]
+    fn test_sticky_view_mode() {
+        let mut buffer = String::new();
+        assert_eq!(push_key(&mut buffer, "Z"), None);
+        assert_eq!(push_key(&mut buffer, "j"), Some("zj".to_string()));
+        assert_eq!(push_key(&mut buffer, "x"), None);
+        assert_eq!(buffer, "Z");
+        assert_eq!(push_key(&mut buffer, "Escape"), None);
+        assert!(buffer.is_empty());
+    }
+}
@@ -18,10 +18,11 @@
 
 pub mod commands;
 pub mod executor;
+pub mod keys;
 pub mod repeat;
 pub mod simulator;
 
 pub use commands::*;
 pub use executor::CommandExecutor;
 pub use repeat::{Movement, RepeatBuffer, RepeatableAction, is_repeatable_command};
-pub use simulator::{HelixSimulator, Mode};
+pub use simulator::{DEFAULT_INDENT_UNIT, HelixSimulator, Mode, Viewport, is_valid_indent_unit};
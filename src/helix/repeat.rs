@@ -15,7 +15,7 @@
 //! - Insert mode text is limited to 1000 characters
 //! - Movements are limited to 100 steps
 
-use crossterm::event::{KeyCode, KeyEvent};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 
 /// Maximum length of insert mode text recording (security limit)
 const MAX_INSERT_TEXT_LENGTH: usize = 1000;
@@ -69,13 +69,15 @@ pub enum Mode {
     Normal,
     /// Insert mode
     Insert,
+    /// Select mode
+    Select,
 }
 
 /// Stores the last repeatable action
 ///
 /// This buffer maintains a history of one action that can be replayed
 /// with the `.` command.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct RepeatBuffer {
     last_action: Option<RepeatableAction>,
     insert_recorder: InsertModeRecorder,
@@ -147,7 +149,7 @@ impl Default for RepeatBuffer {
 ///
 /// - Text recording is limited to 1000 characters
 /// - Movement recording is limited to 100 steps
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct InsertModeRecorder {
     is_recording: bool,
     text: String,
@@ -241,6 +243,7 @@ impl Default for InsertModeRecorder {
 /// - `F1` (hint)
 /// - `Esc` (cancel)
 /// - Movement commands: `h`, `j`, `k`, `l`, `w`, `b`, `e`, `0`, `$`, `g`, `G`
+/// - Any key with Ctrl held (scrolling, redo)
 ///
 /// Returns `true` for all editing commands:
 /// - Character operations: `x`, `r`
@@ -250,6 +253,11 @@ impl Default for InsertModeRecorder {
 /// - Clipboard: `y`, `p`, `P`
 /// - Indent: `>`, `<`
 pub fn is_repeatable_command(key: &KeyEvent) -> bool {
+    // Ctrl chords are scrolling or history commands, never edits
+    if key.modifiers.contains(KeyModifiers::CONTROL) {
+        return false;
+    }
+
     match key.code {
         KeyCode::Char(ch) => match ch {
             // Repeat itself - prevent infinite recursion
@@ -261,9 +269,13 @@ pub fn is_repeatable_command(key: &KeyEvent) -> bool {
             // Movement commands - not editing actions
             'h' | 'j' | 'k' | 'l' => false,
             'w' | 'b' | 'e' => false,
+            'W' | 'B' | 'E' => false,
             '0' | '$' => false,
             'g' | 'G' => false,
 
+            // Select mode toggle - changes how motions behave, not the text
+            'v' => false,
+
             // Editing commands - these ARE repeatable
             'x' => true,       // delete char
             'd' => true,       // delete (dd for line)
@@ -295,7 +307,6 @@ pub fn is_repeatable_command(key: &KeyEvent) -> bool {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crossterm::event::{KeyCode, KeyModifiers};
 
     fn make_key(ch: char) -> KeyEvent {
         KeyEvent::new(KeyCode::Char(ch), KeyModifiers::NONE)
@@ -492,6 +503,9 @@ mod tests {
         assert!(!is_repeatable_command(&make_key('w')));
         assert!(!is_repeatable_command(&make_key('b')));
         assert!(!is_repeatable_command(&make_key('e')));
+        assert!(!is_repeatable_command(&make_key('W')));
+        assert!(!is_repeatable_command(&make_key('B')));
+        assert!(!is_repeatable_command(&make_key('E')));
         assert!(!is_repeatable_command(&make_key('0')));
         assert!(!is_repeatable_command(&make_key('$')));
         assert!(!is_repeatable_command(&make_key('g')));
@@ -525,6 +539,7 @@ mod tests {
         assert!(!is_repeatable_command(&make_key('.'))); // repeat itself
         assert!(!is_repeatable_command(&make_key('u'))); // undo
         assert!(!is_repeatable_command(&make_key('U'))); // redo
+        assert!(!is_repeatable_command(&make_key('v'))); // select mode
 
         // Function keys
         let f1 = KeyEvent::new(KeyCode::F(1), KeyModifiers::NONE);
@@ -535,6 +550,26 @@ mod tests {
         assert!(!is_repeatable_command(&esc));
     }
 
+    #[test]
+    fn test_is_repeatable_ctrl_chords() {
+        // Ctrl-d scrolls even though plain 'd' deletes
+        let ctrl_d = KeyEvent::new(KeyCode::Char('d'), KeyModifiers::CONTROL);
+        assert!(!is_repeatable_command(&ctrl_d));
+
+        let ctrl_r = KeyEvent::new(KeyCode::Char('r'), KeyModifiers::CONTROL);
+        assert!(!is_repeatable_command(&ctrl_r));
+    }
+
+    #[test]
+    fn test_is_repeatable_alt_edits() {
+        // Alt-d and Alt-c edit like their plain counterparts
+        let alt_d = KeyEvent::new(KeyCode::Char('d'), KeyModifiers::ALT);
+        assert!(is_repeatable_command(&alt_d));
+
+        let alt_c = KeyEvent::new(KeyCode::Char('c'), KeyModifiers::ALT);
+        assert!(is_repeatable_command(&alt_c));
+    }
+
     #[test]
     fn test_is_repeatable_unknown_commands() {
         // Random characters should not be repeatable
@@ -1,62 +1,109 @@
 //! Clipboard operations (yank, paste)
 
 use crate::helix::simulator::HelixSimulator;
+use crate::helix::simulator::registers::{DEFAULT_REGISTER, is_linewise};
 use crate::security::UserError;
-use helix_core::{Selection, Transaction};
+use helix_core::{Range, Selection, Transaction};
 
-/// Yank (copy) current character to clipboard
-pub(super) fn yank(sim: &mut HelixSimulator) -> Result<(), UserError> {
-    // Copy current character to clipboard
-    let head = sim.selection.primary().head;
+/// Where pasted text goes relative to each selection
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Paste {
+    Before,
+    After,
+}
 
-    if head >= sim.doc.len_chars() {
-        return Ok(());
-    }
+/// Yank (copy) every selection into the default register
+///
+/// A cursor without a selection yanks the character under it.
+pub(super) fn yank(sim: &mut HelixSimulator) -> Result<(), UserError> {
+    let slice = sim.doc.slice(..);
+    let ranges: Vec<(usize, usize)> = sim
+        .selection
+        .iter()
+        .map(|range| {
+            let range = range.min_width_1(slice);
+            (range.from(), range.to())
+        })
+        .collect();
 
-    let current_char = sim.doc.char(head);
-    sim.clipboard = Some(current_char.to_string());
+    let values = sim.fragments(&ranges);
+    sim.yank_values(values);
     Ok(())
 }
 
-/// Paste clipboard content after cursor
+/// Paste the default register after each selection (`p`)
 pub(super) fn paste_after(sim: &mut HelixSimulator) -> Result<(), UserError> {
-    // Paste clipboard content after cursor
-    if let Some(text) = &sim.clipboard {
-        let head = sim.selection.primary().head;
-        let insert_pos = (head + 1).min(sim.doc.len_chars());
-        let text_len = text.chars().count();
-
-        let transaction = Transaction::change(
-            &sim.doc,
-            [(insert_pos, insert_pos, Some(text.as_str().into()))].into_iter(),
-        );
-
-        sim.apply_transaction(transaction);
-
-        // Move cursor to the end of pasted text
-        let new_pos = insert_pos + text_len;
-        sim.selection = Selection::point(new_pos.min(sim.doc.len_chars()));
-    }
-    Ok(())
+    paste(sim, Paste::After)
 }
 
-/// Paste clipboard content before cursor
+/// Paste the default register before each selection (`P`)
 pub(super) fn paste_before(sim: &mut HelixSimulator) -> Result<(), UserError> {
-    // Paste clipboard content before cursor
-    if let Some(text) = &sim.clipboard {
-        let head = sim.selection.primary().head;
-        let text_len = text.chars().count();
+    paste(sim, Paste::Before)
+}
+
+/// Paste register values the way Helix does
+///
+/// - Linewise values (ending in a newline) go below or above the current
+///   line instead of inside it.
+/// - With several selections, values are pasted pairwise; selections
+///   without a matching value reuse the last one.
+/// - The pasted text becomes the new selection.
+fn paste(sim: &mut HelixSimulator, position: Paste) -> Result<(), UserError> {
+    let Some(values) = sim.registers.read(DEFAULT_REGISTER) else {
+        return Ok(());
+    };
+    let values = values.to_vec();
+    let linewise = values.iter().any(|value| is_linewise(value));
+    let last = values.last().cloned().unwrap_or_default();
 
-        let transaction = Transaction::change(
-            &sim.doc,
-            [(head, head, Some(text.as_str().into()))].into_iter(),
-        );
+    let slice = sim.doc.slice(..);
+    let len_chars = sim.doc.len_chars();
+    let ends_with_newline = len_chars > 0 && sim.doc.char(len_chars - 1) == '\n';
 
-        sim.apply_transaction(transaction);
+    // (insert position, inserted text, chars of the text that are not selected afterwards)
+    let mut inserts = Vec::with_capacity(sim.selection.len());
+    let mut values = values.into_iter().chain(std::iter::repeat(last));
+    for range in sim.selection.iter() {
+        let value = values.next().unwrap_or_default();
+        let pos = match (position, linewise) {
+            (Paste::Before, true) => sim.doc.line_to_char(sim.doc.char_to_line(range.from())),
+            (Paste::After, true) => {
+                let (_, end_line) = range.line_range(slice);
+                sim.doc
+                    .line_to_char((end_line + 1).min(sim.doc.len_lines()))
+            }
+            (Paste::Before, false) => range.from(),
+            (Paste::After, false) => range.min_width_1(slice).to(),
+        };
 
-        // Move cursor to the end of pasted text
-        let new_pos = head + text_len;
-        sim.selection = Selection::point(new_pos.min(sim.doc.len_chars()));
+        // A linewise paste below a last line without a line break needs one first
+        if linewise && pos == len_chars && pos > 0 && !ends_with_newline {
+            let line = value.strip_suffix('\n').unwrap_or(&value);
+            inserts.push((pos, format!("\n{line}"), 1));
+        } else {
+            inserts.push((pos, value, 0));
+        }
     }
+
+    let transaction = Transaction::change(
+        &sim.doc,
+        inserts
+            .iter()
+            .map(|(pos, text, _)| (*pos, *pos, Some(text.as_str().into()))),
+    );
+    sim.apply_transaction(transaction);
+
+    // Select the pasted text, accounting for earlier insertions
+    let mut offset = 0;
+    let ranges = inserts
+        .iter()
+        .map(|(pos, text, skipped)| {
+            let len = text.chars().count();
+            let range = Range::new(pos + offset + skipped, pos + offset + len);
+            offset += len;
+            range
+        })
+        .collect();
+    sim.selection = Selection::new(ranges, sim.selection.primary_index());
     Ok(())
 }
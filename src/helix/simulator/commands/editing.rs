@@ -4,35 +4,75 @@ use crate::helix::simulator::HelixSimulator;
 use crate::security::UserError;
 use helix_core::{Selection, Transaction};
 
-/// Delete character at cursor
+/// Delete the selection, yanking it into the default register (`x`)
+///
+/// A cursor without a selection deletes the character under it.
 pub(super) fn delete_char(sim: &mut HelixSimulator) -> Result<(), UserError> {
-    let transaction = Transaction::change_by_selection(&sim.doc, &sim.selection, |range| {
-        let start = range.from();
-        let end = start.saturating_add(1).min(sim.doc.len_chars()).max(start);
-        (start, end, None)
-    });
-
-    sim.apply_transaction(transaction);
+    let ranges = sim.selection_ranges();
+    let values = sim.fragments(&ranges);
+    sim.yank_values(values);
+    delete_ranges(sim, ranges);
     Ok(())
 }
 
-/// Delete current line
+/// Delete current line, yanking it linewise into the default register
 pub(super) fn delete_line(sim: &mut HelixSimulator) -> Result<(), UserError> {
-    let transaction = Transaction::change_by_selection(&sim.doc, &sim.selection, |range| {
-        let line = sim.doc.char_to_line(range.head);
-        let start = sim.doc.line_to_char(line);
-        let end = if line + 1 < sim.doc.len_lines() {
-            sim.doc.line_to_char(line + 1)
-        } else {
-            sim.doc.len_chars()
-        };
-        (start, end, None)
-    });
+    let mut ranges: Vec<(usize, usize)> = sim
+        .selection
+        .iter()
+        .map(|range| {
+            let line = sim.doc.char_to_line(range.head);
+            let start = sim.doc.line_to_char(line);
+            let end = if line + 1 < sim.doc.len_lines() {
+                sim.doc.line_to_char(line + 1)
+            } else {
+                sim.doc.len_chars()
+            };
+            (start, end)
+        })
+        .collect();
+    ranges.dedup();
+
+    // The last line has no line break of its own; add one so it pastes as a line
+    let values = sim
+        .fragments(&ranges)
+        .into_iter()
+        .map(|line| {
+            if line.ends_with('\n') {
+                line
+            } else {
+                line + "\n"
+            }
+        })
+        .collect();
+    sim.yank_values(values);
+    delete_ranges(sim, ranges);
+    Ok(())
+}
 
-    sim.apply_transaction(transaction);
+/// Delete the selection without yanking (`Alt-d`)
+///
+/// A cursor without a selection deletes the character under it.
+pub(super) fn delete_selection_noyank(sim: &mut HelixSimulator) -> Result<(), UserError> {
+    let ranges = sim.selection_ranges();
+    delete_ranges(sim, ranges);
     Ok(())
 }
 
+/// Delete each (sorted, non-overlapping) char range
+///
+/// Selections are mapped through the deletion, so a deleted selection
+/// collapses to a cursor where it started.
+fn delete_ranges(sim: &mut HelixSimulator, ranges: Vec<(usize, usize)>) {
+    let transaction = Transaction::change(
+        &sim.doc,
+        ranges.into_iter().map(|(from, to)| (from, to, None)),
+    );
+
+    sim.selection = sim.selection.clone().map(transaction.changes());
+    sim.apply_transaction(transaction);
+}
+
 /// Join current line with next line
 pub(super) fn join_lines(sim: &mut HelixSimulator) -> Result<(), UserError> {
     // Join current line with next line
@@ -58,63 +98,115 @@ pub(super) fn join_lines(sim: &mut HelixSimulator) -> Result<(), UserError> {
     Ok(())
 }
 
-/// Indent current line (add 2 spaces)
+/// Indent current line (add one indent unit)
 pub(super) fn indent_line(sim: &mut HelixSimulator) -> Result<(), UserError> {
-    // Add indentation (2 spaces) at the beginning of current line
+    // Add one indent unit at the beginning of current line
     let head = sim.selection.primary().head;
     let current_line = sim.doc.char_to_line(head);
     let line_start = sim.doc.line_to_char(current_line);
+    let unit = sim.indent_unit.to_string();
+    let unit_len = unit.chars().count();
 
-    // Insert 2 spaces at line start
     let transaction = Transaction::change(
         &sim.doc,
-        [(line_start, line_start, Some("  ".into()))].into_iter(),
+        [(line_start, line_start, Some(unit.into()))].into_iter(),
     );
 
     sim.apply_transaction(transaction);
 
     // Move cursor to maintain relative position
-    let new_head = head + 2;
+    let new_head = head + unit_len;
     sim.selection = Selection::point(new_head.min(sim.doc.len_chars()));
 
     Ok(())
 }
 
-/// Dedent current line (remove up to 2 spaces)
+/// Dedent current line (remove up to one indent unit)
 pub(super) fn dedent_line(sim: &mut HelixSimulator) -> Result<(), UserError> {
-    // Remove indentation (up to 2 spaces) from the beginning of current line
+    // Remove the part of an indent unit the current line starts with
     let head = sim.selection.primary().head;
     let current_line = sim.doc.char_to_line(head);
     let line_start = sim.doc.line_to_char(current_line);
 
-    // Check how many spaces to remove (max 2)
     let slice = sim.doc.slice(..);
-    let mut spaces_to_remove = 0;
+    let mut to_remove = 0;
 
-    for i in 0..2 {
+    for (i, unit_char) in sim.indent_unit.chars().enumerate() {
         let pos = line_start + i;
-        if pos < sim.doc.len_chars() && slice.char(pos) == ' ' {
-            spaces_to_remove += 1;
+        if pos < sim.doc.len_chars() && slice.char(pos) == unit_char {
+            to_remove += 1;
         } else {
             break;
         }
     }
 
-    if spaces_to_remove == 0 {
+    if to_remove == 0 {
         return Ok(());
     }
 
-    // Remove the spaces
+    // Remove the indentation
     let transaction = Transaction::change(
         &sim.doc,
-        [(line_start, line_start + spaces_to_remove, None)].into_iter(),
+        [(line_start, line_start + to_remove, None)].into_iter(),
     );
 
     sim.apply_transaction(transaction);
 
-    // Move cursor to maintain relative position
-    let new_head = head.saturating_sub(spaces_to_remove);
+    // Move cursor to maintain relative position; a cursor inside the removed
+    // indentation lands on the line start
+    let new_head = line_start + (head - line_start).saturating_sub(to_remove);
     sim.selection = Selection::point(new_head.min(sim.doc.len_chars()));
 
     Ok(())
 }
+
+/// Add a blank line below the current line (`]Space`)
+///
+/// The cursor stays where it is; only the document grows.
+pub(super) fn add_newline_below(sim: &mut HelixSimulator) -> Result<(), UserError> {
+    let slice = sim.doc.slice(..);
+    let mut positions: Vec<usize> = sim
+        .selection
+        .iter()
+        .map(|range| {
+            let (_, end_line) = range.line_range(slice);
+            slice.line_to_char(end_line + 1)
+        })
+        .collect();
+    positions.dedup();
+
+    add_newlines_at(sim, positions);
+    Ok(())
+}
+
+/// Add a blank line above the current line (`[Space`)
+///
+/// The cursor keeps its place in the text and moves down with its line.
+pub(super) fn add_newline_above(sim: &mut HelixSimulator) -> Result<(), UserError> {
+    let slice = sim.doc.slice(..);
+    let mut positions: Vec<usize> = sim
+        .selection
+        .iter()
+        .map(|range| {
+            let (start_line, _) = range.line_range(slice);
+            slice.line_to_char(start_line)
+        })
+        .collect();
+    positions.dedup();
+
+    add_newlines_at(sim, positions);
+    Ok(())
+}
+
+/// Insert a newline at each (sorted) position, keeping selections attached to their text
+fn add_newlines_at(sim: &mut HelixSimulator, positions: Vec<usize>) {
+    let transaction = Transaction::change(
+        &sim.doc,
+        positions
+            .into_iter()
+            .map(|pos| (pos, pos, Some("\n".into()))),
+    );
+
+    sim.selection = sim.selection.clone().map(transaction.changes());
+    sim.apply_transaction(transaction);
+}
@@ -4,6 +4,7 @@ mod clipboard;
 mod editing;
 mod movement;
 
+use super::view::ScrollDirection;
 use super::{HelixSimulator, Mode};
 use crate::helix::commands::*;
 use crate::helix::repeat::is_repeatable_command;
@@ -29,6 +30,48 @@ fn cmd_to_key_events(cmd: &str) -> Vec<KeyEvent> {
         ];
     }
 
+    // Bracket (unimpaired) sequences
+    if let Some(prefix) = cmd.chars().next()
+        && (prefix == '[' || prefix == ']')
+    {
+        let second = match &cmd[1..] {
+            "p" => 'p',
+            "Space" => ' ',
+            _ => return Vec::new(),
+        };
+        return vec![
+            KeyEvent::new(KeyCode::Char(prefix), KeyModifiers::NONE),
+            KeyEvent::new(KeyCode::Char(second), KeyModifiers::NONE),
+        ];
+    }
+
+    // View mode sequences (e.g., "zz" -> z + z)
+    if cmd.len() == 2
+        && let Some(rest) = cmd.strip_prefix(CMD_VIEW_MODE)
+        && let Some(ch) = rest.chars().next()
+    {
+        return vec![
+            KeyEvent::new(KeyCode::Char('z'), KeyModifiers::NONE),
+            KeyEvent::new(KeyCode::Char(ch), KeyModifiers::NONE),
+        ];
+    }
+
+    // Ctrl chords (e.g., "ctrl-d")
+    if let Some(rest) = cmd.strip_prefix("ctrl-")
+        && let Some(ch) = rest.chars().next()
+        && rest.len() == 1
+    {
+        return vec![KeyEvent::new(KeyCode::Char(ch), KeyModifiers::CONTROL)];
+    }
+
+    // Alt chords (e.g., "alt-d")
+    if let Some(rest) = cmd.strip_prefix("alt-")
+        && let Some(ch) = rest.chars().next()
+        && rest.len() == 1
+    {
+        return vec![KeyEvent::new(KeyCode::Char(ch), KeyModifiers::ALT)];
+    }
+
     // Special keys
     if cmd == CMD_ESCAPE {
         return vec![KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE)];
@@ -70,35 +113,66 @@ fn cmd_to_key_events(cmd: &str) -> Vec<KeyEvent> {
     }
 }
 
+/// Check whether a command only changes the viewport (and manages it itself)
+fn is_view_command(cmd: &str) -> bool {
+    [
+        CMD_ALIGN_VIEW_CENTER,
+        CMD_ALIGN_VIEW_TOP,
+        CMD_ALIGN_VIEW_BOTTOM,
+        CMD_SCROLL_DOWN,
+        CMD_SCROLL_UP,
+        CMD_PAGE_CURSOR_HALF_DOWN,
+        CMD_PAGE_CURSOR_HALF_UP,
+        CMD_PAGE_DOWN,
+        CMD_PAGE_UP,
+    ]
+    .contains(&cmd)
+}
+
 /// Execute a Helix command
 ///
 /// Routes commands to appropriate handlers based on mode and command type.
 /// If the command is repeatable, it will be recorded in the repeat buffer.
+/// Afterwards the viewport follows the cursor, except for view commands
+/// which position the viewport themselves.
 pub(super) fn execute_command(sim: &mut HelixSimulator, cmd: &str) -> Result<(), UserError> {
+    let result = dispatch_command(sim, cmd);
+
+    if !is_view_command(cmd) {
+        sim.ensure_cursor_in_view();
+    }
+
+    result
+}
+
+/// Dispatch a single command to its handler
+fn dispatch_command(sim: &mut HelixSimulator, cmd: &str) -> Result<(), UserError> {
     // Convert command to KeyEvents for potential recording
     let key_events = cmd_to_key_events(cmd);
 
     // Determine if we should record this command (before execution)
-    // Only record in Normal mode for repeatable commands, and NOT during repeat
-    let should_record = !key_events.is_empty()
-        && sim.mode == Mode::Normal
-        && !sim.is_repeating
-        && key_events.iter().all(is_repeatable_command);
+    // Only record repeatable commands that end in Normal mode, and NOT during
+    // repeat. The first key decides: any later key is either part of the same
+    // command (`dd`) or an argument such as the character for `r`.
+    let is_repeatable = key_events.first().is_some_and(is_repeatable_command);
 
-    // Store mode before execution (for recording)
-    let mode_before = sim.mode;
+    // An edit made in select mode returns to normal mode, as in Helix
+    let leaving_select = is_repeatable && sim.mode == Mode::Select && cmd != CMD_YANK;
+    let should_record =
+        is_repeatable && (sim.mode == Mode::Normal || leaving_select) && !sim.is_repeating;
 
     // Check if we're entering insert mode (for insert recording)
     // Don't start recording if we're repeating
     let entering_insert = !sim.is_repeating
-        && sim.mode == Mode::Normal
+        && sim.mode != Mode::Insert
         && (cmd == CMD_INSERT
             || cmd == CMD_APPEND
             || cmd == CMD_INSERT_LINE_START
             || cmd == CMD_APPEND_LINE_END
             || cmd == CMD_OPEN_BELOW
             || cmd == CMD_OPEN_ABOVE
-            || cmd == CMD_CHANGE);
+            || cmd == CMD_CHANGE
+            || cmd == CMD_CHANGE_NOYANK);
 
     // In Insert mode, handle special keys and text input
     if sim.mode == Mode::Insert {
@@ -176,6 +250,38 @@ pub(super) fn execute_command(sim: &mut HelixSimulator, cmd: &str) -> Result<(),
         movement::move_prev_word_start(sim, 1)?;
     } else if cmd == CMD_MOVE_WORD_END {
         movement::move_next_word_end(sim, 1)?;
+    } else if cmd == CMD_MOVE_LONG_WORD_FORWARD {
+        movement::move_next_long_word_start(sim, 1)?;
+    } else if cmd == CMD_MOVE_LONG_WORD_BACKWARD {
+        movement::move_prev_long_word_start(sim, 1)?;
+    } else if cmd == CMD_MOVE_LONG_WORD_END {
+        movement::move_next_long_word_end(sim, 1)?;
+    }
+    // Paragraph movement
+    else if cmd == CMD_GOTO_NEXT_PARAGRAPH {
+        movement::move_next_paragraph(sim, 1)?;
+    } else if cmd == CMD_GOTO_PREV_PARAGRAPH {
+        movement::move_prev_paragraph(sim, 1)?;
+    }
+    // View mode and scrolling
+    else if cmd == CMD_ALIGN_VIEW_CENTER {
+        sim.align_view_center()?;
+    } else if cmd == CMD_ALIGN_VIEW_TOP {
+        sim.align_view_top()?;
+    } else if cmd == CMD_ALIGN_VIEW_BOTTOM {
+        sim.align_view_bottom()?;
+    } else if cmd == CMD_SCROLL_DOWN {
+        sim.scroll_view(ScrollDirection::Down, 1)?;
+    } else if cmd == CMD_SCROLL_UP {
+        sim.scroll_view(ScrollDirection::Up, 1)?;
+    } else if cmd == CMD_PAGE_CURSOR_HALF_DOWN {
+        sim.scroll_with_cursor(ScrollDirection::Down, sim.half_page())?;
+    } else if cmd == CMD_PAGE_CURSOR_HALF_UP {
+        sim.scroll_with_cursor(ScrollDirection::Up, sim.half_page())?;
+    } else if cmd == CMD_PAGE_DOWN {
+        sim.scroll_view(ScrollDirection::Down, sim.full_page())?;
+    } else if cmd == CMD_PAGE_UP {
+        sim.scroll_view(ScrollDirection::Up, sim.full_page())?;
     }
     // Line movement
     else if cmd == CMD_MOVE_LINE_START {
@@ -195,9 +301,17 @@ pub(super) fn execute_command(sim: &mut HelixSimulator, cmd: &str) -> Result<(),
     } else if cmd == CMD_DELETE_LINE {
         editing::delete_line(sim)?;
     } else if cmd == CMD_CHANGE {
-        sim.change_selection()?;
+        sim.change_selection(true)?;
+    } else if cmd == CMD_DELETE_NOYANK {
+        editing::delete_selection_noyank(sim)?;
+    } else if cmd == CMD_CHANGE_NOYANK {
+        sim.change_selection(false)?;
     } else if cmd == CMD_JOIN_LINES {
         editing::join_lines(sim)?;
+    } else if cmd == CMD_ADD_NEWLINE_BELOW {
+        editing::add_newline_below(sim)?;
+    } else if cmd == CMD_ADD_NEWLINE_ABOVE {
+        editing::add_newline_above(sim)?;
     }
     // Indentation
     else if cmd == CMD_INDENT {
@@ -226,6 +340,12 @@ pub(super) fn execute_command(sim: &mut HelixSimulator, cmd: &str) -> Result<(),
         sim.open_below()?;
     } else if cmd == CMD_OPEN_ABOVE {
         sim.open_above()?;
+    } else if cmd == CMD_SELECT_MODE {
+        // `v` toggles select mode; the selection itself is kept either way
+        sim.mode = match sim.mode {
+            Mode::Select => Mode::Normal,
+            _ => Mode::Select,
+        };
     } else if cmd == CMD_ESCAPE {
         sim.mode = Mode::Normal;
     }
@@ -243,7 +363,7 @@ pub(super) fn execute_command(sim: &mut HelixSimulator, cmd: &str) -> Result<(),
         sim.undo()?;
     } else if cmd == CMD_REDO {
         sim.redo()?;
-    } else if cmd == "ctrl-r" {
+    } else if cmd == CMD_REDO_ALT {
         // Alternative redo binding
         sim.redo()?;
     } else {
@@ -251,14 +371,16 @@ pub(super) fn execute_command(sim: &mut HelixSimulator, cmd: &str) -> Result<(),
         return Err(UserError::OperationFailed);
     }
 
-    // If command succeeded and should be recorded, record it
+    if leaving_select && sim.mode == Mode::Select {
+        sim.mode = Mode::Normal;
+    }
+
+    // If command succeeded and should be recorded, record it. Edits made in
+    // select mode are recorded as normal mode ones, since that is the mode
+    // they leave behind and `.` is pressed in.
     if should_record {
-        // Convert Mode from simulator to repeat module
-        let repeat_mode = match mode_before {
-            Mode::Normal => crate::helix::repeat::Mode::Normal,
-            Mode::Insert => crate::helix::repeat::Mode::Insert,
-        };
-        sim.repeat_buffer.record_command(key_events, repeat_mode);
+        sim.repeat_buffer
+            .record_command(key_events, crate::helix::repeat::Mode::Normal);
     }
 
     // If we just entered insert mode, start recording
@@ -1,19 +1,62 @@
 //! Movement commands
 
-use crate::helix::simulator::HelixSimulator;
+use crate::helix::simulator::{HelixSimulator, Mode};
 use crate::security::UserError;
 use helix_core::{
-    Selection,
+    Range, RopeSlice, Selection,
     doc_formatter::TextFormat,
     movement::{self, Movement},
     text_annotations::TextAnnotations,
 };
 
+/// Word motion from helix-core (`move_next_word_start`, `move_prev_long_word_start`, ...)
+type WordMotion = fn(RopeSlice, Range, usize) -> Range;
+
+/// Movement behavior for the current mode: select mode extends the selection
+fn behavior(sim: &HelixSimulator) -> Movement {
+    if sim.mode == Mode::Select {
+        Movement::Extend
+    } else {
+        Movement::Move
+    }
+}
+
+/// Apply a word motion to every range
+///
+/// In select mode the selection is extended up to the motion's cursor, like
+/// Helix's `extend_next_word_start` family.
+fn word_motion(sim: &mut HelixSimulator, count: usize, motion: WordMotion) {
+    let slice = sim.doc.slice(..);
+    let extend = sim.mode == Mode::Select;
+    let new_selection = sim.selection.clone().transform(|range| {
+        let word = motion(slice, range, count);
+        if extend {
+            range.put_cursor(slice, word.cursor(slice), true)
+        } else {
+            word
+        }
+    });
+
+    sim.selection = new_selection;
+}
+
+/// Move the primary cursor to `pos`, extending the selection in select mode
+fn place_cursor(sim: &mut HelixSimulator, pos: usize) {
+    sim.selection = if sim.mode == Mode::Select {
+        let slice = sim.doc.slice(..);
+        let range = sim.selection.primary().put_cursor(slice, pos, true);
+        Selection::single(range.anchor, range.head)
+    } else {
+        Selection::point(pos)
+    };
+}
+
 /// Move left by count characters
 pub(super) fn move_left(sim: &mut HelixSimulator, count: usize) -> Result<(), UserError> {
     use helix_core::movement::Direction;
 
     let slice = sim.doc.slice(..);
+    let behavior = behavior(sim);
     let text_fmt = TextFormat::default();
     let mut annotations = TextAnnotations::default();
 
@@ -23,7 +66,7 @@ pub(super) fn move_left(sim: &mut HelixSimulator, count: usize) -> Result<(), Us
             range,
             Direction::Backward,
             count,
-            Movement::Move,
+            behavior,
             &text_fmt,
             &mut annotations,
         )
@@ -38,6 +81,7 @@ pub(super) fn move_right(sim: &mut HelixSimulator, count: usize) -> Result<(), U
     use helix_core::movement::Direction;
 
     let slice = sim.doc.slice(..);
+    let behavior = behavior(sim);
     let text_fmt = TextFormat::default();
     let mut annotations = TextAnnotations::default();
 
@@ -47,7 +91,7 @@ pub(super) fn move_right(sim: &mut HelixSimulator, count: usize) -> Result<(), U
             range,
             Direction::Forward,
             count,
-            Movement::Move,
+            behavior,
             &text_fmt,
             &mut annotations,
         )
@@ -62,6 +106,7 @@ pub(super) fn move_down(sim: &mut HelixSimulator, count: usize) -> Result<(), Us
     use helix_core::movement::Direction;
 
     let slice = sim.doc.slice(..);
+    let behavior = behavior(sim);
     let text_fmt = TextFormat::default();
     let mut annotations = TextAnnotations::default();
 
@@ -71,7 +116,7 @@ pub(super) fn move_down(sim: &mut HelixSimulator, count: usize) -> Result<(), Us
             range,
             Direction::Forward,
             count,
-            Movement::Move,
+            behavior,
             &text_fmt,
             &mut annotations,
         )
@@ -86,6 +131,7 @@ pub(super) fn move_up(sim: &mut HelixSimulator, count: usize) -> Result<(), User
     use helix_core::movement::Direction;
 
     let slice = sim.doc.slice(..);
+    let behavior = behavior(sim);
     let text_fmt = TextFormat::default();
     let mut annotations = TextAnnotations::default();
 
@@ -95,7 +141,7 @@ pub(super) fn move_up(sim: &mut HelixSimulator, count: usize) -> Result<(), User
             range,
             Direction::Backward,
             count,
-            Movement::Move,
+            behavior,
             &text_fmt,
             &mut annotations,
         )
@@ -110,13 +156,7 @@ pub(super) fn move_next_word_start(
     sim: &mut HelixSimulator,
     count: usize,
 ) -> Result<(), UserError> {
-    let slice = sim.doc.slice(..);
-    let new_selection = sim
-        .selection
-        .clone()
-        .transform(|range| movement::move_next_word_start(slice, range, count));
-
-    sim.selection = new_selection;
+    word_motion(sim, count, movement::move_next_word_start);
     Ok(())
 }
 
@@ -125,25 +165,40 @@ pub(super) fn move_prev_word_start(
     sim: &mut HelixSimulator,
     count: usize,
 ) -> Result<(), UserError> {
-    let slice = sim.doc.slice(..);
-    let new_selection = sim
-        .selection
-        .clone()
-        .transform(|range| movement::move_prev_word_start(slice, range, count));
-
-    sim.selection = new_selection;
+    word_motion(sim, count, movement::move_prev_word_start);
     Ok(())
 }
 
 /// Move to end of next word
 pub(super) fn move_next_word_end(sim: &mut HelixSimulator, count: usize) -> Result<(), UserError> {
-    let slice = sim.doc.slice(..);
-    let new_selection = sim
-        .selection
-        .clone()
-        .transform(|range| movement::move_next_word_end(slice, range, count));
+    word_motion(sim, count, movement::move_next_word_end);
+    Ok(())
+}
 
-    sim.selection = new_selection;
+/// Move to start of next WORD (whitespace-delimited)
+pub(super) fn move_next_long_word_start(
+    sim: &mut HelixSimulator,
+    count: usize,
+) -> Result<(), UserError> {
+    word_motion(sim, count, movement::move_next_long_word_start);
+    Ok(())
+}
+
+/// Move to start of previous WORD (whitespace-delimited)
+pub(super) fn move_prev_long_word_start(
+    sim: &mut HelixSimulator,
+    count: usize,
+) -> Result<(), UserError> {
+    word_motion(sim, count, movement::move_prev_long_word_start);
+    Ok(())
+}
+
+/// Move to end of next WORD (whitespace-delimited)
+pub(super) fn move_next_long_word_end(
+    sim: &mut HelixSimulator,
+    count: usize,
+) -> Result<(), UserError> {
+    word_motion(sim, count, movement::move_next_long_word_end);
     Ok(())
 }
 
@@ -153,7 +208,7 @@ pub(super) fn move_line_start(sim: &mut HelixSimulator) -> Result<(), UserError>
     let line = sim.doc.char_to_line(head);
     let line_start = sim.doc.line_to_char(line);
 
-    sim.selection = Selection::point(line_start);
+    place_cursor(sim, line_start);
     Ok(())
 }
 
@@ -169,19 +224,63 @@ pub(super) fn move_line_end(sim: &mut HelixSimulator) -> Result<(), UserError> {
         sim.doc.len_chars()
     };
 
-    sim.selection = Selection::point(line_end);
+    place_cursor(sim, line_end);
     Ok(())
 }
 
 /// Move to start of document
 pub(super) fn move_document_start(sim: &mut HelixSimulator) -> Result<(), UserError> {
-    sim.selection = Selection::point(0);
+    place_cursor(sim, 0);
     Ok(())
 }
 
 /// Move to end of document
+///
+/// Like Helix, the cursor lands at the start of the last line, skipping the
+/// empty line after a trailing newline.
 pub(super) fn move_document_end(sim: &mut HelixSimulator) -> Result<(), UserError> {
-    let end = sim.doc.len_chars();
-    sim.selection = Selection::point(end);
+    let last_line = sim.doc.len_lines() - 1;
+    let line = if sim.doc.line(last_line).len_chars() == 0 {
+        last_line.saturating_sub(1)
+    } else {
+        last_line
+    };
+    let start = sim.doc.line_to_char(line);
+    place_cursor(sim, start);
+    Ok(())
+}
+
+/// Move to start of next paragraph (`]p`)
+///
+/// The cursor lands on the first line of the next paragraph, or at the end
+/// of the document when there is no paragraph below.
+pub(super) fn move_next_paragraph(sim: &mut HelixSimulator, count: usize) -> Result<(), UserError> {
+    let slice = sim.doc.slice(..);
+    let behavior = behavior(sim);
+    let new_selection = sim.selection.clone().transform(|range| {
+        let moved = movement::move_next_paragraph(slice, range, count, behavior);
+        match behavior {
+            Movement::Extend => moved,
+            Movement::Move => Range::point(moved.head),
+        }
+    });
+
+    sim.selection = new_selection;
+    Ok(())
+}
+
+/// Move to start of current or previous paragraph (`[p`)
+pub(super) fn move_prev_paragraph(sim: &mut HelixSimulator, count: usize) -> Result<(), UserError> {
+    let slice = sim.doc.slice(..);
+    let behavior = behavior(sim);
+    let new_selection = sim.selection.clone().transform(|range| {
+        let moved = movement::move_prev_paragraph(slice, range, count, behavior);
+        match behavior {
+            Movement::Extend => moved,
+            Movement::Move => Range::point(moved.head),
+        }
+    });
+
+    sim.selection = new_selection;
     Ok(())
 }
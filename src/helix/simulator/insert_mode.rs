@@ -5,11 +5,12 @@ use crate::security::UserError;
 use helix_core::{Selection, Transaction};
 
 impl HelixSimulator {
-    /// Append: move cursor one position right and enter insert mode
+    /// Append: enter insert mode after the end of the selection
     pub(super) fn append(&mut self) -> Result<(), UserError> {
-        // Move cursor one position to the right (after current character)
-        let head = self.selection.primary().head;
-        let new_pos = (head + 1).min(self.doc.len_chars());
+        // A bare cursor covers one character, so this is the position right
+        // after it; a selection made by `e` appends after the selected word
+        let slice = self.doc.slice(..);
+        let new_pos = self.selection.primary().min_width_1(slice).to();
         self.selection = Selection::point(new_pos);
         self.mode = Mode::Insert;
         Ok(())
@@ -121,30 +122,25 @@ impl HelixSimulator {
         Ok(())
     }
 
-    /// Change selection: delete current character and enter insert mode
-    pub(super) fn change_selection(&mut self) -> Result<(), UserError> {
-        // Change selection: delete current character and enter insert mode
-        // This is similar to delete_char but also enters insert mode
-        let head = self.selection.primary().head;
-
-        // Don't delete if at end of document
-        if head >= self.doc.len_chars() {
-            self.mode = Mode::Insert;
-            return Ok(());
+    /// Change selection: delete it and enter insert mode
+    ///
+    /// A cursor without a selection deletes the character under it. With
+    /// `yank` the deleted text goes to the default register (`c`); without it
+    /// the registers are left untouched (`Alt-c`).
+    pub(super) fn change_selection(&mut self, yank: bool) -> Result<(), UserError> {
+        let ranges = self.selection_ranges();
+        if yank {
+            let values = self.fragments(&ranges);
+            self.yank_values(values);
         }
 
-        // Delete current character (don't delete newlines)
-        let current_char = self.doc.char(head);
-        if current_char != '\n' {
-            let transaction =
-                Transaction::change_by_selection(&self.doc, &self.selection, |range| {
-                    let start = range.from();
-                    let end = start.saturating_add(1).min(self.doc.len_chars()).max(start);
-                    (start, end, None)
-                });
-
-            self.apply_transaction(transaction);
-        }
+        // Map the selection so every cursor ends where its text was removed
+        let transaction = Transaction::change(
+            &self.doc,
+            ranges.into_iter().map(|(start, end)| (start, end, None)),
+        );
+        self.selection = self.selection.clone().map(transaction.changes());
+        self.apply_transaction(transaction);
 
         // Enter insert mode
         self.mode = Mode::Insert;
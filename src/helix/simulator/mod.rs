@@ -6,7 +6,9 @@
 
 mod commands;
 mod insert_mode;
+mod registers;
 mod undo;
+mod view;
 
 #[cfg(test)]
 mod tests;
@@ -14,17 +16,31 @@ mod tests;
 use crate::game::{CursorPosition, EditorState};
 use crate::helix::repeat::RepeatBuffer;
 use crate::security::UserError;
+use crate::security::limits::MAX_INDENT_WIDTH;
 use helix_core::{Rope, Selection, Transaction};
+use registers::Registers;
+use std::borrow::Cow;
 
 // Re-export Mode for convenience
 pub use Mode::*;
+pub use view::{DEFAULT_VIEWPORT_HEIGHT, Viewport};
 
 /// Maximum recursion depth for repeat command to prevent infinite loops
 /// This allows for reasonable chaining (e.g., recording a repeat within a macro)
 /// while preventing stack overflow from accidental infinite recursion
 const MAX_REPEAT_DEPTH: usize = 100;
 
-/// Editor mode (Normal or Insert)
+/// Indentation added by `>` unless the trainer is configured otherwise
+pub const DEFAULT_INDENT_UNIT: &str = "  ";
+
+/// Whether an indent unit is a tab or 1 to `MAX_INDENT_WIDTH` spaces
+pub fn is_valid_indent_unit(unit: &str) -> bool {
+    let spaces =
+        !unit.is_empty() && unit.len() <= MAX_INDENT_WIDTH && unit.chars().all(|c| c == ' ');
+    unit == "\t" || spaces
+}
+
+/// Editor mode (Normal, Insert or Select)
 ///
 /// Controls which operations are available and how input is interpreted.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -33,12 +49,15 @@ pub enum Mode {
     Normal,
     /// Insert mode: insert characters
     Insert,
+    /// Select mode: motions extend the selection
+    Select,
 }
 
 /// Helix editor simulator using helix-core text primitives
 ///
 /// Provides a faithful simulation of Helix editor operations with proper
 /// unicode handling, undo/redo support, and multi-cursor awareness.
+#[derive(Clone)]
 pub struct HelixSimulator {
     /// Text buffer (using Rope for efficient edits)
     pub(super) doc: Rope,
@@ -52,8 +71,8 @@ pub struct HelixSimulator {
     /// Undo history stack storing both transactions and previous document states
     pub(super) history: Vec<(Transaction, Rope)>,
 
-    /// Clipboard for yank and paste operations
-    pub(super) clipboard: Option<String>,
+    /// Registers for yank and paste operations
+    pub(super) registers: Registers,
 
     /// Repeat buffer for recording and replaying actions
     pub(super) repeat_buffer: RepeatBuffer,
@@ -63,6 +82,12 @@ pub struct HelixSimulator {
 
     /// Current recursion depth for repeat command (protects against infinite loops)
     pub(super) repeat_depth: usize,
+
+    /// Visible window of the document (top line and height)
+    pub(super) viewport: Viewport,
+
+    /// Indentation added by `>` and removed by `<`
+    pub(super) indent_unit: Cow<'static, str>,
 }
 
 impl HelixSimulator {
@@ -73,10 +98,12 @@ impl HelixSimulator {
             selection: Selection::point(0),
             mode: Mode::Normal,
             history: Vec::new(),
-            clipboard: None,
+            registers: Registers::new(),
             repeat_buffer: RepeatBuffer::new(),
             is_repeating: false,
             repeat_depth: 0,
+            viewport: Viewport::default(),
+            indent_unit: Cow::Borrowed(DEFAULT_INDENT_UNIT),
         }
     }
 
@@ -115,10 +142,12 @@ impl HelixSimulator {
             selection: Selection::point(safe_pos),
             mode: Mode::Normal,
             history: Vec::new(),
-            clipboard: None,
+            registers: Registers::new(),
             repeat_buffer: RepeatBuffer::new(),
             is_repeating: false,
             repeat_depth: 0,
+            viewport: Viewport::default(),
+            indent_unit: Cow::Borrowed(DEFAULT_INDENT_UNIT),
         }
     }
 
@@ -130,26 +159,47 @@ impl HelixSimulator {
     }
 
     /// Get current editor state
+    ///
+    /// The cursor is the primary selection's head; every selection range is
+    /// reported with its anchor as `start` and head as `end`.
     pub fn get_state(&self) -> Result<EditorState, UserError> {
-        let mut head = self.selection.primary().head;
+        let cursor = self.position_of(self.selection.primary().head)?;
+        let selections = self
+            .selection
+            .iter()
+            .map(|range| {
+                Ok(crate::game::Selection::new(
+                    self.position_of(range.anchor)?,
+                    self.position_of(range.head)?,
+                ))
+            })
+            .collect::<Result<Vec<_>, UserError>>()?;
+
+        EditorState::new(self.doc.to_string(), cursor, None)
+            .and_then(|state| state.with_selections(selections, self.selection.primary_index()))
+            .map_err(|_| UserError::OperationFailed)
+    }
 
-        // Clamp cursor to valid bounds (sometimes helix-core can put it past end)
-        let max_pos = self.doc.len_chars();
-        if head > max_pos {
-            head = max_pos;
-        }
+    /// Convert a char index to a (line, col) position
+    fn position_of(&self, pos: usize) -> Result<CursorPosition, UserError> {
+        // Clamp to valid bounds (sometimes helix-core can put it past end)
+        let pos = pos.min(self.doc.len_chars());
 
-        // Convert head position to (line, col)
-        let line = self.doc.char_to_line(head);
-        let line_start = self.doc.line_to_char(line);
-        let col = head - line_start;
+        let line = self.doc.char_to_line(pos);
+        let col = pos - self.doc.line_to_char(line);
+        CursorPosition::new(line, col).map_err(|_| UserError::OperationFailed)
+    }
 
-        EditorState::new(
-            self.doc.to_string(),
-            CursorPosition::new(line, col).map_err(|_| UserError::OperationFailed)?,
-            None,
+    /// Describe everything that decides how later commands behave
+    ///
+    /// Two simulators with the same fingerprint react the same way to every
+    /// command except undo, redo and scrolling, which also depend on the
+    /// history and the view. Used to skip repeated states when searching.
+    pub fn fingerprint(&self) -> String {
+        format!(
+            "{}\0{:?}\0{:?}\0{:?}\0{:?}",
+            self.doc, self.selection, self.mode, self.registers, self.repeat_buffer
         )
-        .map_err(|_| UserError::OperationFailed)
     }
 
     /// Convert simulator state to EditorState (alias for get_state)
@@ -157,11 +207,108 @@ impl HelixSimulator {
         self.get_state()
     }
 
+    /// Convert a (line, col) position to a char index
+    fn char_of(&self, pos: CursorPosition) -> Result<usize, UserError> {
+        if pos.row >= self.doc.len_lines() {
+            return Err(UserError::OperationFailed);
+        }
+        let line_start = self.doc.line_to_char(pos.row);
+        // The last valid column is the line break, or the end of the last line
+        let line_end = if pos.row + 1 < self.doc.len_lines() {
+            self.doc.line_to_char(pos.row + 1) - 1
+        } else {
+            self.doc.len_chars()
+        };
+        if line_start + pos.col > line_end {
+            return Err(UserError::OperationFailed);
+        }
+        Ok(line_start + pos.col)
+    }
+
+    /// Replace all selections, e.g. with the ones from a scenario setup
+    ///
+    /// Each selection's `start` becomes the anchor and `end` the head.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `selections` is empty, the primary index is out of
+    /// range or a position lies outside the document.
+    pub fn set_selections(
+        &mut self,
+        selections: &[crate::game::Selection],
+        primary_index: usize,
+    ) -> Result<(), UserError> {
+        if primary_index >= selections.len() {
+            return Err(UserError::OperationFailed);
+        }
+        let ranges = selections
+            .iter()
+            .map(|sel| {
+                Ok(helix_core::Range::new(
+                    self.char_of(sel.start)?,
+                    self.char_of(sel.end)?,
+                ))
+            })
+            .collect::<Result<Vec<_>, UserError>>()?;
+
+        self.selection = Selection::new(ranges.into_iter().collect(), primary_index);
+        Ok(())
+    }
+
     /// Get current mode
     pub fn mode(&self) -> Mode {
         self.mode
     }
 
+    /// Get the indentation `>` adds
+    pub fn indent_unit(&self) -> &str {
+        &self.indent_unit
+    }
+
+    /// Set the indentation `>` adds and `<` removes, like Helix's
+    /// `indent.unit`
+    pub fn set_indent_unit(&mut self, unit: &str) {
+        self.indent_unit = Cow::Owned(unit.to_string());
+    }
+
+    /// Switch the mode without running a command, e.g. for a scenario setup
+    ///
+    /// Starting in insert mode also starts recording the insertion for `.`.
+    pub fn set_mode(&mut self, mode: Mode) {
+        if mode == Mode::Insert && self.mode != Mode::Insert {
+            self.repeat_buffer.insert_recorder_mut().start();
+        }
+        self.mode = mode;
+    }
+
+    /// Pre-record the action that `.` repeats
+    ///
+    /// The commands run on a scratch copy of the document, so only the
+    /// recorded action is kept. They must leave the editor in normal mode.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a command fails, the commands end outside normal
+    /// mode or nothing repeatable was recorded.
+    pub fn seed_last_action(&mut self, commands: &[String]) -> Result<(), UserError> {
+        let mut scratch = Self::new(self.doc.to_string());
+        scratch.selection = self.selection.clone();
+        for cmd in commands {
+            scratch.execute_command(cmd)?;
+        }
+        if scratch.mode != Mode::Normal {
+            return Err(UserError::OperationFailed);
+        }
+
+        let action = scratch
+            .repeat_buffer
+            .last_action()
+            .cloned()
+            .ok_or(UserError::OperationFailed)?;
+        self.repeat_buffer.set_last_action(action);
+        Ok(())
+    }
+
     /// Get a reference to the repeat buffer
     ///
     /// Allows inspection of the last recorded action for debugging or testing.
@@ -177,6 +324,20 @@ impl HelixSimulator {
         transaction.apply(&mut self.doc);
     }
 
+    /// Char range of every selection, as `(from, to)` pairs for editing
+    ///
+    /// A cursor without a selection covers the character under it.
+    pub(super) fn selection_ranges(&self) -> Vec<(usize, usize)> {
+        let slice = self.doc.slice(..);
+        self.selection
+            .iter()
+            .map(|range| {
+                let range = range.min_width_1(slice);
+                (range.from(), range.to())
+            })
+            .collect()
+    }
+
     /// Execute the repeat (`.`) command
     ///
     /// Replays the last recorded action. If no action has been recorded,
@@ -191,15 +352,17 @@ impl HelixSimulator {
     /// # Examples
     ///
     /// ```no_run
+    /// use helix_trainer::helix::HelixSimulator;
+    ///
     /// let mut sim = HelixSimulator::new("hello".to_string());
     ///
     /// // Delete a character
     /// sim.execute_command("x").unwrap();
-    /// assert_eq!(sim.text(), "ello");
+    /// assert_eq!(sim.get_state().unwrap().content(), "ello");
     ///
     /// // Repeat the delete
     /// sim.execute_command(".").unwrap();
-    /// assert_eq!(sim.text(), "llo");
+    /// assert_eq!(sim.get_state().unwrap().content(), "llo");
     /// ```
     pub(super) fn execute_repeat(&mut self) -> Result<(), UserError> {
         // Check recursion depth to prevent infinite loops
@@ -244,6 +407,7 @@ impl HelixSimulator {
                 let current_mode = match self.mode {
                     Mode::Normal => crate::helix::repeat::Mode::Normal,
                     Mode::Insert => crate::helix::repeat::Mode::Insert,
+                    Mode::Select => crate::helix::repeat::Mode::Select,
                 };
 
                 // If mode doesn't match, this is a no-op (Vim/Helix semantics)
@@ -307,7 +471,7 @@ impl HelixSimulator {
 /// - The key code is not a known command
 fn key_events_to_cmd(keys: &[crossterm::event::KeyEvent]) -> Result<String, UserError> {
     use crate::helix::commands::*;
-    use crossterm::event::KeyCode;
+    use crossterm::event::{KeyCode, KeyModifiers};
 
     if keys.is_empty() {
         return Err(UserError::OperationFailed);
@@ -321,6 +485,10 @@ fn key_events_to_cmd(keys: &[crossterm::event::KeyEvent]) -> Result<String, User
         return match (ch1, ch2) {
             ('d', 'd') => Ok(CMD_DELETE_LINE.to_string()),
             ('g', 'g') => Ok(CMD_GOTO_FILE_START.to_string()),
+            (']', 'p') => Ok(CMD_GOTO_NEXT_PARAGRAPH.to_string()),
+            ('[', 'p') => Ok(CMD_GOTO_PREV_PARAGRAPH.to_string()),
+            (']', ' ') => Ok(CMD_ADD_NEWLINE_BELOW.to_string()),
+            ('[', ' ') => Ok(CMD_ADD_NEWLINE_ABOVE.to_string()),
             ('r', _) => Ok(format!("r{}", ch2)), // Replace command
             _ => Err(UserError::OperationFailed), // Unknown multi-key sequence
         };
@@ -329,6 +497,9 @@ fn key_events_to_cmd(keys: &[crossterm::event::KeyEvent]) -> Result<String, User
     // Single key command
     if keys.len() == 1 {
         return match keys[0].code {
+            KeyCode::Char(ch) if keys[0].modifiers.contains(KeyModifiers::ALT) => {
+                Ok(format!("alt-{}", ch))
+            }
             KeyCode::Char(ch) => Ok(ch.to_string()),
             KeyCode::Esc => Ok(CMD_ESCAPE.to_string()),
             KeyCode::Backspace => Ok(CMD_BACKSPACE.to_string()),
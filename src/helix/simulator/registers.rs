@@ -0,0 +1,77 @@
+//! Registers for yank, delete and paste
+
+use super::HelixSimulator;
+use std::collections::BTreeMap;
+
+/// Register used when no register is selected
+pub const DEFAULT_REGISTER: char = '"';
+
+/// Register that discards everything written to it
+pub const BLACKHOLE_REGISTER: char = '_';
+
+/// Named registers holding one value per selection
+///
+/// A value ending in a newline is linewise: it is pasted on its own line
+/// instead of inside the current line.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Registers {
+    values: BTreeMap<char, Vec<String>>,
+}
+
+impl Registers {
+    /// Create an empty register set
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Read the values stored in a register
+    ///
+    /// Returns `None` for empty registers and for the blackhole register.
+    pub fn read(&self, name: char) -> Option<&[String]> {
+        self.values
+            .get(&name)
+            .map(Vec::as_slice)
+            .filter(|values| !values.is_empty())
+    }
+
+    /// Replace the values stored in a register
+    ///
+    /// Writes to the blackhole register are ignored.
+    pub fn write(&mut self, name: char, values: Vec<String>) {
+        if name == BLACKHOLE_REGISTER {
+            return;
+        }
+        self.values.insert(name, values);
+    }
+}
+
+/// Check whether a register value is linewise (ends with a line break)
+pub(super) fn is_linewise(value: &str) -> bool {
+    value.ends_with('\n')
+}
+
+impl HelixSimulator {
+    /// Fill a register, e.g. from a scenario setup
+    pub fn set_register(&mut self, name: char, values: Vec<String>) {
+        self.registers.write(name, values);
+    }
+
+    /// Store yanked or deleted text in the default register (one value per selection)
+    ///
+    /// Nothing is written when every value is empty, so the register keeps
+    /// its previous content.
+    pub(super) fn yank_values(&mut self, values: Vec<String>) {
+        if values.iter().all(String::is_empty) {
+            return;
+        }
+        self.registers.write(DEFAULT_REGISTER, values);
+    }
+
+    /// Text of each `(from, to)` char range, for yanking
+    pub(super) fn fragments(&self, ranges: &[(usize, usize)]) -> Vec<String> {
+        ranges
+            .iter()
+            .map(|&(from, to)| self.doc.slice(from..to).to_string())
+            .collect()
+    }
+}
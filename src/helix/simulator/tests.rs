@@ -208,8 +208,8 @@ fn test_move_word_end() {
 fn test_move_prev_word() {
     let mut sim = HelixSimulator::new("hello world foo".to_string());
 
-    // Move to end of document first
-    sim.execute_command("G").unwrap();
+    // Move to end of line first
+    sim.execute_command("$").unwrap();
     // Then move to previous word
     sim.execute_command("b").unwrap();
 
@@ -233,6 +233,18 @@ fn test_append_mode() {
     assert_eq!(state.cursor_position().col, 1); // Moved one right
 }
 
+#[test]
+fn test_append_after_selection() {
+    let mut sim = HelixSimulator::new("hello world".to_string());
+
+    // 'e' selects "hello"; appending goes right after it
+    sim.execute_command("e").unwrap();
+    sim.execute_command("a").unwrap();
+    sim.execute_command("!").unwrap();
+
+    assert_eq!(sim.get_state().unwrap().content(), "hello! world");
+}
+
 #[test]
 fn test_open_below() {
     let mut sim = HelixSimulator::new("line1\nline2".to_string());
@@ -588,6 +600,23 @@ fn test_dedent_line() {
     assert_eq!(state.cursor_position().col, 0);
 }
 
+#[test]
+fn test_dedent_line_on_later_line() {
+    let mut sim = HelixSimulator::new("hello\n  world".to_string());
+    sim.execute_command("j").unwrap();
+    for _ in 0..3 {
+        sim.execute_command("l").unwrap();
+    }
+
+    // The cursor stays on the same character ('o')
+    sim.execute_command("<").unwrap();
+
+    let state = sim.get_state().unwrap();
+    assert_eq!(state.content(), "hello\nworld");
+    assert_eq!(state.cursor_position().row, 1);
+    assert_eq!(state.cursor_position().col, 1);
+}
+
 #[test]
 fn test_dedent_line_with_one_space() {
     let mut sim = HelixSimulator::new(" hello".to_string());
@@ -624,6 +653,20 @@ fn test_multiple_indent() {
     assert_eq!(state.cursor_position().col, 4);
 }
 
+#[test]
+fn test_indent_with_configured_unit() {
+    let mut sim = HelixSimulator::new("code\n".to_string());
+    sim.set_indent_unit("\t");
+
+    sim.execute_command(">").unwrap();
+    sim.execute_command(">").unwrap();
+    assert_eq!(sim.get_state().unwrap().content(), "\t\tcode\n");
+    assert_eq!(sim.get_state().unwrap().cursor_position().col, 2);
+
+    sim.execute_command("<").unwrap();
+    assert_eq!(sim.get_state().unwrap().content(), "\tcode\n");
+}
+
 // ============================================================================
 // Phase 2: Repeat Buffer Integration Tests
 // ============================================================================
@@ -1160,6 +1203,20 @@ fn test_repeat_replace_char() {
     assert_eq!(state.content(), "xxllo");
 }
 
+#[test]
+fn test_repeat_replace_with_non_command_char() {
+    let mut sim = HelixSimulator::new("a-b-c".to_string());
+
+    // '_' is not a command key, but it is still part of the replace
+    sim.execute_command("l").unwrap();
+    sim.execute_command("r_").unwrap();
+    sim.execute_command("l").unwrap();
+    sim.execute_command("l").unwrap();
+    sim.execute_command(".").unwrap();
+
+    assert_eq!(sim.get_state().unwrap().content(), "a_b_c");
+}
+
 #[test]
 fn test_repeat_append() {
     let mut sim = HelixSimulator::new("hello".to_string());
@@ -1352,3 +1409,756 @@ fn test_repeat_append_at_line_end() {
     let state = sim.get_state().unwrap();
     assert_eq!(state.content(), "!!hello!!");
 }
+
+// ============================================================================
+// Unimpaired Bracket Command Tests
+// ============================================================================
+
+#[test]
+fn test_next_paragraph() {
+    let mut sim = HelixSimulator::new("one\ntwo\n\nthree\nfour\n\nfive".to_string());
+
+    sim.execute_command("]p").unwrap();
+    let state = sim.get_state().unwrap();
+    assert_eq!(state.cursor_position().row, 3);
+    assert_eq!(state.cursor_position().col, 0);
+
+    sim.execute_command("]p").unwrap();
+    let state = sim.get_state().unwrap();
+    assert_eq!(state.cursor_position().row, 6);
+}
+
+#[test]
+fn test_goto_last_line() {
+    let mut sim = HelixSimulator::new("one\ntwo\nthree".to_string());
+    sim.execute_command("G").unwrap();
+    let state = sim.get_state().unwrap();
+    assert_eq!(state.cursor_position().row, 2);
+    assert_eq!(state.cursor_position().col, 0);
+
+    // The empty line after a trailing newline is skipped
+    let mut sim = HelixSimulator::new("one\ntwo\n".to_string());
+    sim.execute_command("G").unwrap();
+    let state = sim.get_state().unwrap();
+    assert_eq!(state.cursor_position().row, 1);
+    assert_eq!(state.cursor_position().col, 0);
+}
+
+#[test]
+fn test_prev_paragraph() {
+    let mut sim = HelixSimulator::new("one\ntwo\n\nthree\nfour".to_string());
+    sim.execute_command("G").unwrap();
+
+    // First jump goes to the start of the current paragraph
+    sim.execute_command("[p").unwrap();
+    let state = sim.get_state().unwrap();
+    assert_eq!(state.cursor_position().row, 3);
+
+    // Second jump crosses the blank line into the previous paragraph
+    sim.execute_command("[p").unwrap();
+    let state = sim.get_state().unwrap();
+    assert_eq!(state.cursor_position().row, 0);
+}
+
+#[test]
+fn test_paragraph_motions_do_not_edit() {
+    let mut sim = HelixSimulator::new("a\n\nb".to_string());
+
+    sim.execute_command("]p").unwrap();
+    sim.execute_command("[p").unwrap();
+
+    assert_eq!(sim.get_state().unwrap().content(), "a\n\nb");
+    assert!(sim.repeat_buffer().last_action().is_none());
+}
+
+#[test]
+fn test_add_newline_below() {
+    let mut sim = HelixSimulator::new("first\nsecond".to_string());
+    sim.execute_command("l").unwrap();
+
+    sim.execute_command("]Space").unwrap();
+    let state = sim.get_state().unwrap();
+    assert_eq!(state.content(), "first\n\nsecond");
+    assert_eq!(state.cursor_position().row, 0);
+    assert_eq!(state.cursor_position().col, 1);
+}
+
+#[test]
+fn test_add_newline_below_last_line() {
+    let mut sim = HelixSimulator::new("only".to_string());
+
+    sim.execute_command("]Space").unwrap();
+    assert_eq!(sim.get_state().unwrap().content(), "only\n");
+}
+
+#[test]
+fn test_add_newline_above() {
+    let mut sim = HelixSimulator::new("first\nsecond".to_string());
+    sim.execute_command("j").unwrap();
+
+    sim.execute_command("[Space").unwrap();
+    let state = sim.get_state().unwrap();
+    assert_eq!(state.content(), "first\n\nsecond");
+    // Cursor follows its line down
+    assert_eq!(state.cursor_position().row, 2);
+    assert_eq!(state.cursor_position().col, 0);
+}
+
+#[test]
+fn test_add_newline_is_undoable() {
+    let mut sim = HelixSimulator::new("text".to_string());
+
+    sim.execute_command("[Space").unwrap();
+    sim.execute_command("u").unwrap();
+    assert_eq!(sim.get_state().unwrap().content(), "text");
+}
+
+// ============================================================================
+// Viewport and View Mode Tests
+// ============================================================================
+
+/// Build a document with `n` numbered lines
+fn numbered_lines(n: usize) -> String {
+    (1..=n)
+        .map(|i| format!("line {}", i))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[test]
+fn test_viewport_defaults() {
+    let sim = HelixSimulator::new(numbered_lines(100));
+    let viewport = sim.viewport();
+    assert_eq!(viewport.top_line(), 0);
+    assert_eq!(viewport.height(), DEFAULT_VIEWPORT_HEIGHT);
+}
+
+#[test]
+fn test_viewport_follows_cursor() {
+    let mut sim = HelixSimulator::new(numbered_lines(100));
+    sim.set_viewport_height(10);
+
+    sim.execute_command("G").unwrap();
+    let viewport = sim.viewport();
+    assert_eq!(viewport.top_line(), 90);
+    assert_eq!(viewport.visible_lines(100), 90..100);
+
+    sim.execute_command("gg").unwrap();
+    assert_eq!(sim.viewport().top_line(), 0);
+}
+
+#[test]
+fn test_viewport_keeps_scrolloff() {
+    let mut sim = HelixSimulator::new(numbered_lines(100));
+    sim.set_viewport_height(20);
+
+    // Moving to line 15 needs 5 lines of context below it
+    for _ in 0..15 {
+        sim.execute_command("j").unwrap();
+    }
+    assert_eq!(sim.viewport().top_line(), 1);
+}
+
+#[test]
+fn test_align_view_commands() {
+    let mut sim = HelixSimulator::new(numbered_lines(100));
+    sim.set_viewport_height(20);
+    for _ in 0..50 {
+        sim.execute_command("j").unwrap();
+    }
+
+    sim.execute_command("zz").unwrap();
+    assert_eq!(sim.viewport().top_line(), 40);
+
+    sim.execute_command("zt").unwrap();
+    assert_eq!(sim.viewport().top_line(), 45);
+
+    sim.execute_command("zb").unwrap();
+    assert_eq!(sim.viewport().top_line(), 36);
+
+    // Aligning the view never moves the cursor
+    assert_eq!(sim.get_state().unwrap().cursor_position().row, 50);
+}
+
+#[test]
+fn test_align_view_clamped_at_document_edges() {
+    let mut sim = HelixSimulator::new(numbered_lines(30));
+    sim.set_viewport_height(20);
+
+    sim.execute_command("zb").unwrap();
+    assert_eq!(sim.viewport().top_line(), 0);
+
+    sim.execute_command("G").unwrap();
+    sim.execute_command("zt").unwrap();
+    assert_eq!(sim.viewport().top_line(), 10);
+}
+
+#[test]
+fn test_scroll_view_keeps_cursor_until_edge() {
+    let mut sim = HelixSimulator::new(numbered_lines(100));
+    sim.set_viewport_height(20);
+    for _ in 0..10 {
+        sim.execute_command("j").unwrap();
+    }
+
+    sim.execute_command("zj").unwrap();
+    assert_eq!(sim.viewport().top_line(), 1);
+    assert_eq!(sim.get_state().unwrap().cursor_position().row, 10);
+
+    for _ in 0..5 {
+        sim.execute_command("zj").unwrap();
+    }
+    // Cursor pushed down to stay scrolloff lines below the top
+    assert_eq!(sim.viewport().top_line(), 6);
+    assert_eq!(sim.get_state().unwrap().cursor_position().row, 11);
+
+    sim.execute_command("zk").unwrap();
+    assert_eq!(sim.viewport().top_line(), 5);
+}
+
+#[test]
+fn test_half_page_scroll_moves_cursor() {
+    let mut sim = HelixSimulator::new(numbered_lines(100));
+    sim.set_viewport_height(20);
+    sim.execute_command("l").unwrap();
+    sim.execute_command("l").unwrap();
+
+    sim.execute_command("ctrl-d").unwrap();
+    let state = sim.get_state().unwrap();
+    assert_eq!(state.cursor_position().row, 10);
+    assert_eq!(state.cursor_position().col, 2);
+    // View scrolled by half a page, then pulled back to keep scrolloff
+    assert_eq!(sim.viewport().top_line(), 5);
+
+    sim.execute_command("ctrl-u").unwrap();
+    assert_eq!(sim.get_state().unwrap().cursor_position().row, 0);
+    assert_eq!(sim.viewport().top_line(), 0);
+}
+
+#[test]
+fn test_full_page_scroll() {
+    let mut sim = HelixSimulator::new(numbered_lines(100));
+    sim.set_viewport_height(20);
+
+    sim.execute_command("ctrl-f").unwrap();
+    assert_eq!(sim.viewport().top_line(), 20);
+    assert_eq!(sim.get_state().unwrap().cursor_position().row, 25);
+
+    sim.execute_command("ctrl-b").unwrap();
+    assert_eq!(sim.viewport().top_line(), 0);
+    assert_eq!(sim.get_state().unwrap().cursor_position().row, 14);
+}
+
+#[test]
+fn test_scrolling_short_document_does_not_move_view() {
+    let mut sim = HelixSimulator::new(numbered_lines(5));
+
+    sim.execute_command("ctrl-f").unwrap();
+    sim.execute_command("zj").unwrap();
+    assert_eq!(sim.viewport().top_line(), 0);
+    assert_eq!(sim.get_state().unwrap().cursor_position().row, 0);
+}
+
+#[test]
+fn test_view_commands_are_not_repeatable() {
+    let mut sim = HelixSimulator::new(numbered_lines(50));
+    sim.execute_command("x").unwrap();
+    sim.execute_command("ctrl-d").unwrap();
+    sim.execute_command("zz").unwrap();
+
+    // The last repeatable action is still the delete
+    match sim.repeat_buffer().last_action() {
+        Some(crate::helix::repeat::RepeatableAction::Command { keys, .. }) => {
+            assert_eq!(keys.len(), 1);
+            assert_eq!(keys[0].code, crossterm::event::KeyCode::Char('x'));
+        }
+        _ => panic!("Expected the delete to remain the last action"),
+    }
+}
+
+// ============================================================================
+// Long Word and Select Mode Tests
+// ============================================================================
+
+#[test]
+fn test_long_word_forward_skips_punctuation() {
+    let mut sim = HelixSimulator::new("call foo.bar(baz) done".to_string());
+
+    sim.execute_command("W").unwrap();
+    assert_eq!(sim.get_state().unwrap().cursor_position().col, 5);
+
+    sim.execute_command("W").unwrap();
+    assert_eq!(sim.get_state().unwrap().cursor_position().col, 18); // "done"
+}
+
+#[test]
+fn test_word_forward_stops_at_punctuation() {
+    let mut sim = HelixSimulator::new("call foo.bar(baz) done".to_string());
+
+    sim.execute_command("w").unwrap();
+    sim.execute_command("w").unwrap();
+    assert_eq!(sim.get_state().unwrap().cursor_position().col, 8); // "."
+}
+
+#[test]
+fn test_long_word_backward() {
+    let mut sim = HelixSimulator::new("value = foo.bar(baz)".to_string());
+    sim.execute_command("$").unwrap();
+
+    sim.execute_command("B").unwrap();
+    assert_eq!(sim.get_state().unwrap().cursor_position().col, 8);
+}
+
+#[test]
+fn test_long_word_end() {
+    let mut sim = HelixSimulator::new("foo.bar(baz) next".to_string());
+
+    sim.execute_command("E").unwrap();
+    let range = sim.selection.primary();
+    assert_eq!((range.anchor, range.head), (0, 12));
+}
+
+#[test]
+fn test_select_mode_toggle() {
+    let mut sim = HelixSimulator::new("hello world".to_string());
+
+    sim.execute_command("v").unwrap();
+    assert_eq!(sim.mode(), Mode::Select);
+
+    sim.execute_command("v").unwrap();
+    assert_eq!(sim.mode(), Mode::Normal);
+
+    sim.execute_command("v").unwrap();
+    sim.execute_command("Escape").unwrap();
+    assert_eq!(sim.mode(), Mode::Normal);
+}
+
+#[test]
+fn test_select_mode_extends_with_long_words() {
+    let mut sim = HelixSimulator::new("one foo.bar two three".to_string());
+
+    sim.execute_command("v").unwrap();
+    sim.execute_command("W").unwrap();
+    sim.execute_command("W").unwrap();
+
+    // The anchor stays put while the head moves to the start of "two"
+    let range = sim.selection.primary();
+    assert_eq!(range.anchor, 0);
+    assert_eq!(range.head, 12);
+}
+
+#[test]
+fn test_select_mode_extends_horizontally() {
+    let mut sim = HelixSimulator::new("hello".to_string());
+
+    sim.execute_command("l").unwrap();
+    sim.execute_command("v").unwrap();
+    sim.execute_command("l").unwrap();
+    sim.execute_command("l").unwrap();
+
+    let range = sim.selection.primary();
+    assert_eq!(range.from(), 1);
+    assert_eq!(range.to(), 4);
+}
+
+#[test]
+fn test_select_mode_extends_to_line_end() {
+    let mut sim = HelixSimulator::new("hello world".to_string());
+
+    sim.execute_command("v").unwrap();
+    sim.execute_command("$").unwrap();
+
+    let range = sim.selection.primary();
+    assert_eq!(range.anchor, 0);
+    assert_eq!(range.head, 11);
+}
+
+#[test]
+fn test_select_mode_motions_are_not_recorded() {
+    let mut sim = HelixSimulator::new("hello world".to_string());
+    sim.execute_command("x").unwrap();
+    sim.execute_command("v").unwrap();
+    sim.execute_command("W").unwrap();
+
+    match sim.repeat_buffer().last_action() {
+        Some(crate::helix::repeat::RepeatableAction::Command { keys, .. }) => {
+            assert_eq!(keys[0].code, crossterm::event::KeyCode::Char('x'));
+        }
+        _ => panic!("Expected the delete to remain the last action"),
+    }
+}
+
+#[test]
+fn test_select_mode_edit_returns_to_normal() {
+    let mut sim = HelixSimulator::new("abcdef".to_string());
+    sim.execute_command("v").unwrap();
+    sim.execute_command("l").unwrap();
+    sim.execute_command("alt-d").unwrap();
+
+    assert_eq!(sim.mode(), Mode::Normal);
+    assert_eq!(sim.get_state().unwrap().content(), "cdef");
+
+    // Recorded as a normal mode edit, so `.` repeats it
+    sim.execute_command(".").unwrap();
+    assert_eq!(sim.get_state().unwrap().content(), "def");
+}
+
+// ============================================================================
+// Register and Paste Tests
+// ============================================================================
+
+#[test]
+fn test_paste_selects_pasted_text() {
+    let mut sim = HelixSimulator::new("abc".to_string());
+    sim.execute_command("y").unwrap();
+    sim.execute_command("p").unwrap();
+
+    let range = sim.selection.primary();
+    assert_eq!((range.anchor, range.head), (1, 2));
+    assert_eq!(sim.get_state().unwrap().content(), "aabc");
+}
+
+#[test]
+fn test_yank_select_mode_range() {
+    let mut sim = HelixSimulator::new("foo bar".to_string());
+    sim.execute_command("v").unwrap();
+    sim.execute_command("e").unwrap();
+    sim.execute_command("y").unwrap();
+    sim.execute_command("Escape").unwrap();
+    sim.execute_command("$").unwrap();
+    sim.execute_command("P").unwrap();
+
+    assert_eq!(sim.get_state().unwrap().content(), "foo barfoo");
+}
+
+#[test]
+fn test_linewise_paste_after_goes_below_line() {
+    let mut sim = HelixSimulator::new("one\ntwo\n".to_string());
+    sim.registers
+        .write(registers::DEFAULT_REGISTER, vec!["new\n".to_string()]);
+    sim.execute_command("l").unwrap();
+    sim.execute_command("p").unwrap();
+
+    let state = sim.get_state().unwrap();
+    assert_eq!(state.content(), "one\nnew\ntwo\n");
+    let range = sim.selection.primary();
+    assert_eq!((range.from(), range.to()), (4, 8));
+}
+
+#[test]
+fn test_linewise_paste_before_goes_above_line() {
+    let mut sim = HelixSimulator::new("one\ntwo\n".to_string());
+    sim.registers
+        .write(registers::DEFAULT_REGISTER, vec!["new\n".to_string()]);
+    sim.execute_command("j").unwrap();
+    sim.execute_command("l").unwrap();
+    sim.execute_command("P").unwrap();
+
+    assert_eq!(sim.get_state().unwrap().content(), "one\nnew\ntwo\n");
+}
+
+#[test]
+fn test_linewise_paste_after_last_line_without_newline() {
+    let mut sim = HelixSimulator::new("one\ntwo".to_string());
+    sim.registers
+        .write(registers::DEFAULT_REGISTER, vec!["new\n".to_string()]);
+    sim.execute_command("j").unwrap();
+    sim.execute_command("p").unwrap();
+
+    let state = sim.get_state().unwrap();
+    assert_eq!(state.content(), "one\ntwo\nnew");
+    assert_eq!(state.cursor_position().row, 2);
+}
+
+#[test]
+fn test_paste_multiple_values_pairwise() {
+    let mut sim = HelixSimulator::new("a b c".to_string());
+    sim.selection = Selection::new(
+        [
+            helix_core::Range::point(0),
+            helix_core::Range::point(2),
+            helix_core::Range::point(4),
+        ]
+        .into_iter()
+        .collect(),
+        0,
+    );
+    sim.registers.write(
+        registers::DEFAULT_REGISTER,
+        vec!["1".to_string(), "2".to_string()],
+    );
+    sim.execute_command("p").unwrap();
+
+    // The third selection reuses the last value
+    assert_eq!(sim.get_state().unwrap().content(), "a1 b2 c2");
+    let ranges: Vec<(usize, usize)> = sim
+        .selection
+        .iter()
+        .map(|range| (range.from(), range.to()))
+        .collect();
+    assert_eq!(ranges, vec![(1, 2), (4, 5), (7, 8)]);
+}
+
+#[test]
+fn test_yank_multiple_selections() {
+    let mut sim = HelixSimulator::new("ab".to_string());
+    sim.selection = Selection::new(
+        [helix_core::Range::point(0), helix_core::Range::point(1)]
+            .into_iter()
+            .collect(),
+        0,
+    );
+    sim.execute_command("y").unwrap();
+
+    let values = sim.registers.read(registers::DEFAULT_REGISTER).unwrap();
+    assert_eq!(values, ["a".to_string(), "b".to_string()]);
+}
+
+#[test]
+fn test_blackhole_register_discards_writes() {
+    let mut sim = HelixSimulator::new("abc".to_string());
+    sim.registers
+        .write(registers::BLACKHOLE_REGISTER, vec!["x".to_string()]);
+
+    assert!(sim.registers.read(registers::BLACKHOLE_REGISTER).is_none());
+}
+
+#[test]
+fn test_delete_char_yanks() {
+    let mut sim = HelixSimulator::new("abc".to_string());
+    sim.execute_command("x").unwrap();
+    sim.execute_command("p").unwrap();
+
+    assert_eq!(sim.get_state().unwrap().content(), "bac");
+}
+
+#[test]
+fn test_delete_line_yanks_linewise() {
+    let mut sim = HelixSimulator::new("one\ntwo\nthree".to_string());
+    sim.execute_command("dd").unwrap();
+    sim.execute_command("p").unwrap();
+
+    assert_eq!(sim.get_state().unwrap().content(), "two\none\nthree");
+}
+
+#[test]
+fn test_delete_last_line_yanks_linewise() {
+    let mut sim = HelixSimulator::new("one\ntwo".to_string());
+    sim.execute_command("j").unwrap();
+    sim.execute_command("dd").unwrap();
+
+    let values = sim.registers.read(registers::DEFAULT_REGISTER).unwrap();
+    assert_eq!(values, ["two\n".to_string()]);
+}
+
+#[test]
+fn test_change_yanks() {
+    let mut sim = HelixSimulator::new("abc".to_string());
+    sim.execute_command("c").unwrap();
+
+    let values = sim.registers.read(registers::DEFAULT_REGISTER).unwrap();
+    assert_eq!(values, ["a".to_string()]);
+}
+
+#[test]
+fn test_delete_noyank_keeps_register() {
+    let mut sim = HelixSimulator::new("a_bc".to_string());
+    sim.execute_command("y").unwrap();
+    sim.execute_command("l").unwrap();
+    sim.execute_command("alt-d").unwrap();
+
+    assert_eq!(sim.get_state().unwrap().content(), "abc");
+    let values = sim.registers.read(registers::DEFAULT_REGISTER).unwrap();
+    assert_eq!(values, ["a".to_string()]);
+}
+
+#[test]
+fn test_delete_noyank_deletes_selection() {
+    let mut sim = HelixSimulator::new("foo bar".to_string());
+    sim.execute_command("v").unwrap();
+    sim.execute_command("w").unwrap();
+    sim.execute_command("alt-d").unwrap();
+
+    let state = sim.get_state().unwrap();
+    assert_eq!(state.content(), "bar");
+    assert_eq!(state.cursor_position().col, 0);
+}
+
+#[test]
+fn test_delete_char_deletes_selection() {
+    let mut sim = HelixSimulator::new("foo bar".to_string());
+    sim.execute_command("v").unwrap();
+    sim.execute_command("w").unwrap();
+    sim.execute_command("x").unwrap();
+
+    assert_eq!(sim.get_state().unwrap().content(), "bar");
+    let values = sim.registers.read(registers::DEFAULT_REGISTER).unwrap();
+    assert_eq!(values, ["foo ".to_string()]);
+}
+
+#[test]
+fn test_change_deletes_selection() {
+    let mut sim = HelixSimulator::new("foo bar".to_string());
+    sim.execute_command("w").unwrap();
+    sim.execute_command("c").unwrap();
+
+    let state = sim.get_state().unwrap();
+    assert_eq!(state.content(), "bar");
+    assert_eq!(state.cursor_position().col, 0);
+    assert_eq!(sim.mode(), Mode::Insert);
+    let values = sim.registers.read(registers::DEFAULT_REGISTER).unwrap();
+    assert_eq!(values, ["foo ".to_string()]);
+}
+
+#[test]
+fn test_change_noyank_keeps_register() {
+    let mut sim = HelixSimulator::new("abc".to_string());
+    sim.execute_command("l").unwrap();
+    sim.execute_command("y").unwrap();
+    sim.execute_command("h").unwrap();
+    sim.execute_command("alt-c").unwrap();
+
+    assert_eq!(sim.mode(), Mode::Insert);
+    assert_eq!(sim.get_state().unwrap().content(), "bc");
+    let values = sim.registers.read(registers::DEFAULT_REGISTER).unwrap();
+    assert_eq!(values, ["b".to_string()]);
+}
+
+#[test]
+fn test_repeat_delete_noyank() {
+    let mut sim = HelixSimulator::new("abcd".to_string());
+    sim.execute_command("alt-d").unwrap();
+    sim.execute_command(".").unwrap();
+
+    assert_eq!(sim.get_state().unwrap().content(), "cd");
+}
+
+// ============================================================================
+// Multi-Selection State Tests
+// ============================================================================
+
+#[test]
+fn test_get_state_reports_all_selections() {
+    let mut sim = HelixSimulator::new("foo bar\nbaz".to_string());
+    sim.selection = Selection::new(
+        [helix_core::Range::new(0, 3), helix_core::Range::new(11, 8)]
+            .into_iter()
+            .collect(),
+        1,
+    );
+
+    let state = sim.get_state().unwrap();
+    let selections: Vec<((usize, usize), (usize, usize))> = state
+        .selections()
+        .iter()
+        .map(|sel| ((sel.start.row, sel.start.col), (sel.end.row, sel.end.col)))
+        .collect();
+
+    // Anchor maps to start and head to end, so direction is preserved
+    assert_eq!(selections, vec![((0, 0), (0, 3)), ((1, 3), (1, 0))]);
+    assert_eq!(state.primary_selection(), 1);
+    assert_eq!(state.cursor_position().row, 1);
+    assert_eq!(state.cursor_position().col, 0);
+}
+
+#[test]
+fn test_get_state_reports_cursor_as_empty_selection() {
+    let sim = HelixSimulator::new("foo".to_string());
+    let state = sim.get_state().unwrap();
+
+    assert_eq!(state.selections().len(), 1);
+    assert!(state.selection().unwrap().is_empty());
+}
+
+// ============================================================================
+// Setup State Tests
+// ============================================================================
+
+fn game_selection(anchor: (usize, usize), head: (usize, usize)) -> crate::game::Selection {
+    crate::game::Selection::new(
+        CursorPosition::new(anchor.0, anchor.1).unwrap(),
+        CursorPosition::new(head.0, head.1).unwrap(),
+    )
+}
+
+#[test]
+fn test_set_selections() {
+    let mut sim = HelixSimulator::new("foo\nbar".to_string());
+    sim.set_selections(
+        &[
+            game_selection((0, 0), (0, 3)),
+            game_selection((1, 3), (1, 0)),
+        ],
+        1,
+    )
+    .unwrap();
+
+    let ranges: Vec<(usize, usize)> = sim
+        .selection
+        .iter()
+        .map(|range| (range.anchor, range.head))
+        .collect();
+    assert_eq!(ranges, vec![(0, 3), (7, 4)]);
+    assert_eq!(sim.selection.primary_index(), 1);
+}
+
+#[test]
+fn test_set_selections_rejects_invalid_positions() {
+    let mut sim = HelixSimulator::new("foo\nbar".to_string());
+    assert!(
+        sim.set_selections(&[game_selection((0, 0), (0, 5))], 0)
+            .is_err()
+    );
+    assert!(
+        sim.set_selections(&[game_selection((0, 0), (3, 0))], 0)
+            .is_err()
+    );
+    assert!(
+        sim.set_selections(&[game_selection((0, 0), (0, 1))], 1)
+            .is_err()
+    );
+}
+
+#[test]
+fn test_seed_last_action_command() {
+    let mut sim = HelixSimulator::new("a\nb\nc".to_string());
+    sim.seed_last_action(&["dd".to_string()]).unwrap();
+
+    // Seeding leaves the document untouched
+    assert_eq!(sim.get_state().unwrap().content(), "a\nb\nc");
+
+    sim.execute_command(".").unwrap();
+    assert_eq!(sim.get_state().unwrap().content(), "b\nc");
+}
+
+#[test]
+fn test_seed_last_action_insert_sequence() {
+    let mut sim = HelixSimulator::new("bar".to_string());
+    sim.seed_last_action(&["i".to_string(), "x".to_string(), "Escape".to_string()])
+        .unwrap();
+    sim.execute_command(".").unwrap();
+
+    assert_eq!(sim.get_state().unwrap().content(), "xbar");
+}
+
+#[test]
+fn test_seed_last_action_rejects_unfinished_insert() {
+    let mut sim = HelixSimulator::new("bar".to_string());
+    assert!(
+        sim.seed_last_action(&["i".to_string(), "x".to_string()])
+            .is_err()
+    );
+    assert!(sim.seed_last_action(&["w".to_string()]).is_err());
+}
+
+#[test]
+fn test_set_mode_insert_records_for_repeat() {
+    let mut sim = HelixSimulator::new("bar".to_string());
+    sim.set_mode(Mode::Insert);
+    sim.execute_command("x").unwrap();
+    sim.execute_command("Escape").unwrap();
+    sim.execute_command(".").unwrap();
+
+    assert_eq!(sim.mode(), Mode::Normal);
+    assert!(sim.get_state().unwrap().content().starts_with("xx"));
+}
@@ -0,0 +1,241 @@
+//! Viewport state and view mode (`z`) commands
+
+use super::HelixSimulator;
+use crate::security::UserError;
+use helix_core::Selection;
+
+/// Number of visible lines used until the UI reports the real editor height
+pub const DEFAULT_VIEWPORT_HEIGHT: usize = 20;
+
+/// Minimum number of lines kept between the cursor and the viewport edges
+/// (Helix's default `scrolloff`)
+const SCROLLOFF: usize = 5;
+
+/// Direction of a scroll or page command
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum ScrollDirection {
+    Up,
+    Down,
+}
+
+/// The window of document lines visible in the editor pane
+///
+/// The viewport never scrolls past the end of the document: the last line
+/// can at most be shown on the bottom row.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Viewport {
+    top_line: usize,
+    height: usize,
+}
+
+impl Viewport {
+    /// Create a viewport showing `height` lines from the top of the document
+    pub fn new(height: usize) -> Self {
+        Self {
+            top_line: 0,
+            height: height.max(1),
+        }
+    }
+
+    /// First visible line (0-indexed)
+    pub fn top_line(&self) -> usize {
+        self.top_line
+    }
+
+    /// Number of visible lines
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Range of visible line indices, clipped to `total_lines`
+    pub fn visible_lines(&self, total_lines: usize) -> std::ops::Range<usize> {
+        let start = self.top_line.min(total_lines);
+        let end = (self.top_line + self.height).min(total_lines);
+        start..end
+    }
+
+    /// Return a viewport of the same height scrolled so that `line` is visible
+    ///
+    /// Used for panes without their own scroll state, such as the target view.
+    pub fn scrolled_to(self, line: usize, total_lines: usize) -> Self {
+        let mut view = self;
+        view.ensure_line_visible(line, total_lines);
+        view
+    }
+
+    /// Effective scrolloff for this height (at most half the view)
+    fn scrolloff(&self) -> usize {
+        SCROLLOFF.min(self.height.saturating_sub(1) / 2)
+    }
+
+    /// Largest top line that does not show empty space below the document
+    fn max_top(&self, total_lines: usize) -> usize {
+        total_lines.saturating_sub(self.height)
+    }
+
+    fn set_top(&mut self, top: usize, total_lines: usize) {
+        self.top_line = top.min(self.max_top(total_lines));
+    }
+
+    /// Lowest and highest lines the cursor may occupy while respecting scrolloff
+    fn cursor_bounds(&self, total_lines: usize) -> (usize, usize) {
+        let last_line = total_lines.saturating_sub(1);
+        let scrolloff = self.scrolloff();
+        let bottom = self.top_line + self.height - 1;
+
+        let lower = if self.top_line == 0 {
+            0
+        } else {
+            (self.top_line + scrolloff).min(last_line)
+        };
+        let upper = if bottom >= last_line {
+            last_line
+        } else {
+            bottom.saturating_sub(scrolloff)
+        };
+        (lower, upper.max(lower))
+    }
+
+    /// Scroll the minimum amount needed to show `line` with scrolloff
+    fn ensure_line_visible(&mut self, line: usize, total_lines: usize) {
+        let scrolloff = self.scrolloff();
+        let last_line = total_lines.saturating_sub(1);
+
+        if line < self.top_line + scrolloff {
+            self.set_top(line.saturating_sub(scrolloff), total_lines);
+        } else if line + scrolloff >= self.top_line + self.height {
+            let wanted_bottom = (line + scrolloff).min(last_line);
+            self.set_top((wanted_bottom + 1).saturating_sub(self.height), total_lines);
+        }
+    }
+}
+
+impl Default for Viewport {
+    fn default() -> Self {
+        Self::new(DEFAULT_VIEWPORT_HEIGHT)
+    }
+}
+
+impl HelixSimulator {
+    /// Get the current viewport
+    pub fn viewport(&self) -> Viewport {
+        self.viewport
+    }
+
+    /// Resize the viewport (called by the UI with the editor pane's inner height)
+    ///
+    /// The view is scrolled if needed so the cursor stays visible.
+    pub fn set_viewport_height(&mut self, height: usize) {
+        self.viewport.height = height.max(1);
+        self.ensure_cursor_in_view();
+    }
+
+    /// Line containing the primary cursor
+    fn cursor_line(&self) -> usize {
+        let head = self.selection.primary().head.min(self.doc.len_chars());
+        self.doc.char_to_line(head)
+    }
+
+    /// Scroll the view so the cursor is visible (run after every non-view command)
+    pub(super) fn ensure_cursor_in_view(&mut self) {
+        let line = self.cursor_line();
+        let total_lines = self.doc.len_lines();
+        self.viewport.ensure_line_visible(line, total_lines);
+    }
+
+    /// Align the cursor line to the center of the view (`zz`)
+    pub(super) fn align_view_center(&mut self) -> Result<(), UserError> {
+        let line = self.cursor_line();
+        let top = line.saturating_sub(self.viewport.height / 2);
+        self.viewport.set_top(top, self.doc.len_lines());
+        Ok(())
+    }
+
+    /// Align the cursor line to the top of the view (`zt`)
+    pub(super) fn align_view_top(&mut self) -> Result<(), UserError> {
+        let line = self.cursor_line();
+        let top = line.saturating_sub(self.viewport.scrolloff());
+        self.viewport.set_top(top, self.doc.len_lines());
+        Ok(())
+    }
+
+    /// Align the cursor line to the bottom of the view (`zb`)
+    pub(super) fn align_view_bottom(&mut self) -> Result<(), UserError> {
+        let line = self.cursor_line();
+        let bottom = line + self.viewport.scrolloff();
+        let top = (bottom + 1).saturating_sub(self.viewport.height);
+        self.viewport.set_top(top, self.doc.len_lines());
+        Ok(())
+    }
+
+    /// Scroll the view by `lines` without moving the cursor (`zj`, `zk`, `Ctrl-f`, `Ctrl-b`)
+    ///
+    /// If the cursor would leave the view it is moved to the start of the
+    /// nearest line that is still inside the scrolloff margin.
+    pub(super) fn scroll_view(
+        &mut self,
+        direction: ScrollDirection,
+        lines: usize,
+    ) -> Result<(), UserError> {
+        let total_lines = self.doc.len_lines();
+        let top = match direction {
+            ScrollDirection::Down => self.viewport.top_line + lines,
+            ScrollDirection::Up => self.viewport.top_line.saturating_sub(lines),
+        };
+        self.viewport.set_top(top, total_lines);
+
+        let line = self.cursor_line();
+        let (lower, upper) = self.viewport.cursor_bounds(total_lines);
+        let target_line = line.clamp(lower, upper);
+        if target_line != line {
+            let pos = self.doc.line_to_char(target_line);
+            self.selection = Selection::point(pos);
+        }
+        Ok(())
+    }
+
+    /// Scroll the view and move the cursor by the same number of lines (`Ctrl-d`, `Ctrl-u`)
+    pub(super) fn scroll_with_cursor(
+        &mut self,
+        direction: ScrollDirection,
+        lines: usize,
+    ) -> Result<(), UserError> {
+        let total_lines = self.doc.len_lines();
+        let top = match direction {
+            ScrollDirection::Down => self.viewport.top_line + lines,
+            ScrollDirection::Up => self.viewport.top_line.saturating_sub(lines),
+        };
+        self.viewport.set_top(top, total_lines);
+
+        let line = self.cursor_line();
+        let target_line = match direction {
+            ScrollDirection::Down => (line + lines).min(total_lines.saturating_sub(1)),
+            ScrollDirection::Up => line.saturating_sub(lines),
+        };
+
+        // Keep the column where the target line is long enough
+        let head = self.selection.primary().head.min(self.doc.len_chars());
+        let col = head - self.doc.line_to_char(line);
+        let target_start = self.doc.line_to_char(target_line);
+        let target_len = self.doc.line(target_line).len_chars();
+        let target_len = if target_line + 1 < total_lines {
+            target_len.saturating_sub(1)
+        } else {
+            target_len
+        };
+        self.selection = Selection::point(target_start + col.min(target_len));
+
+        self.ensure_cursor_in_view();
+        Ok(())
+    }
+
+    /// Half of the view height, used by `Ctrl-d` and `Ctrl-u`
+    pub(super) fn half_page(&self) -> usize {
+        (self.viewport.height / 2).max(1)
+    }
+
+    /// Full view height, used by `Ctrl-f` and `Ctrl-b`
+    pub(super) fn full_page(&self) -> usize {
+        self.viewport.height
+    }
+}
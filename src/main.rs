@@ -3,20 +3,29 @@
 //! This is the application's entry point. It initializes the terminal UI,
 //! loads scenarios, and runs the main event loop.
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use crossterm::{
     event::{self, Event, KeyCode, KeyEvent, KeyModifiers},
     execute,
     terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
 };
 use helix_trainer::{
-    config::ScenarioLoader,
+    config::{
+        BASE_LOCALE, ConfigOverrides, GameMode, ImportMeta, KeyNotation, KeymapMode, LintOptions,
+        ScenarioDirs, ScenarioLoader, ScenarioPack, ScenarioSource, ScenariosFile, TrainerConfig,
+        dirs::{base_source, builtin_scenarios_dir, config_file},
+        embedded::{embedded_locale_files, matches_embedded},
+        import_challenge, scenario_schema,
+        trainer::parse_setting,
+    },
+    game::SearchBudget,
     helix::commands::*,
     ui::{self, AppState, Message},
 };
 use ratatui::{Terminal, backend::CrosstermBackend};
 use std::borrow::Cow;
 use std::io;
+use std::path::PathBuf;
 use std::time::Duration;
 use tracing_subscriber::filter::LevelFilter;
 use tracing_subscriber::prelude::*;
@@ -48,9 +57,366 @@ fn init_secure_logging() -> Result<()> {
     Ok(())
 }
 
+/// Usage text for the command line
+const USAGE: &str = "Usage: helix-trainer [--scenarios <dir>] [--config <file>] [--locale <code>]
+                     [--theme dark|light|monochrome] [--mode scenarios|motion_drill]
+                     [--scenario-dir <dir>]... [--success-delay-ms <ms>]
+                     [--indent-unit tab|<spaces>] [--hint-penalty <points>]
+       helix-trainer validate [--search] <path>...
+       helix-trainer import <start> <end> [--meta <file>] [--out <file>]
+       helix-trainer export [--format json|toml] <path>...
+       helix-trainer schema";
+
+/// Options of an interactive run
+#[derive(Debug, Default)]
+struct RunOptions {
+    /// Directory from `--scenarios`
+    scenarios_dir: Option<PathBuf>,
+    /// File from `--config`, used instead of the XDG configuration file
+    config_path: Option<PathBuf>,
+    /// Settings given as flags, which win over the configuration file
+    overrides: ConfigOverrides,
+}
+
+/// Parse the flags of an interactive run
+fn parse_run_args(args: &[String]) -> Result<RunOptions, String> {
+    let mut options = RunOptions::default();
+    let mut args = args.iter();
+    while let Some(flag) = args.next() {
+        let mut value = || args.next().ok_or_else(|| format!("{flag} needs a value"));
+        match flag.as_str() {
+            "--scenarios" => options.scenarios_dir = Some(PathBuf::from(value()?)),
+            "--config" => options.config_path = Some(PathBuf::from(value()?)),
+            "--locale" => options.overrides.locale = Some(value()?.clone()),
+            "--theme" => {
+                options.overrides.theme = Some(parse_setting(value()?).map_err(|e| e.to_string())?);
+            }
+            "--mode" => {
+                options.overrides.mode = Some(parse_setting(value()?).map_err(|e| e.to_string())?);
+            }
+            "--scenario-dir" => {
+                let dir = PathBuf::from(value()?);
+                options
+                    .overrides
+                    .scenario_dirs
+                    .get_or_insert_with(Vec::new)
+                    .push(dir);
+            }
+            "--success-delay-ms" => {
+                options.overrides.success_delay_ms = Some(parse_number(flag, value()?)?);
+            }
+            "--indent-unit" => {
+                options.overrides.indent_unit = Some(match value()?.as_str() {
+                    "tab" => "\t".to_string(),
+                    // A u8 keeps a huge count from allocating before validation
+                    spaces => " ".repeat(parse_number::<u8>(flag, spaces)?.into()),
+                });
+            }
+            "--hint-penalty" => {
+                options.overrides.hint_penalty = Some(parse_number(flag, value()?)?);
+            }
+            _ => return Err(format!("Unknown argument: {flag}")),
+        }
+    }
+    Ok(options)
+}
+
+/// Parse the number given to a flag
+fn parse_number<T: std::str::FromStr>(flag: &str, value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("{flag} needs a number, got '{value}'"))
+}
+
+/// What the event loop needs to save settings and reload scenarios
+struct RunContext {
+    /// Scenario directories outside the built-in set
+    dirs: ScenarioDirs,
+    /// Configuration file that settings are saved to
+    config_path: Option<PathBuf>,
+    /// Configuration as last read from or saved to the file
+    file_config: TrainerConfig,
+    /// Settings given as flags, which are not saved
+    overrides: ConfigOverrides,
+}
+
+/// Loader for scenario files given on the command line
+///
+/// Files may live anywhere, so exactly the paths that were given are
+/// allowed. Paths are made absolute first: a bare file name such as
+/// `delete.toml` has an empty parent, which would allow nothing.
+fn loader_for(paths: &[PathBuf]) -> ScenarioLoader {
+    let allowed = paths
+        .iter()
+        .map(|path| {
+            let path = path.canonicalize().unwrap_or_else(|_| path.clone());
+            match path.parent() {
+                Some(parent) if path.is_file() => parent.to_path_buf(),
+                _ => path,
+            }
+        })
+        .collect();
+    ScenarioLoader::with_allowed_paths(allowed)
+}
+
+/// Validate scenario files and print JSON diagnostics
+///
+/// With `--search`, each scenario is also searched for a solution shorter
+/// than its `optimal_count`.
+///
+/// Exits with status 1 when any problem is found, so the command can gate
+/// commits of scenario files.
+fn run_validate(args: &[String]) -> Result<()> {
+    let mut options = LintOptions::default();
+    let mut paths = Vec::new();
+    for arg in args {
+        match arg.as_str() {
+            "--search" => options.search = Some(SearchBudget::default()),
+            _ => paths.push(PathBuf::from(arg)),
+        }
+    }
+    if paths.is_empty() {
+        eprintln!("{USAGE}");
+        std::process::exit(2);
+    }
+
+    let report = loader_for(&paths).lint(&paths, &options);
+
+    println!("{}", serde_json::to_string_pretty(&report)?);
+
+    if !report.is_clean() {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+/// Load scenario files and print them as JSON or TOML
+///
+/// Scenarios are printed as loaded: templates expanded into their instances
+/// and selection markers resolved.
+fn run_export(args: &[String]) -> Result<()> {
+    let mut format = "json".to_string();
+    let mut paths = Vec::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--format" => format = args.next().cloned().unwrap_or_default(),
+            _ => paths.push(PathBuf::from(arg)),
+        }
+    }
+    if paths.is_empty() || !matches!(format.as_str(), "json" | "toml") {
+        eprintln!("{USAGE}");
+        std::process::exit(2);
+    }
+
+    let loader = loader_for(&paths);
+    let mut scenarios = Vec::new();
+    for path in &paths {
+        if path.is_dir() {
+            scenarios.extend(loader.load_directory(path)?);
+        } else {
+            scenarios.extend(loader.load(path)?);
+        }
+    }
+
+    let file = ScenariosFile {
+        scenarios,
+        translations: Vec::new(),
+    };
+    match format.as_str() {
+        "toml" => print!("{}", toml::to_string_pretty(&file)?),
+        _ => println!("{}", serde_json::to_string_pretty(&file)?),
+    }
+    Ok(())
+}
+
+/// Print the JSON Schema of the scenario file format
+fn run_schema() -> Result<()> {
+    println!("{}", serde_json::to_string_pretty(&scenario_schema())?);
+    Ok(())
+}
+
+/// Import a start/end challenge as a scenario file
+///
+/// Writes the scenario TOML to `--out`, or prints it when no output file is
+/// given. The id defaults to the start file's name.
+fn run_import(args: &[String]) -> Result<()> {
+    let mut files = Vec::new();
+    let (mut meta_path, mut out_path) = (None, None);
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = || match args.next() {
+            Some(value) => PathBuf::from(value),
+            None => {
+                eprintln!("{arg} needs a value\n{USAGE}");
+                std::process::exit(2);
+            }
+        };
+        match arg.as_str() {
+            "--meta" => meta_path = Some(value()),
+            "--out" => out_path = Some(value()),
+            _ => files.push(PathBuf::from(arg)),
+        }
+    }
+    let [start_path, end_path] = files.as_slice() else {
+        eprintln!("{USAGE}");
+        std::process::exit(2);
+    };
+
+    let meta = match &meta_path {
+        Some(path) => toml::from_str(&std::fs::read_to_string(path)?)?,
+        None => ImportMeta::default(),
+    };
+    let default_id = start_path
+        .file_stem()
+        .map_or(Cow::Borrowed("challenge"), |stem| stem.to_string_lossy());
+    let toml = import_challenge(
+        &std::fs::read_to_string(start_path)?,
+        &std::fs::read_to_string(end_path)?,
+        &meta,
+        &default_id,
+    )?;
+
+    match out_path {
+        Some(path) => std::fs::write(path, toml)?,
+        None => print!("{toml}"),
+    }
+    Ok(())
+}
+
+/// Collect the scenario sources to load for a locale
+///
+/// The scenarios embedded in the binary come first, then the on-disk
+/// built-in set when it differs from the embedded one, the user's data
+/// directory, the configuration file's `scenario_dirs`,
+/// `HELIX_TRAINER_SCENARIOS` and `--scenarios`; later sources override
+/// earlier scenarios by id. Each place contributes its English base set
+/// followed by the locale's own directory, whose translations replace the
+/// English texts. A user directory without an `en` subdirectory is the
+/// base set itself, minus its locale subdirectories. Each user directory is
+/// added to the loader's allowed paths. A data directory that fails the
+/// path checks is skipped; directories named explicitly must pass them.
+fn scenario_sources(
+    loader: &mut ScenarioLoader,
+    user_dirs: &ScenarioDirs,
+    locale: &str,
+) -> Result<Vec<ScenarioSource>> {
+    let mut sources = Vec::new();
+    let overlay = (locale != BASE_LOCALE).then_some(locale);
+
+    for locale in std::iter::once(BASE_LOCALE).chain(overlay) {
+        let embedded = embedded_locale_files(locale);
+        if embedded.is_empty() {
+            tracing::info!("No built-in scenarios for locale: {}", locale);
+        } else {
+            sources.push(ScenarioSource::Memory(embedded));
+        }
+    }
+
+    // An unchanged copy of the embedded set would only load it twice
+    if let Some(builtin) = builtin_scenarios_dir() {
+        for locale in std::iter::once(BASE_LOCALE).chain(overlay) {
+            let dir = builtin.join(locale);
+            if dir.is_dir() && !matches_embedded(&dir, locale) {
+                sources.push(ScenarioSource::Directory(dir));
+            }
+        }
+    }
+
+    for dir in user_dirs.user_dirs() {
+        match loader.allow_directory(&dir) {
+            Ok(canonical) => {
+                sources.push(base_source(&canonical));
+                if let Some(locale) = overlay
+                    && canonical.join(locale).is_dir()
+                {
+                    sources.push(ScenarioSource::Directory(canonical.join(locale)));
+                }
+            }
+            Err(e) if user_dirs.data_dir.as_ref() == Some(&dir) => {
+                tracing::warn!("Skipping user data directory: {}", e);
+            }
+            Err(e) => {
+                tracing::error!("Invalid scenarios directory: {}", e);
+                return Err(e.into());
+            }
+        }
+    }
+
+    Ok(sources)
+}
+
+/// Load the scenarios for a locale, grouped by pack
+fn load_packs(dirs: &ScenarioDirs, locale: &str) -> Result<Vec<ScenarioPack>> {
+    tracing::info!("Loading scenarios for locale: {}", locale);
+
+    let mut loader = ScenarioLoader::new();
+    let sources = scenario_sources(&mut loader, dirs, locale)?;
+    let packs = loader.load_sources(&sources)?;
+
+    tracing::info!(
+        "Loaded {} scenarios in {} packs from {} locale",
+        packs.iter().map(|pack| pack.scenarios.len()).sum::<usize>(),
+        packs.len(),
+        locale
+    );
+    Ok(packs)
+}
+
+/// Save changed settings, reloading the scenarios after a locale change
+///
+/// The new locale only takes effect, and is only saved, once its scenarios
+/// load; otherwise the previous one stays. Settings given as flags keep the
+/// file's values unless they were changed on the settings screen. Failures
+/// are logged rather than returned so a bad locale or an unwritable config
+/// directory does not end the session.
+fn apply_settings(state: &mut AppState, context: &mut RunContext) {
+    state.config_changed = false;
+
+    let current_locale = rust_i18n::locale();
+    let current: &str = current_locale.as_ref();
+    if current != state.config.locale {
+        match load_packs(&context.dirs, &state.config.locale) {
+            Ok(packs) => {
+                state.set_packs(packs);
+                rust_i18n::set_locale(&state.config.locale);
+            }
+            Err(e) => {
+                tracing::error!("Failed to reload scenarios: {}", e);
+                state.config.locale = current.to_string();
+            }
+        }
+    }
+
+    if let Some(path) = &context.config_path {
+        let saved = context
+            .overrides
+            .to_save(&state.config, &context.file_config);
+        match saved.save(path) {
+            Ok(()) => context.file_config = saved,
+            Err(e) => tracing::warn!("Failed to save settings: {}", e),
+        }
+    }
+}
+
 /// Main entry point
 #[tokio::main]
 async fn main() -> Result<()> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let options = match args.first().map(String::as_str) {
+        Some("validate" | "lint") => return run_validate(&args[1..]),
+        Some("import") => return run_import(&args[1..]),
+        Some("export") => return run_export(&args[1..]),
+        Some("schema") => return run_schema(),
+        _ => match parse_run_args(&args) {
+            Ok(options) => options,
+            Err(message) => {
+                eprintln!("{message}\n{USAGE}");
+                std::process::exit(2);
+            }
+        },
+    };
+
     // Warn if running debug build
     #[cfg(debug_assertions)]
     {
@@ -63,25 +429,42 @@ async fn main() -> Result<()> {
 
     tracing::info!("Starting Helix Keybindings Trainer");
 
-    // Load scenarios from language-specific directory (recursively)
-    // Use current locale from rust-i18n
-    let current_locale = rust_i18n::locale();
-    let locale_str: &str = current_locale.as_ref();
-    let scenarios_path = format!("./scenarios/{}", locale_str);
-
-    tracing::info!("Loading scenarios for locale: {}", locale_str);
-
-    let loader = ScenarioLoader::new();
-    let scenarios = loader.load_directory(std::path::Path::new(&scenarios_path))?;
-
-    tracing::info!(
-        "Loaded {} scenarios from {} locale",
-        scenarios.len(),
-        locale_str
-    );
+    // Read the configuration; a broken file is reported, not overwritten
+    let config_path = options.config_path.or_else(config_file);
+    let file_config = match &config_path {
+        Some(path) => TrainerConfig::load(path)
+            .with_context(|| format!("Invalid configuration file {}", path.display()))?,
+        None => TrainerConfig::default(),
+    };
+    let mut config = file_config.clone();
+    options.overrides.apply(&mut config)?;
+    rust_i18n::set_locale(&config.locale);
+
+    // Load scenarios from language-specific directories (recursively)
+    let dirs = ScenarioDirs {
+        config_dirs: config.scenario_dirs.clone(),
+        ..ScenarioDirs::from_env(options.scenarios_dir)
+    };
+    let packs = load_packs(&dirs, &config.locale)?;
 
     // Initialize app state
-    let mut app_state = AppState::new(scenarios);
+    let start_drill = config.mode == GameMode::MotionDrill;
+    let mut app_state = AppState::with_packs(packs);
+    app_state.config = config;
+    let scenario_roots: Vec<PathBuf> = builtin_scenarios_dir()
+        .into_iter()
+        .chain(dirs.user_dirs())
+        .collect();
+    app_state.locales = ScenarioLoader::available_locales(&scenario_roots);
+    if start_drill {
+        ui::update(&mut app_state, Message::StartMotionDrill)?;
+    }
+    let mut context = RunContext {
+        dirs,
+        config_path,
+        file_config,
+        overrides: options.overrides,
+    };
 
     // Setup terminal
     enable_raw_mode()?;
@@ -93,7 +476,7 @@ async fn main() -> Result<()> {
     tracing::debug!("Terminal initialized");
 
     // Run the main event loop
-    let result = run_app(&mut terminal, &mut app_state);
+    let result = run_app(&mut terminal, &mut app_state, &mut context);
 
     // Restore terminal
     disable_raw_mode()?;
@@ -111,10 +494,12 @@ async fn main() -> Result<()> {
 /// 1. Renders the current state
 /// 2. Handles user input
 /// 3. Updates state based on messages
-/// 4. Repeats until the app exits
+/// 4. Saves and applies changed settings
+/// 5. Repeats until the app exits
 fn run_app(
     terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
     state: &mut AppState,
+    context: &mut RunContext,
 ) -> Result<()> {
     loop {
         // Render the current state
@@ -127,7 +512,7 @@ fn run_app(
 
         // Check if scenario completed and delay elapsed
         if let Some(completion_time) = state.completion_time
-            && completion_time.elapsed() >= Duration::from_millis(1500)
+            && completion_time.elapsed() >= Duration::from_millis(state.config.success_delay_ms)
         {
             tracing::debug!("Success screen delay elapsed, transitioning to results");
             ui::update(state, Message::CompleteScenario)?;
@@ -150,6 +535,10 @@ fn run_app(
                 tracing::debug!("Message: {:?}", msg);
                 ui::update(state, msg)?;
             }
+
+            if state.config_changed {
+                apply_settings(state, context);
+            }
         }
     }
 
@@ -165,6 +554,7 @@ fn handle_key_event(key: KeyEvent, state: &AppState) -> Option<Message> {
         ui::Screen::MainMenu => handle_menu_keys(key, state),
         ui::Screen::Task => handle_task_keys(key, state),
         ui::Screen::Results => handle_results_keys(key),
+        ui::Screen::Settings => handle_settings_keys(key),
     }
 }
 
@@ -194,6 +584,19 @@ fn handle_task_keys(key: KeyEvent, state: &AppState) -> Option<Message> {
     // Handle special UI keys first
     match key.code {
         KeyCode::F(1) => return Some(Message::ShowHint),
+        // Esc cancels a pending multi-key command (or sticky view mode) first
+        KeyCode::Esc if !state.command_buffer.is_empty() => {
+            return Some(Message::ExecuteCommand(Cow::Borrowed(CMD_ESCAPE)));
+        }
+        // Esc leaves select mode instead of abandoning the scenario
+        KeyCode::Esc
+            if state
+                .session
+                .as_ref()
+                .is_some_and(|session| session.is_select_mode()) =>
+        {
+            return Some(Message::ExecuteCommand(Cow::Borrowed(CMD_ESCAPE)));
+        }
         KeyCode::Esc => return Some(Message::AbandonScenario),
         _ => {}
     }
@@ -233,8 +636,46 @@ fn handle_task_keys(key: KeyEvent, state: &AppState) -> Option<Message> {
         }
     }
 
-    // Convert key to Helix command string (Normal mode)
-    let command = match (key.code, key.modifiers) {
+    // Convert key to Helix command string (Normal mode). A remapped key
+    // names no Shift, so it matches with or without it; the lenient keymap
+    // likewise retries a character with Shift toggled, for terminals that
+    // report 'G' without Shift or 'W' with it.
+    let command = match remap_key(key, &state.config) {
+        Some((code, modifiers)) => normal_mode_command(code, modifiers)
+            .or_else(|| normal_mode_command(code, modifiers | KeyModifiers::SHIFT)),
+        None => normal_mode_command(key.code, key.modifiers).or_else(|| {
+            match (state.config.keymap, key.code) {
+                (KeymapMode::Lenient, KeyCode::Char(_)) => {
+                    normal_mode_command(key.code, key.modifiers ^ KeyModifiers::SHIFT)
+                }
+                _ => None,
+            }
+        }),
+    }?;
+
+    Some(Message::ExecuteCommand(Cow::Borrowed(command)))
+}
+
+/// The key a remap in the configuration turns a Normal mode key into
+fn remap_key(key: KeyEvent, config: &TrainerConfig) -> Option<(KeyCode, KeyModifiers)> {
+    let KeyCode::Char(typed) = key.code else {
+        return None;
+    };
+    let target = config.keys.get(&KeyNotation {
+        key: typed,
+        ctrl: key.modifiers.contains(KeyModifiers::CONTROL),
+        alt: key.modifiers.contains(KeyModifiers::ALT),
+    })?;
+
+    let mut modifiers = KeyModifiers::NONE;
+    modifiers.set(KeyModifiers::CONTROL, target.ctrl);
+    modifiers.set(KeyModifiers::ALT, target.alt);
+    Some((KeyCode::Char(target.key), modifiers))
+}
+
+/// Helix command for a key in Normal mode
+fn normal_mode_command(code: KeyCode, modifiers: KeyModifiers) -> Option<&'static str> {
+    let command = match (code, modifiers) {
         // Movement commands
         (KeyCode::Char('h'), KeyModifiers::NONE) => CMD_MOVE_LEFT,
         (KeyCode::Char('j'), KeyModifiers::NONE) => CMD_MOVE_DOWN,
@@ -245,6 +686,9 @@ fn handle_task_keys(key: KeyEvent, state: &AppState) -> Option<Message> {
         (KeyCode::Char('w'), KeyModifiers::NONE) => CMD_MOVE_WORD_FORWARD,
         (KeyCode::Char('b'), KeyModifiers::NONE) => CMD_MOVE_WORD_BACKWARD,
         (KeyCode::Char('e'), KeyModifiers::NONE) => CMD_MOVE_WORD_END,
+        (KeyCode::Char('W'), KeyModifiers::SHIFT) => CMD_MOVE_LONG_WORD_FORWARD,
+        (KeyCode::Char('B'), KeyModifiers::SHIFT) => CMD_MOVE_LONG_WORD_BACKWARD,
+        (KeyCode::Char('E'), KeyModifiers::SHIFT) => CMD_MOVE_LONG_WORD_END,
 
         // Line movement
         (KeyCode::Char('0'), KeyModifiers::NONE) => CMD_MOVE_LINE_START,
@@ -254,6 +698,8 @@ fn handle_task_keys(key: KeyEvent, state: &AppState) -> Option<Message> {
         (KeyCode::Char('x'), KeyModifiers::NONE) => CMD_DELETE_CHAR,
         (KeyCode::Char('d'), KeyModifiers::NONE) => "d", // Single 'd' for multi-key handling
         (KeyCode::Char('c'), KeyModifiers::NONE) => CMD_CHANGE,
+        (KeyCode::Char('d'), KeyModifiers::ALT) => CMD_DELETE_NOYANK,
+        (KeyCode::Char('c'), KeyModifiers::ALT) => CMD_CHANGE_NOYANK,
         (KeyCode::Char('J'), KeyModifiers::SHIFT) => CMD_JOIN_LINES,
 
         // Indentation
@@ -272,6 +718,7 @@ fn handle_task_keys(key: KeyEvent, state: &AppState) -> Option<Message> {
         (KeyCode::Char('A'), KeyModifiers::SHIFT) => CMD_APPEND_LINE_END,
         (KeyCode::Char('o'), KeyModifiers::NONE) => CMD_OPEN_BELOW,
         (KeyCode::Char('O'), KeyModifiers::SHIFT) => CMD_OPEN_ABOVE,
+        (KeyCode::Char('v'), KeyModifiers::NONE) => CMD_SELECT_MODE,
 
         // Replace character
         (KeyCode::Char('r'), KeyModifiers::NONE) => CMD_REPLACE,
@@ -279,7 +726,7 @@ fn handle_task_keys(key: KeyEvent, state: &AppState) -> Option<Message> {
         // Undo/Redo
         (KeyCode::Char('u'), KeyModifiers::NONE) => CMD_UNDO,
         (KeyCode::Char('U'), KeyModifiers::SHIFT) => CMD_REDO,
-        (KeyCode::Char('r'), KeyModifiers::CONTROL) => "ctrl-r", // TODO: add constant
+        (KeyCode::Char('r'), KeyModifiers::CONTROL) => CMD_REDO_ALT,
 
         // Repeat last action
         (KeyCode::Char('.'), KeyModifiers::NONE) => CMD_REPEAT,
@@ -288,10 +735,38 @@ fn handle_task_keys(key: KeyEvent, state: &AppState) -> Option<Message> {
         (KeyCode::Char('g'), KeyModifiers::NONE) => "g", // Note: multi-key 'gg' handled elsewhere
         (KeyCode::Char('G'), KeyModifiers::NONE) => CMD_GOTO_FILE_END,
 
+        // View mode prefixes ('z' one-shot, 'Z' sticky)
+        (KeyCode::Char('z'), KeyModifiers::NONE) => CMD_VIEW_MODE,
+        (KeyCode::Char('Z'), KeyModifiers::SHIFT) => CMD_STICKY_VIEW_MODE,
+
+        // Scrolling
+        (KeyCode::Char('d'), KeyModifiers::CONTROL) => CMD_PAGE_CURSOR_HALF_DOWN,
+        (KeyCode::Char('u'), KeyModifiers::CONTROL) => CMD_PAGE_CURSOR_HALF_UP,
+        (KeyCode::Char('f'), KeyModifiers::CONTROL) => CMD_PAGE_DOWN,
+        (KeyCode::Char('b'), KeyModifiers::CONTROL) => CMD_PAGE_UP,
+
+        // Unimpaired bracket prefixes (']p', '[p', ']Space', '[Space')
+        (KeyCode::Char('['), KeyModifiers::NONE) => "[",
+        (KeyCode::Char(']'), KeyModifiers::NONE) => "]",
+        (KeyCode::Char(' '), KeyModifiers::NONE) => " ",
+
         _ => return None,
     };
 
-    Some(Message::ExecuteCommand(Cow::Borrowed(command)))
+    Some(command)
+}
+
+/// Handle keyboard events on the settings screen
+fn handle_settings_keys(key: KeyEvent) -> Option<Message> {
+    match key.code {
+        KeyCode::Esc | KeyCode::Char('q') => Some(Message::NavigateTo(ui::Screen::MainMenu)),
+        KeyCode::Up | KeyCode::Char('k') => Some(Message::MenuUp),
+        KeyCode::Down | KeyCode::Char('j') => Some(Message::MenuDown),
+        KeyCode::Enter | KeyCode::Char(' ') | KeyCode::Right | KeyCode::Char('l') => {
+            Some(Message::ChangeSetting)
+        }
+        _ => None,
+    }
 }
 
 /// Handle keyboard events on the results screen
@@ -300,6 +775,7 @@ fn handle_results_keys(key: KeyEvent) -> Option<Message> {
         KeyCode::Char('q') => Some(Message::QuitApp),
         KeyCode::Char('r') => Some(Message::RetryScenario),
         KeyCode::Char('m') => Some(Message::BackToMenu),
+        KeyCode::Char('n') => Some(Message::NextScenario),
         _ => None,
     }
 }
@@ -365,6 +841,111 @@ mod tests {
         assert_eq!(msg, Some(Message::AbandonScenario));
     }
 
+    #[test]
+    fn test_lenient_keymap_ignores_shift_mismatch() {
+        let key = KeyEvent::new(KeyCode::Char('W'), KeyModifiers::NONE);
+        let mut state = AppState::new(vec![]);
+        assert_eq!(handle_task_keys(key, &state), None);
+
+        state.config.keymap = KeymapMode::Lenient;
+        assert_eq!(
+            handle_task_keys(key, &state),
+            Some(Message::ExecuteCommand(Cow::Borrowed(
+                CMD_MOVE_LONG_WORD_FORWARD
+            )))
+        );
+        let key = KeyEvent::new(KeyCode::Char('G'), KeyModifiers::SHIFT);
+        assert_eq!(
+            handle_task_keys(key, &state),
+            Some(Message::ExecuteCommand(Cow::Borrowed(CMD_GOTO_FILE_END)))
+        );
+    }
+
+    #[test]
+    fn test_remapped_keys_act_as_their_targets() {
+        let mut state = AppState::new(vec![]);
+        state.config = TrainerConfig::parse("[keys]\n\"ö\" = \"h\"\n\"C-j\" = \"G\"").unwrap();
+
+        let key = KeyEvent::new(KeyCode::Char('ö'), KeyModifiers::NONE);
+        assert_eq!(
+            handle_task_keys(key, &state),
+            Some(Message::ExecuteCommand(Cow::Borrowed(CMD_MOVE_LEFT)))
+        );
+        let key = KeyEvent::new(KeyCode::Char('j'), KeyModifiers::CONTROL);
+        assert_eq!(
+            handle_task_keys(key, &state),
+            Some(Message::ExecuteCommand(Cow::Borrowed(CMD_GOTO_FILE_END)))
+        );
+    }
+
+    #[test]
+    fn test_run_args_override_settings() {
+        let args: Vec<String> = [
+            "--theme",
+            "light",
+            "--mode",
+            "motion_drill",
+            "--locale",
+            "de",
+        ]
+        .map(String::from)
+        .to_vec();
+        let options = parse_run_args(&args).unwrap();
+        assert_eq!(
+            options.overrides.theme,
+            Some(helix_trainer::config::Theme::Light)
+        );
+        assert_eq!(options.overrides.mode, Some(GameMode::MotionDrill));
+        assert_eq!(options.overrides.locale.as_deref(), Some("de"));
+
+        let args: Vec<String> = [
+            "--scenario-dir",
+            "/srv/a",
+            "--scenario-dir",
+            "/srv/b",
+            "--success-delay-ms",
+            "0",
+            "--indent-unit",
+            "4",
+            "--hint-penalty",
+            "20",
+        ]
+        .map(String::from)
+        .to_vec();
+        let overrides = parse_run_args(&args).unwrap().overrides;
+        assert_eq!(
+            overrides.scenario_dirs,
+            Some(vec![PathBuf::from("/srv/a"), PathBuf::from("/srv/b")])
+        );
+        assert_eq!(overrides.success_delay_ms, Some(0));
+        assert_eq!(overrides.indent_unit.as_deref(), Some("    "));
+        assert_eq!(overrides.hint_penalty, Some(20));
+        let tab = parse_run_args(&["--indent-unit".to_string(), "tab".to_string()]).unwrap();
+        assert_eq!(tab.overrides.indent_unit.as_deref(), Some("\t"));
+
+        for args in [
+            &["--theme", "neon"][..],
+            &["--mode"],
+            &["--verbose"],
+            &["--hint-penalty", "-1"],
+            &["--indent-unit", "wide"],
+        ] {
+            let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+            assert!(parse_run_args(&args).is_err(), "{args:?}");
+        }
+    }
+
+    #[test]
+    fn test_settings_keys() {
+        let key = KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE);
+        assert_eq!(handle_settings_keys(key), Some(Message::ChangeSetting));
+        let key = KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE);
+        assert_eq!(
+            handle_settings_keys(key),
+            Some(Message::NavigateTo(ui::Screen::MainMenu))
+        );
+    }
+
     #[test]
     fn test_results_key_r_retries() {
         let key = KeyEvent::new(KeyCode::Char('r'), KeyModifiers::NONE);
@@ -381,6 +962,13 @@ mod tests {
         assert_eq!(msg, Some(Message::BackToMenu));
     }
 
+    #[test]
+    fn test_results_key_n_starts_next() {
+        let key = KeyEvent::new(KeyCode::Char('n'), KeyModifiers::NONE);
+        let msg = handle_results_keys(key);
+        assert_eq!(msg, Some(Message::NextScenario));
+    }
+
     #[test]
     fn test_results_key_q_quits() {
         let key = KeyEvent::new(KeyCode::Char('q'), KeyModifiers::NONE);
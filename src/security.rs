@@ -84,6 +84,21 @@ pub enum SecurityError {
 
     #[error("Invalid input: {0}")]
     InvalidInput(String),
+
+    #[error("Scenario '{id}' {solution} diverges at step {step} ('{key}'): {reason}")]
+    SolutionMismatch {
+        id: String,
+        solution: String,
+        step: usize,
+        key: String,
+        reason: String,
+    },
+
+    #[error("Scenario '{id}' has an unreachable target: {reason}")]
+    UnreachableTarget { id: String, reason: String },
+
+    #[error("Scenario pack '{name}' is incompatible: {reason}")]
+    IncompatiblePack { name: String, reason: String },
 }
 
 /// User-facing error messages (sanitized)
@@ -103,6 +118,11 @@ pub enum UserError {
 
     #[error("Session has expired. Please start a new session.")]
     SessionExpired,
+
+    #[error(
+        "A scenario pack is not supported by this version. Please update helix-trainer or the pack."
+    )]
+    IncompatiblePack,
 }
 
 impl From<SecurityError> for UserError {
@@ -115,7 +135,9 @@ impl From<SecurityError> for UserError {
             SecurityError::PathTraversal
             | SecurityError::InvalidPath
             | SecurityError::SuspiciousPath
-            | SecurityError::InvalidToml(_) => UserError::ScenarioLoadError,
+            | SecurityError::InvalidToml(_)
+            | SecurityError::SolutionMismatch { .. }
+            | SecurityError::UnreachableTarget { .. } => UserError::ScenarioLoadError,
 
             SecurityError::FileTooLarge { .. }
             | SecurityError::TooManyScenarios { .. }
@@ -127,6 +149,8 @@ impl From<SecurityError> for UserError {
 
             SecurityError::SessionTimeout(_) => UserError::SessionExpired,
 
+            SecurityError::IncompatiblePack { .. } => UserError::IncompatiblePack,
+
             _ => UserError::OperationFailed,
         }
     }
@@ -139,6 +163,24 @@ pub mod limits {
     /// Maximum size of a scenario TOML file (10 MB)
     pub const MAX_SCENARIO_FILE_SIZE: u64 = 10 * 1024 * 1024;
 
+    /// Maximum size of the settings file (64 KB)
+    pub const MAX_CONFIG_FILE_SIZE: u64 = 64 * 1024;
+
+    /// Maximum number of scenario directories in the settings file
+    pub const MAX_CONFIG_SCENARIO_DIRS: usize = 20;
+
+    /// Maximum number of key remaps in the settings file
+    pub const MAX_KEY_REMAPS: usize = 100;
+
+    /// Longest configurable delay before the results screen (10 seconds)
+    pub const MAX_SUCCESS_DELAY_MS: u64 = 10_000;
+
+    /// Maximum number of spaces in an indent unit
+    pub const MAX_INDENT_WIDTH: usize = 8;
+
+    /// Maximum points deducted per hint shown
+    pub const MAX_HINT_PENALTY: u32 = 1000;
+
     /// Maximum number of scenarios per file
     pub const MAX_SCENARIOS_PER_FILE: usize = 100;
 
@@ -154,6 +196,42 @@ pub mod limits {
     /// Maximum command sequence length
     pub const MAX_COMMAND_SEQUENCE_LENGTH: usize = 100;
 
+    /// Maximum number of selections in a scenario setup or target
+    pub const MAX_SELECTIONS: usize = 100;
+
+    /// Maximum number of pre-filled registers in a scenario setup
+    pub const MAX_REGISTERS: usize = 10;
+
+    /// Maximum number of tags, prerequisites or taught commands per scenario
+    pub const MAX_METADATA_ENTRIES: usize = 20;
+
+    /// Maximum length of a scenario category, tag or taught command
+    pub const MAX_LABEL_LENGTH: usize = 32;
+
+    /// Highest scenario difficulty level
+    pub const MAX_DIFFICULTY: u8 = 5;
+
+    /// Maximum length of a pack manifest text field
+    pub const MAX_PACK_FIELD_LENGTH: usize = 1000;
+
+    /// Maximum number of scenario ids in a pack's `order`
+    pub const MAX_PACK_ORDER_LENGTH: usize = 1000;
+
+    /// Maximum compiled size of a completion rule's regular expression (1 MB)
+    pub const MAX_PATTERN_SIZE: usize = 1024 * 1024;
+
+    /// Maximum number of lesson steps after a scenario's first
+    pub const MAX_LESSON_STEPS: usize = 20;
+
+    /// Maximum number of instances generated from one scenario template
+    pub const MAX_TEMPLATE_INSTANCES: usize = 20;
+
+    /// Maximum number of variables in a scenario template
+    pub const MAX_TEMPLATE_VARIABLES: usize = 10;
+
+    /// Maximum number of words in a template word list
+    pub const MAX_TEMPLATE_WORDS: usize = 100;
+
     /// Command timeout
     pub const COMMAND_TIMEOUT: Duration = Duration::from_secs(30);
 
@@ -204,6 +282,26 @@ pub mod path_validator {
         Ok(canonical)
     }
 
+    /// Validates a directory before it becomes an allowed base path
+    ///
+    /// Returns the canonical directory, which `validate_path` then accepts
+    /// files below.
+    pub fn validate_base_dir(path: &Path) -> Result<PathBuf, SecurityError> {
+        let canonical = path
+            .canonicalize()
+            .map_err(|_| SecurityError::InvalidPath)?;
+
+        if !canonical.is_dir() {
+            return Err(SecurityError::InvalidPath);
+        }
+
+        if is_suspicious_path(&canonical) {
+            return Err(SecurityError::SuspiciousPath);
+        }
+
+        Ok(canonical)
+    }
+
     /// Checks if path contains suspicious patterns
     pub fn is_suspicious_path(path: &Path) -> bool {
         let path_str = path.to_string_lossy();
@@ -1,6 +1,7 @@
 //! Editor text rendering with cursor and selection
 
-use super::helpers::{char_range_to_bytes, split_at_char_index};
+use super::helpers::{char_range_to_bytes, gutter_span, gutter_width, split_at_char_index};
+use crate::helix::Viewport;
 use ratatui::{
     style::{Color, Modifier, Style},
     text::{Line, Span},
@@ -12,9 +13,13 @@ use ratatui::{
 /// - Green: lines that match target
 /// - Red: lines that differ from target
 /// - Cursor shown with inverse colors
+///
+/// Only the lines inside `viewport` are rendered, each prefixed with a
+/// line-number gutter.
 pub(super) fn render_editor_with_diff<'a>(
     current: &'a crate::game::EditorState,
     target: &crate::game::EditorState,
+    viewport: Viewport,
 ) -> Vec<Line<'a>> {
     let current_content = current.content();
     let target_content = target.content();
@@ -23,10 +28,14 @@ pub(super) fn render_editor_with_diff<'a>(
 
     let current_lines: Vec<&str> = current_content.lines().collect();
     let target_lines: Vec<&str> = target_content.lines().collect();
+    let visible = viewport.visible_lines(current_lines.len());
+    let width = gutter_width(current_lines.len());
 
     current_lines
         .iter()
         .enumerate()
+        .skip(visible.start)
+        .take(visible.len())
         .map(|(line_idx, &line_text)| {
             // Determine if this line matches target
             let matches_target = target_lines
@@ -80,129 +89,164 @@ pub(super) fn render_editor_with_diff<'a>(
                     ));
                 }
 
-                Line::from(spans)
+                with_gutter(line_idx, width, true, spans)
             } else {
                 // Regular line without cursor
-                Line::from(Span::styled(line_text, Style::default().fg(line_color)))
+                with_gutter(
+                    line_idx,
+                    width,
+                    false,
+                    vec![Span::styled(line_text, Style::default().fg(line_color))],
+                )
             }
         })
         .collect()
 }
 
-/// Render editor text with selection highlighted
+/// Render editor text with selections highlighted
 ///
-/// Takes EditorState and returns Vec<Line> with selection range
-/// highlighted using background color and cursor shown if present.
+/// Takes EditorState and returns Vec<Line> with every selection range
+/// highlighted using background color. Empty selections (extra cursors) and
+/// the cursor are shown with inverse colors.
+/// Only the lines inside `viewport` are rendered, with a line-number gutter.
 pub(super) fn render_editor_with_selection<'a>(
     state: &'a crate::game::EditorState,
+    viewport: Viewport,
 ) -> Vec<Line<'a>> {
     let content = state.content();
     let cursor = state.cursor_position();
-    let (cursor_line, cursor_col) = (cursor.row, cursor.col);
-    let selection = state.selection();
+    let selections = state.selections();
+    let total_lines = content.lines().count();
+    let visible = viewport.visible_lines(total_lines);
+    let width = gutter_width(total_lines);
 
     content
         .lines()
         .enumerate()
+        .skip(visible.start)
+        .take(visible.len())
         .map(|(line_idx, line_text)| {
-            if let Some(sel) = selection {
-                // Check if this line has selection
-                let sel_start_line = sel.start.row;
-                let sel_end_line = sel.end.row;
-
-                if line_idx >= sel_start_line && line_idx <= sel_end_line {
-                    // This line contains selection
-                    let mut spans = Vec::new();
-
-                    // Determine selection range for this line
-                    let line_start_col = if line_idx == sel_start_line {
-                        sel.start.col
-                    } else {
-                        0
-                    };
-
-                    let line_end_col = if line_idx == sel_end_line {
-                        sel.end.col
-                    } else {
-                        line_text.len()
-                    };
-
-                    // Get byte indices for selection range (zero-allocation)
-                    let (start_byte, end_byte) =
-                        char_range_to_bytes(line_text, line_start_col, line_end_col);
-
-                    // Text before selection
-                    if start_byte > 0 {
-                        spans.push(Span::styled(
-                            &line_text[..start_byte],
-                            Style::default().fg(Color::Yellow),
-                        ));
-                    }
-
-                    // Selected text with highlight
-                    if start_byte < end_byte {
-                        spans.push(Span::styled(
-                            &line_text[start_byte..end_byte],
-                            Style::default()
-                                .bg(Color::Blue)
-                                .fg(Color::White)
-                                .add_modifier(Modifier::BOLD),
-                        ));
-                    }
-
-                    // Text after selection
-                    if end_byte < line_text.len() {
-                        spans.push(Span::styled(
-                            &line_text[end_byte..],
-                            Style::default().fg(Color::Yellow),
-                        ));
-                    }
-
-                    return Line::from(spans);
-                }
+            let line_len = line_text.chars().count();
+            let mut highlights = line_highlights(selections, line_idx, line_len);
+
+            // Show the cursor on its line unless a selection already covers it
+            if line_idx == cursor.row
+                && !highlights
+                    .iter()
+                    .any(|&(start, end, _)| start <= cursor.col && cursor.col < end)
+            {
+                highlights.push((cursor.col, cursor.col + 1, cursor_style()));
+                highlights.sort_by_key(|&(start, _, _)| start);
             }
 
-            // No selection on this line - show with cursor if applicable
-            if line_idx == cursor_line {
-                let mut spans = Vec::new();
-
-                // Split line at cursor position (zero-allocation)
-                let (before_end, char_start, char_end, after_start) =
-                    split_at_char_index(line_text, cursor_col);
+            let spans = highlighted_spans(line_text, &highlights);
+            with_gutter(line_idx, width, line_idx == cursor.row, spans)
+        })
+        .collect()
+}
 
-                if before_end > 0 {
-                    spans.push(Span::styled(
-                        &line_text[..before_end],
-                        Style::default().fg(Color::Yellow),
-                    ));
-                }
+/// Style for cursors (inverse colors)
+fn cursor_style() -> Style {
+    Style::default()
+        .bg(Color::White)
+        .fg(Color::Black)
+        .add_modifier(Modifier::BOLD)
+}
 
-                let cursor_char = &line_text[char_start..char_end];
-                let cursor_display = if cursor_char.is_empty() {
-                    " "
-                } else {
-                    cursor_char
-                };
-                spans.push(Span::styled(
-                    cursor_display,
-                    Style::default()
-                        .bg(Color::White)
-                        .fg(Color::Black)
-                        .add_modifier(Modifier::BOLD),
-                ));
+/// Style for selected text
+fn selection_style() -> Style {
+    Style::default()
+        .bg(Color::Blue)
+        .fg(Color::White)
+        .add_modifier(Modifier::BOLD)
+}
 
-                if after_start < line_text.len() {
-                    spans.push(Span::styled(
-                        &line_text[after_start..],
-                        Style::default().fg(Color::Yellow),
-                    ));
-                }
+/// Highlighted char ranges `(start_col, end_col, style)` of one line, sorted by start
+fn line_highlights(
+    selections: &[crate::game::Selection],
+    line_idx: usize,
+    line_len: usize,
+) -> Vec<(usize, usize, Style)> {
+    let mut highlights: Vec<(usize, usize, Style)> = selections
+        .iter()
+        .filter_map(|sel| {
+            let (start, end) = sel.normalized();
+            if line_idx < start.row || line_idx > end.row {
+                return None;
+            }
+            if sel.is_empty() {
+                return Some((start.col, start.col + 1, cursor_style()));
+            }
 
-                Line::from(spans)
+            let from = if line_idx == start.row { start.col } else { 0 };
+            let to = if line_idx == end.row {
+                end.col
             } else {
-                // Regular line
-                Line::from(Span::styled(line_text, Style::default().fg(Color::Yellow)))
-            }
+                line_len
+            };
+            (from < to).then_some((from, to, selection_style()))
         })
-        .collect()
+        .collect();
+    highlights.sort_by_key(|&(start, _, _)| start);
+    highlights
+}
+
+/// Split a line into spans, styling the highlighted char ranges
+///
+/// Highlights past the end of the line (a cursor on the line break) are
+/// drawn as a single space.
+fn highlighted_spans<'a>(
+    line_text: &'a str,
+    highlights: &[(usize, usize, Style)],
+) -> Vec<Span<'a>> {
+    let text_style = Style::default().fg(Color::Yellow);
+    let line_len = line_text.chars().count();
+    let mut spans = Vec::new();
+    let mut col = 0;
+
+    for &(start, end, style) in highlights {
+        // Skip overlapping parts of earlier highlights
+        let start = start.max(col);
+        if start >= end {
+            continue;
+        }
+
+        if start >= line_len {
+            if col < line_len {
+                let (from, _) = char_range_to_bytes(line_text, col, line_len);
+                spans.push(Span::styled(&line_text[from..], text_style));
+            }
+            spans.push(Span::styled(" ", style));
+            col = line_len;
+            break;
+        }
+
+        if col < start {
+            let (from, to) = char_range_to_bytes(line_text, col, start);
+            spans.push(Span::styled(&line_text[from..to], text_style));
+        }
+        let end = end.min(line_len);
+        let (from, to) = char_range_to_bytes(line_text, start, end);
+        spans.push(Span::styled(&line_text[from..to], style));
+        col = end;
+    }
+
+    if col < line_len {
+        let (from, _) = char_range_to_bytes(line_text, col, line_len);
+        spans.push(Span::styled(&line_text[from..], text_style));
+    }
+    spans
+}
+
+/// Build a rendered line from its text spans, prefixed with the line number
+fn with_gutter<'a>(
+    line_idx: usize,
+    width: usize,
+    is_cursor_line: bool,
+    spans: Vec<Span<'a>>,
+) -> Line<'a> {
+    let mut line_spans = Vec::with_capacity(spans.len() + 1);
+    line_spans.push(gutter_span(line_idx, width, is_cursor_line));
+    line_spans.extend(spans);
+    Line::from(line_spans)
 }
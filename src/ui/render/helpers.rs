@@ -2,7 +2,8 @@
 
 use ratatui::{
     layout::Rect,
-    style::{Color, Style},
+    style::{Color, Modifier, Style},
+    text::Span,
     widgets::{Block, Borders},
 };
 
@@ -129,3 +130,29 @@ pub(super) fn inner_rect(outer: Rect) -> Rect {
         height: outer.height.saturating_sub(2),
     }
 }
+
+/// Width of the line-number gutter for a document with `total_lines` lines
+///
+/// Wide enough for the largest line number, with a minimum of 3 digits so the
+/// text column does not shift while editing small files.
+pub(super) fn gutter_width(total_lines: usize) -> usize {
+    total_lines.max(1).to_string().len().max(3)
+}
+
+/// Create the gutter span (1-based line number plus separator) for a line
+///
+/// # Arguments
+///
+/// * `line_idx` - 0-based line index
+/// * `width` - Gutter width from [`gutter_width`]
+/// * `is_cursor_line` - Highlight the number of the line holding the cursor
+pub(super) fn gutter_span(line_idx: usize, width: usize, is_cursor_line: bool) -> Span<'static> {
+    let style = if is_cursor_line {
+        Style::default()
+            .fg(Color::White)
+            .add_modifier(Modifier::BOLD)
+    } else {
+        Style::default().fg(Color::DarkGray)
+    };
+    Span::styled(format!("{:>width$} ", line_idx + 1), style)
+}
@@ -1,5 +1,7 @@
 //! Main menu rendering
 
+use crate::config::Scenario;
+use crate::security::limits::MAX_DIFFICULTY;
 use crate::ui::state::AppState;
 use ratatui::{
     Frame,
@@ -37,46 +39,83 @@ pub(super) fn render_main_menu(frame: &mut Frame, state: &mut AppState) {
 
     // Calculate visible area height for menu (excluding borders)
     let menu_height = chunks[1].height.saturating_sub(2) as usize; // -2 for borders
-    let total_items = state.scenarios.len() + 1; // +1 for Quit option
-
-    // Adjust scroll offset to keep selected item visible
-    if state.selected_menu_item < state.menu_scroll_offset {
-        // Selected item is above visible area - scroll up
-        state.menu_scroll_offset = state.selected_menu_item;
-    } else if state.selected_menu_item >= state.menu_scroll_offset + menu_height {
-        // Selected item is below visible area - scroll down
-        state.menu_scroll_offset = state.selected_menu_item.saturating_sub(menu_height - 1);
-    }
 
-    // Clamp scroll offset to valid range
-    let max_offset = total_items.saturating_sub(menu_height);
-    state.menu_scroll_offset = state.menu_scroll_offset.min(max_offset);
+    // Menu rows - pack headers, all scenarios, the motion drill, Settings and
+    // the Quit option. Headers cannot be selected, so scrolling works on rows
+    // rather than items.
+    let mut menu_items: Vec<ListItem> = Vec::new();
+    let mut selected_row = 0;
+    for (i, scenario) in state.scenarios.iter().enumerate() {
+        if let Some(title) = state.pack_title_at(i) {
+            menu_items.push(
+                ListItem::new(title.to_string()).style(
+                    Style::default()
+                        .fg(Color::Cyan)
+                        .add_modifier(Modifier::BOLD),
+                ),
+            );
+        }
 
-    // Menu items - show all scenarios + Quit option
-    let mut menu_items: Vec<ListItem> = state
-        .scenarios
-        .iter()
-        .enumerate()
-        .map(|(i, scenario)| {
-            let selected = i == state.selected_menu_item;
-            let style = if selected {
-                Style::default()
-                    .bg(Color::Blue)
-                    .fg(Color::White)
-                    .add_modifier(Modifier::BOLD)
-            } else {
-                Style::default().fg(Color::White)
-            };
+        let selected = i == state.selected_menu_item;
+        if selected {
+            selected_row = menu_items.len();
+        }
+        let style = if selected {
+            Style::default()
+                .bg(Color::Blue)
+                .fg(Color::White)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(Color::White)
+        };
+
+        let prefix = if selected { "> " } else { "  " };
+        let display = menu_label(i, scenario);
+        menu_items.push(ListItem::new(format!("{}{}", prefix, display)).style(style));
+    }
 
-            let prefix = if selected { "> " } else { "  " };
-            let display = format!("{}. {}", i + 1, scenario.name);
-            ListItem::new(format!("{}{}", prefix, display)).style(style)
-        })
-        .collect();
+    // Endless motion drill after the scenarios
+    let drill_selected = state.motion_drill_index() == state.selected_menu_item;
+    if drill_selected {
+        selected_row = menu_items.len();
+    }
+    let drill_style = if drill_selected {
+        Style::default()
+            .bg(Color::Blue)
+            .fg(Color::White)
+            .add_modifier(Modifier::BOLD)
+    } else {
+        Style::default().fg(Color::Yellow)
+    };
+    let drill_prefix = if drill_selected { "> " } else { "  " };
+    menu_items.push(
+        ListItem::new(format!("{}{}", drill_prefix, t!("menu.motion_drill"))).style(drill_style),
+    );
+
+    // Settings before Quit
+    let settings_selected = state.settings_index() == state.selected_menu_item;
+    if settings_selected {
+        selected_row = menu_items.len();
+    }
+    let settings_style = if settings_selected {
+        Style::default()
+            .bg(Color::Blue)
+            .fg(Color::White)
+            .add_modifier(Modifier::BOLD)
+    } else {
+        Style::default().fg(Color::Cyan)
+    };
+    let settings_prefix = if settings_selected { "> " } else { "  " };
+    menu_items.push(
+        ListItem::new(format!("{}{}", settings_prefix, t!("menu.settings"))).style(settings_style),
+    );
 
     // Add Quit option at the end
-    let quit_index = state.scenarios.len();
+    let quit_index = state.quit_index();
     let quit_selected = quit_index == state.selected_menu_item;
+    if quit_selected {
+        selected_row = menu_items.len();
+    }
     let quit_style = if quit_selected {
         Style::default()
             .bg(Color::Blue)
@@ -88,6 +127,22 @@ pub(super) fn render_main_menu(frame: &mut Frame, state: &mut AppState) {
     let quit_prefix = if quit_selected { "> " } else { "  " };
     menu_items.push(ListItem::new(format!("{}{}", quit_prefix, t!("menu.quit"))).style(quit_style));
 
+    let total_rows = menu_items.len();
+    let total_items = state.quit_index() + 1; // Scenarios, Motion Drill, Settings and Quit
+
+    // Adjust scroll offset to keep selected row visible
+    if selected_row < state.menu_scroll_offset {
+        // Selected row is above visible area - scroll up
+        state.menu_scroll_offset = selected_row;
+    } else if selected_row >= state.menu_scroll_offset + menu_height {
+        // Selected row is below visible area - scroll down
+        state.menu_scroll_offset = selected_row.saturating_sub(menu_height - 1);
+    }
+
+    // Clamp scroll offset to valid range
+    let max_offset = total_rows.saturating_sub(menu_height);
+    state.menu_scroll_offset = state.menu_scroll_offset.min(max_offset);
+
     // Apply scroll offset by skipping items
     let visible_items: Vec<ListItem> = menu_items
         .into_iter()
@@ -96,14 +151,14 @@ pub(super) fn render_main_menu(frame: &mut Frame, state: &mut AppState) {
         .collect();
 
     // Add scroll indicator to title if list is scrollable
-    let menu_title = if total_items > menu_height {
+    let menu_title = if total_rows > menu_height {
         let first_visible = state.menu_scroll_offset + 1;
-        let last_visible = (state.menu_scroll_offset + menu_height).min(total_items);
+        let last_visible = (state.menu_scroll_offset + menu_height).min(total_rows);
         t!(
             "menu.main_menu_with_scroll",
             first = first_visible,
             last = last_visible,
-            total = total_items
+            total = total_rows
         )
         .to_string()
     } else {
@@ -116,20 +171,20 @@ pub(super) fn render_main_menu(frame: &mut Frame, state: &mut AppState) {
     frame.render_widget(menu, chunks[1]);
 
     // Draw scrollbar if needed
-    if total_items > menu_height {
+    if total_rows > menu_height {
         let scrollbar_area = chunks[1];
         let scrollbar_height = scrollbar_area.height.saturating_sub(2) as usize; // -2 for borders
 
         if scrollbar_height > 0 {
             // Calculate scrollbar position
-            let scrollbar_pos = if total_items > 1 {
-                (state.menu_scroll_offset * scrollbar_height) / (total_items - menu_height).max(1)
+            let scrollbar_pos = if total_rows > 1 {
+                (state.menu_scroll_offset * scrollbar_height) / (total_rows - menu_height).max(1)
             } else {
                 0
             };
 
             // Calculate scrollbar thumb size (proportional to visible items)
-            let thumb_size = ((menu_height * scrollbar_height) / total_items).max(1);
+            let thumb_size = ((menu_height * scrollbar_height) / total_rows).max(1);
 
             // Draw scrollbar on the right edge
             for y in 0..scrollbar_height {
@@ -170,3 +225,17 @@ pub(super) fn render_main_menu(frame: &mut Frame, state: &mut AppState) {
         .block(Block::default().borders(Borders::ALL));
     frame.render_widget(instructions, chunks[2]);
 }
+
+/// Numbered scenario name, followed by its category and difficulty stars
+fn menu_label(index: usize, scenario: &Scenario) -> String {
+    let mut label = format!("{}. {}", index + 1, scenario.name);
+    if let Some(category) = &scenario.category {
+        label.push_str(&format!("  [{}]", category));
+    }
+    if let Some(difficulty) = scenario.difficulty {
+        let filled = usize::from(difficulty.min(MAX_DIFFICULTY));
+        let empty = usize::from(MAX_DIFFICULTY) - filled;
+        label.push_str(&format!("  {}{}", "★".repeat(filled), "☆".repeat(empty)));
+    }
+    label
+}
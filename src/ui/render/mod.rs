@@ -9,7 +9,9 @@ mod helpers;
 mod menu;
 mod popups;
 mod results;
+mod settings;
 mod task;
+mod theme;
 
 #[cfg(test)]
 mod tests;
@@ -31,5 +33,7 @@ pub fn render(frame: &mut Frame, state: &mut AppState) {
         Screen::MainMenu => menu::render_main_menu(frame, state),
         Screen::Task => task::render_task_screen(frame, state),
         Screen::Results => results::render_results_screen(frame, state),
+        Screen::Settings => settings::render_settings(frame, state),
     }
+    theme::apply_theme(frame.buffer_mut(), state.config.theme);
 }
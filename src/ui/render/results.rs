@@ -42,6 +42,12 @@ pub(super) fn render_results_screen(frame: &mut Frame, state: &AppState) {
         // Title
         let title_text = if feedback.success {
             t!("results.completed").to_string()
+        } else if session.is_completed() {
+            t!(
+                "results.missing_required",
+                commands = feedback.missing_commands.join(" ")
+            )
+            .to_string()
         } else {
             t!("results.abandoned").to_string()
         };
@@ -78,13 +84,24 @@ pub(super) fn render_results_screen(frame: &mut Frame, state: &AppState) {
         result_lines.push(Line::from(""));
 
         // Score
-        result_lines.push(Line::from(vec![
+        let mut score_spans = vec![
             Span::raw(format!("{}: ", t!("results.score"))),
             Span::styled(
                 format!("{}/{}", feedback.score, feedback.max_points),
                 Style::default().fg(Color::Cyan),
             ),
-        ]));
+        ];
+        if feedback.hint_penalty > 0 {
+            score_spans.push(Span::styled(
+                format!(
+                    " ({} {})",
+                    t!("results.hints_title"),
+                    t!("scoring.penalty", points = feedback.hint_penalty)
+                ),
+                Style::default().fg(Color::Red),
+            ));
+        }
+        result_lines.push(Line::from(score_spans));
 
         // Actions
         let action_color = if feedback.is_optimal {
@@ -106,13 +123,40 @@ pub(super) fn render_results_screen(frame: &mut Frame, state: &AppState) {
         ]));
 
         // Duration
-        result_lines.push(Line::from(vec![
-            Span::raw("Time: "),
-            Span::styled(
-                format!("{:.1}s", feedback.duration.as_secs_f32()),
-                Style::default().fg(Color::Blue),
-            ),
-        ]));
+        if state.config.show_timer {
+            result_lines.push(Line::from(vec![
+                Span::raw("Time: "),
+                Span::styled(
+                    format!("{:.1}s", feedback.duration.as_secs_f32()),
+                    Style::default().fg(Color::Blue),
+                ),
+            ]));
+        }
+
+        // Per-step summary for lessons
+        if feedback.steps.len() > 1 {
+            result_lines.push(Line::from(""));
+            for (index, step) in feedback.steps.iter().enumerate() {
+                result_lines.push(Line::from(vec![
+                    Span::styled(
+                        format!("{} ", t!("results.step", number = index + 1)),
+                        Style::default().fg(Color::Yellow),
+                    ),
+                    Span::styled(
+                        format!("{}/{}", step.score, step.max_points),
+                        Style::default().fg(Color::Cyan),
+                    ),
+                    Span::raw(format!(
+                        " - {} {} ({}: {}) - {}",
+                        step.actions_taken,
+                        t!("task.actions").to_lowercase(),
+                        t!("results.optimal_actions"),
+                        step.optimal_actions,
+                        step.instructions
+                    )),
+                ]));
+            }
+        }
 
         // Hint if provided
         if let Some(hint) = &feedback.hint {
@@ -134,7 +178,12 @@ pub(super) fn render_results_screen(frame: &mut Frame, state: &AppState) {
         frame.render_widget(results, chunks[1]);
 
         // Instructions
-        let instructions = Paragraph::new(t!("results.instructions").to_string())
+        let instructions = if state.drill_round.is_some() {
+            t!("results.instructions_drill")
+        } else {
+            t!("results.instructions")
+        };
+        let instructions = Paragraph::new(instructions.to_string())
             .style(Style::default().fg(Color::Gray))
             .alignment(Alignment::Center)
             .block(Block::default().borders(Borders::ALL));
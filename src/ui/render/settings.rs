@@ -0,0 +1,68 @@
+//! Settings screen rendering
+
+use crate::ui::state::{AppState, Setting};
+use ratatui::{
+    Frame,
+    layout::{Alignment, Constraint, Direction, Layout},
+    style::{Color, Modifier, Style},
+    widgets::{Block, Borders, List, ListItem, Paragraph},
+};
+use rust_i18n::t;
+
+/// Render the settings screen
+pub(super) fn render_settings(frame: &mut Frame, state: &AppState) {
+    let area = frame.area();
+
+    // Create layout: title | settings | instructions
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(2)
+        .constraints([
+            Constraint::Length(3),
+            Constraint::Min(4),
+            Constraint::Length(3),
+        ])
+        .split(area);
+
+    let title = Paragraph::new(t!("settings.title").to_string())
+        .style(
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        )
+        .alignment(Alignment::Center)
+        .block(Block::default().borders(Borders::ALL));
+    frame.render_widget(title, chunks[0]);
+
+    let rows: Vec<ListItem> = Setting::ALL
+        .iter()
+        .enumerate()
+        .map(|(index, &setting)| {
+            // The locale is shown as its code, other values by name
+            let value = match setting {
+                Setting::Locale => state.setting_value(setting).to_string(),
+                _ => t!(state.setting_value(setting)).to_string(),
+            };
+            let selected = index == state.selected_setting;
+            let style = if selected {
+                Style::default()
+                    .bg(Color::Blue)
+                    .fg(Color::White)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(Color::White)
+            };
+            let prefix = if selected { "> " } else { "  " };
+            ListItem::new(format!("{}{}: {}", prefix, t!(setting.label_key()), value)).style(style)
+        })
+        .collect();
+
+    let list = List::new(rows).block(Block::default().borders(Borders::ALL));
+    frame.render_widget(list, chunks[1]);
+
+    let instructions = Paragraph::new(t!("settings.instructions").to_string())
+        .style(Style::default().fg(Color::Gray))
+        .alignment(Alignment::Center)
+        .block(Block::default().borders(Borders::ALL));
+    frame.render_widget(instructions, chunks[2]);
+}
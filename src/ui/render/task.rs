@@ -13,25 +13,37 @@ use ratatui::{
 use rust_i18n::t;
 
 /// Render the task screen where user plays a scenario
-pub(super) fn render_task_screen(frame: &mut Frame, state: &AppState) {
+pub(super) fn render_task_screen(frame: &mut Frame, state: &mut AppState) {
     let area = frame.area();
 
+    // Layout: title | description | editor view | stats | instructions
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(1)
+        .constraints([
+            Constraint::Length(3), // Title
+            Constraint::Length(4), // Description
+            Constraint::Min(8),    // Editor view
+            Constraint::Length(3), // Stats
+            Constraint::Length(3), // Instructions
+        ])
+        .split(area);
+
+    // Editor view - split into current and target
+    let editor_chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(chunks[2]);
+
+    // Size the simulator viewport to the editor pane (minus borders)
+    if let Some(session) = &mut state.session {
+        session.set_viewport_height(editor_chunks[0].height.saturating_sub(2) as usize);
+    }
+
+    let state = &*state;
     if let Some(session) = &state.session {
         let scenario = session.scenario();
 
-        // Layout: title | description | editor view | stats | instructions
-        let chunks = Layout::default()
-            .direction(Direction::Vertical)
-            .margin(1)
-            .constraints([
-                Constraint::Length(3), // Title
-                Constraint::Length(4), // Description
-                Constraint::Min(8),    // Editor view
-                Constraint::Length(3), // Stats
-                Constraint::Length(3), // Instructions
-            ])
-            .split(area);
-
         // Title
         let title = Paragraph::new(format!("Scenario: {}", scenario.name))
             .style(
@@ -43,61 +55,90 @@ pub(super) fn render_task_screen(frame: &mut Frame, state: &AppState) {
             .block(Block::default().borders(Borders::ALL));
         frame.render_widget(title, chunks[0]);
 
-        // Description
-        let description = Paragraph::new(scenario.description.as_str())
+        // Description, or the current step's instructions in a lesson
+        let description_title = if session.step_count() > 1 {
+            t!(
+                "task.step",
+                current = session.current_step() + 1,
+                total = session.step_count()
+            )
+        } else {
+            t!("task.title")
+        };
+        // Command constraints follow the title on the block border
+        let mut title_spans = vec![Span::raw(description_title.to_string())];
+        if !scenario.forbidden_commands.is_empty() {
+            title_spans.push(Span::styled(
+                format!(
+                    " | {}",
+                    t!(
+                        "task.forbidden",
+                        commands = scenario.forbidden_commands.join(" ")
+                    )
+                ),
+                Style::default().fg(Color::Red),
+            ));
+        }
+        let missing = session.missing_commands();
+        if !missing.is_empty() {
+            title_spans.push(Span::styled(
+                format!(" | {}", t!("task.required", commands = missing.join(" "))),
+                Style::default().fg(Color::Yellow),
+            ));
+        }
+        let description = Paragraph::new(session.instructions())
             .wrap(Wrap { trim: true })
             .style(Style::default().fg(Color::White))
             .block(
                 Block::default()
-                    .title(t!("task.title").to_string())
+                    .title(Line::from(title_spans))
                     .borders(Borders::ALL),
             );
         frame.render_widget(description, chunks[1]);
 
-        // Editor view - split into current and target
-        let editor_chunks = Layout::default()
-            .direction(Direction::Horizontal)
-            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
-            .split(chunks[2]);
-
         // Current state with cursor and diff highlighting
+        // (no wrapping: the viewport maps one document line to one row)
         let current_state = session.current_state();
         let target_state = session.target_state();
-        let current_lines = render_editor_with_diff(current_state, target_state);
-        let current = Paragraph::new(current_lines)
-            .block(
-                Block::default()
-                    .title(t!("editor.current_state").to_string())
-                    .borders(Borders::ALL),
-            )
-            .wrap(Wrap { trim: false });
+        let viewport = session.viewport();
+        let current_lines = render_editor_with_diff(current_state, target_state, viewport);
+        let current = Paragraph::new(current_lines).block(
+            Block::default()
+                .title(t!("editor.current_state").to_string())
+                .borders(Borders::ALL),
+        );
         frame.render_widget(current, editor_chunks[0]);
 
-        // Target state with selection highlighting (if any)
-        let target_state = session.target_state();
-        let target_lines = render_editor_with_selection(target_state);
-        let target = Paragraph::new(target_lines)
-            .block(
-                Block::default()
-                    .title(t!("editor.target_state").to_string())
-                    .borders(Borders::ALL),
-            )
-            .wrap(Wrap { trim: false });
+        // Target state with selection highlighting (if any), scrolled in step
+        // with the current pane unless that would hide the target cursor
+        let target_viewport = viewport.scrolled_to(
+            target_state.cursor_position().row,
+            target_state.content().lines().count(),
+        );
+        let target_lines = render_editor_with_selection(target_state, target_viewport);
+        let target = Paragraph::new(target_lines).block(
+            Block::default()
+                .title(t!("editor.target_state").to_string())
+                .borders(Borders::ALL),
+        );
         frame.render_widget(target, editor_chunks[1]);
 
-        // Stats with mode indicator and progress
-        let optimal = scenario.scoring.optimal_count;
-        let actions = session.action_count();
-        let elapsed = session.elapsed();
-        let elapsed_secs = elapsed.as_secs_f32();
+        // Stats with mode indicator and progress, counted per lesson step
+        let optimal = session.step_scoring().optimal_count;
+        let actions = session.step_action_count();
+        let timer = if state.config.show_timer {
+            format!(" | Time: {:.1}s", session.elapsed().as_secs_f32())
+        } else {
+            String::new()
+        };
         let mode = session.mode_name();
         let progress = session.completion_progress();
 
-        // Color code mode: green for Normal, yellow for Insert
-        let mode_color = if mode == "NORMAL" {
-            Color::Green
-        } else {
-            Color::Yellow
+        // Color code mode: green for Normal, yellow for Insert, magenta for Select
+        let mode_color = match mode {
+            "NORMAL" => Color::Green,
+            "SELECT" => Color::Magenta,
+            _ => Color::Yellow,
         };
 
         // Color code progress: green if 100%, yellow if >50%, red otherwise
@@ -110,10 +151,10 @@ pub(super) fn render_task_screen(frame: &mut Frame, state: &AppState) {
         };
 
         // Translate mode name for display
-        let mode_display = if mode == "NORMAL" {
-            t!("task.mode_normal")
-        } else {
-            t!("task.mode_insert")
+        let mode_display = match mode {
+            "NORMAL" => t!("task.mode_normal"),
+            "SELECT" => t!("task.mode_select"),
+            _ => t!("task.mode_insert"),
         };
 
         // Create colored mode indicator
@@ -133,27 +174,35 @@ pub(super) fn render_task_screen(frame: &mut Frame, state: &AppState) {
         // Create rest of stats
         let rest_of_stats = if actions <= optimal {
             format!(
-                "| {}: {} ({}: {}) | Time: {:.1}s",
+                "| {}: {} ({}: {}){}",
                 t!("task.actions"),
                 actions,
                 t!("task.optimal"),
                 optimal,
-                elapsed_secs
+                timer
             )
         } else {
             format!(
-                "| {}: {} ({}: {}) - {} extra | Time: {:.1}s",
+                "| {}: {} ({}: {}) - {} extra{}",
                 t!("task.actions"),
                 actions,
                 t!("task.optimal"),
                 optimal,
                 actions - optimal,
-                elapsed_secs
+                timer
             )
         };
         let rest_span = Span::styled(rest_of_stats, Style::default().fg(Color::White));
+        let mut stats_spans = vec![mode_span, progress_span, rest_span];
+        let blocked = session.blocked_commands().len();
+        if blocked > 0 {
+            stats_spans.push(Span::styled(
+                format!(" | {}: {}", t!("task.blocked"), blocked),
+                Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+            ));
+        }
 
-        let stats = Paragraph::new(Line::from(vec![mode_span, progress_span, rest_span]))
+        let stats = Paragraph::new(Line::from(stats_spans))
             .alignment(Alignment::Center)
             .block(Block::default().borders(Borders::ALL));
         frame.render_widget(stats, chunks[3]);
@@ -1,22 +1,41 @@
 //! Tests for rendering functions
 
-use crate::config::{ScoringConfig, Setup, Solution, TargetState};
+use crate::config::{Completion, ScoringConfig, Setup, SetupMode, Solution, TargetState};
 use crate::ui::state::AppState;
+use std::collections::BTreeMap;
 
 fn create_test_scenario() -> crate::config::Scenario {
     crate::config::Scenario {
         id: "test_001".to_string(),
         name: "Test Scenario".to_string(),
         description: "A test scenario for rendering".to_string(),
+        category: None,
+        difficulty: None,
+        tags: Vec::new(),
+        requires: Vec::new(),
+        commands_taught: Vec::new(),
+        forbidden_commands: Vec::new(),
+        required_commands: Vec::new(),
+        estimated_seconds: None,
         setup: Setup {
             file_content: "line 1\n".to_string(),
-            cursor_position: (0, 0),
+            cursor_position: Some((0, 0)),
+            selections: Vec::new(),
+            primary_selection: 0,
+            mode: SetupMode::Normal,
+            registers: BTreeMap::new(),
+            last_action: Vec::new(),
+            indent_unit: None,
         },
-        target: TargetState {
+        target: Some(TargetState {
             file_content: "line 2\n".to_string(),
-            cursor_position: (0, 0),
+            cursor_position: Some((0, 0)),
             selection: None,
-        },
+            selections: Vec::new(),
+            primary_selection: 0,
+            match_selection: false,
+            completion: Completion::Exact,
+        }),
         solution: Solution {
             commands: vec!["dd".to_string()],
             description: "Delete line".to_string(),
@@ -28,6 +47,9 @@ fn create_test_scenario() -> crate::config::Scenario {
             max_points: 100,
             tolerance: 0,
         },
+        markers: false,
+        template: None,
+        steps: Vec::new(),
     }
 }
 
@@ -74,3 +96,205 @@ fn test_render_task_screen_with_session() {
         })
         .unwrap();
 }
+
+#[test]
+fn test_render_task_screen_scrolls_to_cursor() {
+    use ratatui::Terminal;
+    use ratatui::backend::TestBackend;
+
+    let content = (1..=200)
+        .map(|i| format!("row{}", i))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let mut scenario = create_test_scenario();
+    scenario.setup = Setup {
+        file_content: content.clone(),
+        cursor_position: Some((150, 0)),
+        selections: Vec::new(),
+        primary_selection: 0,
+        mode: SetupMode::Normal,
+        registers: BTreeMap::new(),
+        last_action: Vec::new(),
+        indent_unit: None,
+    };
+    scenario.target = Some(TargetState {
+        file_content: content,
+        cursor_position: Some((150, 0)),
+        selection: None,
+        selections: Vec::new(),
+        primary_selection: 0,
+        match_selection: false,
+        completion: Completion::Exact,
+    });
+
+    let backend = TestBackend::new(100, 40);
+    let mut terminal = Terminal::new(backend).unwrap();
+    let mut state = AppState::new(vec![scenario]);
+    crate::ui::update(&mut state, crate::ui::Message::StartScenario(0)).unwrap();
+
+    terminal
+        .draw(|f| {
+            super::super::render(f, &mut state);
+        })
+        .unwrap();
+
+    let rendered: String = terminal
+        .backend()
+        .buffer()
+        .content()
+        .iter()
+        .map(|cell| cell.symbol())
+        .collect();
+
+    // The cursor line is visible with its line number; the top of the file is not
+    assert!(rendered.contains("151 row151"));
+    assert!(!rendered.contains("row1 "));
+
+    // The viewport was sized to the editor pane
+    let session = state.session.as_ref().unwrap();
+    assert!(session.viewport().height() < 40);
+}
+
+#[test]
+fn test_render_main_menu_shows_pack_headers() {
+    use ratatui::Terminal;
+    use ratatui::backend::TestBackend;
+
+    let backend = TestBackend::new(80, 24);
+    let mut terminal = Terminal::new(backend).unwrap();
+    let mut state = AppState::new(vec![create_test_scenario(), create_test_scenario()]);
+    state.pack_titles = vec![(1, "Basics 1.0.0".to_string())];
+
+    terminal
+        .draw(|f| {
+            super::super::render(f, &mut state);
+        })
+        .unwrap();
+
+    let buffer = terminal.backend().buffer();
+    let rows: Vec<String> = (0..buffer.area.height)
+        .map(|y| {
+            (0..buffer.area.width)
+                .map(|x| buffer[(x, y)].symbol())
+                .collect()
+        })
+        .collect();
+    let row_of = |text: &str| rows.iter().position(|row| row.contains(text)).unwrap();
+
+    // The header sits between the unpacked scenario and the pack's scenario
+    assert!(row_of("1. Test Scenario") < row_of("Basics 1.0.0"));
+    assert!(row_of("Basics 1.0.0") < row_of("2. Test Scenario"));
+
+    // The motion drill follows the scenarios, then Settings and Quit
+    assert!(row_of("2. Test Scenario") < row_of("Motion Drill"));
+    assert!(row_of("Motion Drill") < row_of("Settings"));
+    assert!(row_of("Settings") < row_of("Quit"));
+}
+
+#[test]
+fn test_render_main_menu_shows_category_and_difficulty() {
+    use ratatui::Terminal;
+    use ratatui::backend::TestBackend;
+
+    let backend = TestBackend::new(80, 24);
+    let mut terminal = Terminal::new(backend).unwrap();
+    let mut scenario = create_test_scenario();
+    scenario.category = Some("movement".to_string());
+    scenario.difficulty = Some(2);
+    let mut state = AppState::new(vec![scenario]);
+
+    terminal
+        .draw(|f| {
+            super::super::render(f, &mut state);
+        })
+        .unwrap();
+
+    let buffer = terminal.backend().buffer();
+    let row: String = (0..buffer.area.height)
+        .map(|y| {
+            (0..buffer.area.width)
+                .map(|x| buffer[(x, y)].symbol())
+                .collect::<String>()
+        })
+        .find(|row| row.contains("1. Test Scenario"))
+        .unwrap();
+    assert!(row.contains("[movement]"), "{row}");
+    assert!(row.contains("★★☆☆☆"), "{row}");
+}
+
+#[test]
+fn test_render_settings_screen_with_theme() {
+    use crate::config::Theme;
+    use ratatui::Terminal;
+    use ratatui::backend::TestBackend;
+    use ratatui::style::{Color, Modifier};
+
+    let backend = TestBackend::new(80, 24);
+    let mut terminal = Terminal::new(backend).unwrap();
+    let mut state = AppState::new(vec![]);
+    state.screen = crate::ui::Screen::Settings;
+    state.config.theme = Theme::Monochrome;
+
+    terminal
+        .draw(|f| {
+            super::super::render(f, &mut state);
+        })
+        .unwrap();
+
+    let buffer = terminal.backend().buffer();
+    let rows: Vec<String> = (0..buffer.area.height)
+        .map(|y| {
+            (0..buffer.area.width)
+                .map(|x| buffer[(x, y)].symbol())
+                .collect()
+        })
+        .collect();
+    let row = rows
+        .iter()
+        .position(|row| row.contains("> Language: en"))
+        .unwrap();
+    assert!(rows.iter().any(|row| row.contains("Theme: Monochrome")));
+
+    // No colours remain, and the selected row is reversed instead
+    assert!(
+        buffer
+            .content
+            .iter()
+            .all(|cell| cell.fg == Color::Reset && cell.bg == Color::Reset)
+    );
+    assert!(
+        buffer[(4, row as u16)]
+            .modifier
+            .contains(Modifier::REVERSED)
+    );
+}
+
+#[test]
+fn test_render_task_screen_shows_command_constraints() {
+    use ratatui::Terminal;
+    use ratatui::backend::TestBackend;
+
+    let backend = TestBackend::new(100, 24);
+    let mut terminal = Terminal::new(backend).unwrap();
+    let scenario = crate::config::Scenario {
+        forbidden_commands: vec!["h".to_string(), "l".to_string()],
+        required_commands: vec![".".to_string()],
+        ..create_test_scenario()
+    };
+    let mut state = AppState::new(vec![scenario]);
+    crate::ui::update(&mut state, crate::ui::Message::StartScenario(0)).unwrap();
+
+    terminal
+        .draw(|f| {
+            super::super::render(f, &mut state);
+        })
+        .unwrap();
+
+    let buffer = terminal.backend().buffer();
+    let screen: String = (0..buffer.area.height)
+        .flat_map(|y| (0..buffer.area.width).map(move |x| (x, y)))
+        .map(|position| buffer[position].symbol())
+        .collect();
+    assert!(screen.contains("Without: h l"));
+    assert!(screen.contains("Must use: ."));
+}
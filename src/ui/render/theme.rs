@@ -0,0 +1,51 @@
+//! Colour themes
+//!
+//! Screens draw with the colours of the dark theme; other themes rewrite the
+//! frame's cells once the screen is drawn, so no screen needs to know about
+//! them.
+
+use crate::config::Theme;
+use ratatui::{
+    buffer::Buffer,
+    style::{Color, Modifier},
+};
+
+/// Rewrite the drawn cells for the theme
+pub(super) fn apply_theme(buffer: &mut Buffer, theme: Theme) {
+    match theme {
+        Theme::Dark => {}
+        Theme::Light => {
+            for cell in &mut buffer.content {
+                // Text on a coloured background keeps its colour
+                if matches!(cell.bg, Color::Reset | Color::Black | Color::White) {
+                    cell.fg = light_color(cell.fg);
+                }
+                cell.bg = light_color(cell.bg);
+            }
+        }
+        Theme::Monochrome => {
+            for cell in &mut buffer.content {
+                // Highlights become reversed text; dark popup backgrounds go
+                if !matches!(cell.bg, Color::Reset | Color::Black) {
+                    cell.modifier.insert(Modifier::REVERSED);
+                }
+                cell.fg = Color::Reset;
+                cell.bg = Color::Reset;
+            }
+        }
+    }
+}
+
+/// Counterpart of a dark theme colour that reads on a light background
+fn light_color(color: Color) -> Color {
+    match color {
+        Color::White => Color::Black,
+        Color::Black => Color::White,
+        Color::Gray => Color::DarkGray,
+        Color::DarkGray => Color::Gray,
+        Color::Yellow => Color::Indexed(136),
+        Color::Cyan => Color::Indexed(30),
+        Color::Green => Color::Indexed(28),
+        other => other,
+    }
+}
@@ -18,10 +18,12 @@
 //! - State transitions are testable and reproducible
 //! - UI rendering is pure (no side effects)
 
-use crate::config::Scenario;
-use crate::game::GameSession;
+use crate::config::{BASE_LOCALE, HintMode, Scenario, ScenarioPack, TrainerConfig};
+use crate::game::{DrillOptions, GameSession, generate_motion_drill};
+use crate::helix::keys::push_key;
 use crate::security::UserError;
 use std::fmt;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 /// The current screen being displayed in the UI
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -32,6 +34,45 @@ pub enum Screen {
     Task,
     /// Results screen after scenario completion
     Results,
+    /// Settings screen
+    Settings,
+}
+
+/// A row of the settings screen
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Setting {
+    /// Locale of the interface and the scenarios
+    Locale,
+    /// How strictly keys must match the Helix keymap
+    Keymap,
+    /// Colour theme
+    Theme,
+    /// When hints are shown
+    Hints,
+    /// Whether the elapsed time is shown
+    Timer,
+}
+
+impl Setting {
+    /// All settings in screen order
+    pub const ALL: [Self; 5] = [
+        Self::Locale,
+        Self::Keymap,
+        Self::Theme,
+        Self::Hints,
+        Self::Timer,
+    ];
+
+    /// Translation key of the setting's name
+    pub fn label_key(self) -> &'static str {
+        match self {
+            Self::Locale => "settings.locale",
+            Self::Keymap => "settings.keymap",
+            Self::Theme => "settings.theme",
+            Self::Hints => "settings.hints",
+            Self::Timer => "settings.timer",
+        }
+    }
 }
 
 /// Messages that trigger state updates
@@ -47,10 +88,12 @@ pub enum Message {
     /// Quit the application
     QuitApp,
 
-    /// Menu navigation: move selection up
+    /// Menu navigation: move selection up (the selected setting on the
+    /// settings screen)
     MenuUp,
 
-    /// Menu navigation: move selection down
+    /// Menu navigation: move selection down (the selected setting on the
+    /// settings screen)
     MenuDown,
 
     /// Menu action: select current menu item
@@ -59,6 +102,9 @@ pub enum Message {
     /// Start a scenario at the given index
     StartScenario(usize),
 
+    /// Start the endless motion drill with a freshly generated drill
+    StartMotionDrill,
+
     /// Mark the current scenario as complete
     CompleteScenario,
 
@@ -74,11 +120,14 @@ pub enum Message {
     /// Retry the current scenario
     RetryScenario,
 
-    /// Move to next scenario
+    /// Move to next scenario (the next drill during a motion drill)
     NextScenario,
 
     /// Return to main menu
     BackToMenu,
+
+    /// Switch the selected setting to its next value
+    ChangeSetting,
 }
 
 /// Main application state
@@ -101,6 +150,18 @@ pub struct AppState {
     /// Size: 24 bytes (Vec) - placed first for alignment
     pub scenarios: Vec<Scenario>,
 
+    /// Index of the first scenario of each named pack, with the pack title
+    /// Size: 24 bytes (Vec)
+    pub pack_titles: Vec<(usize, String)>,
+
+    /// Locales with scenarios, offered on the settings screen
+    /// Size: 24 bytes (Vec)
+    pub locales: Vec<String>,
+
+    /// User settings
+    /// Size: ~32 bytes (String and small enums)
+    pub config: TrainerConfig,
+
     /// Active game session (Some if on Task screen)
     /// Size: ~200+ bytes - large type, placed early
     pub session: Option<GameSession>,
@@ -133,6 +194,18 @@ pub struct AppState {
     /// Size: 8 bytes (usize)
     pub menu_scroll_offset: usize,
 
+    /// Seed the motion drills are generated from
+    /// Size: 8 bytes (u64)
+    pub drill_seed: u64,
+
+    /// Number of the current drill while the motion drill runs
+    /// Size: 16 bytes (Option<usize>)
+    pub drill_round: Option<usize>,
+
+    /// Index of the selected row on the settings screen
+    /// Size: 8 bytes (usize)
+    pub selected_setting: usize,
+
     /// The screen currently being displayed
     /// Size: 1 byte (enum)
     pub screen: Screen,
@@ -148,6 +221,10 @@ pub struct AppState {
     /// Whether to show key history popup
     /// Size: 1 byte (bool)
     pub show_key_history: bool,
+
+    /// Whether settings changed since the event loop last saved them
+    /// Size: 1 byte (bool)
+    pub config_changed: bool,
 }
 
 impl fmt::Debug for AppState {
@@ -156,7 +233,11 @@ impl fmt::Debug for AppState {
             .field("screen", &self.screen)
             .field("session", &"<GameSession>")
             .field("scenarios", &self.scenarios.len())
+            .field("pack_titles", &self.pack_titles.len())
             .field("selected_menu_item", &self.selected_menu_item)
+            .field("drill_round", &self.drill_round)
+            .field("config", &self.config)
+            .field("selected_setting", &self.selected_setting)
             .field("running", &self.running)
             .field("current_hint", &self.current_hint.is_some())
             .field("show_hint_panel", &self.show_hint_panel)
@@ -189,6 +270,9 @@ impl AppState {
     pub fn new(scenarios: Vec<Scenario>) -> Self {
         Self {
             scenarios,
+            pack_titles: Vec::new(),
+            locales: vec![BASE_LOCALE.to_string()],
+            config: TrainerConfig::default(),
             session: None,
             current_hint: None,
             last_command: None,
@@ -197,11 +281,54 @@ impl AppState {
             completion_time: None,
             selected_menu_item: 0,
             menu_scroll_offset: 0,
+            drill_seed: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |elapsed| elapsed.as_nanos() as u64),
+            drill_round: None,
+            selected_setting: 0,
             screen: Screen::MainMenu,
             running: true,
             show_hint_panel: false,
             show_key_history: false,
+            config_changed: false,
+        }
+    }
+
+    /// Create a new application state with scenarios grouped by pack
+    ///
+    /// Scenarios keep the pack order, and the main menu shows a header
+    /// above the scenarios of each pack that has a manifest.
+    pub fn with_packs(packs: Vec<ScenarioPack>) -> Self {
+        let mut state = Self::new(Vec::new());
+        state.set_packs(packs);
+        state
+    }
+
+    /// Replace the scenarios, as after a locale change
+    ///
+    /// The menu selection moves to the Settings item, where the change was
+    /// made.
+    pub fn set_packs(&mut self, packs: Vec<ScenarioPack>) {
+        self.scenarios.clear();
+        self.pack_titles.clear();
+        for pack in packs {
+            if let Some(title) = pack.title() {
+                self.pack_titles.push((self.scenarios.len(), title));
+            }
+            self.scenarios.extend(pack.scenarios);
+        }
+        if self.selected_menu_item > 0 {
+            self.selected_menu_item = self.settings_index();
         }
+        self.menu_scroll_offset = 0;
+    }
+
+    /// Title of the pack starting at the given scenario index, if any
+    pub fn pack_title_at(&self, index: usize) -> Option<&str> {
+        self.pack_titles
+            .iter()
+            .find(|(start, _)| *start == index)
+            .map(|(_, title)| title.as_str())
     }
 
     /// Get reference to the current session
@@ -229,6 +356,104 @@ impl AppState {
         self.scenarios.get(index)
     }
 
+    /// Menu index of the motion drill item, after the scenarios
+    pub fn motion_drill_index(&self) -> usize {
+        self.scenarios.len()
+    }
+
+    /// Menu index of the Settings item, after the motion drill
+    pub fn settings_index(&self) -> usize {
+        self.scenarios.len() + 1
+    }
+
+    /// Menu index of the Quit item, the last one
+    pub fn quit_index(&self) -> usize {
+        self.scenarios.len() + 2
+    }
+
+    /// Display value of a setting, a locale code or a translation key
+    pub fn setting_value(&self, setting: Setting) -> &str {
+        match setting {
+            Setting::Locale => &self.config.locale,
+            Setting::Keymap => self.config.keymap.label_key(),
+            Setting::Theme => self.config.theme.label_key(),
+            Setting::Hints => self.config.hints.label_key(),
+            Setting::Timer if self.config.show_timer => "settings.timer_shown",
+            Setting::Timer => "settings.timer_hidden",
+        }
+    }
+
+    /// Switch a setting to its next value
+    fn change_setting(&mut self, setting: Setting) {
+        let config = &mut self.config;
+        match setting {
+            Setting::Locale => {
+                let next = self
+                    .locales
+                    .iter()
+                    .position(|locale| *locale == config.locale)
+                    .map_or(0, |index| (index + 1) % self.locales.len());
+                if let Some(locale) = self.locales.get(next) {
+                    config.locale.clone_from(locale);
+                }
+            }
+            Setting::Keymap => config.keymap = config.keymap.next(),
+            Setting::Theme => config.theme = config.theme.next(),
+            Setting::Hints => config.hints = config.hints.next(),
+            Setting::Timer => config.show_timer = !config.show_timer,
+        }
+        self.config_changed = true;
+    }
+
+    /// Show the next hint, unless hints are off
+    fn show_next_hint(&mut self) {
+        if self.config.hints != HintMode::Off
+            && let Some(session) = &mut self.session
+            && let Some(hint) = session.get_hint()
+        {
+            self.current_hint = Some(hint);
+            self.show_hint_panel = true;
+        }
+    }
+
+    /// Show the first hint right away when hints are automatic
+    ///
+    /// The user did not ask for it, so it costs no points.
+    fn show_automatic_hint(&mut self) {
+        if self.config.hints == HintMode::Automatic
+            && let Some(session) = &mut self.session
+            && let Some(hint) = session.get_free_hint()
+        {
+            self.current_hint = Some(hint);
+            self.show_hint_panel = true;
+        }
+    }
+
+    /// Make the session current and reset the task screen for it
+    fn begin_session(&mut self, mut session: GameSession) {
+        session.set_indent_unit(&self.config.indent_unit);
+        session.set_hint_penalty(self.config.hint_penalty);
+        self.session = Some(session);
+        self.screen = Screen::Task;
+        self.show_hint_panel = false;
+        self.show_key_history = false;
+        self.current_hint = None;
+        self.last_command = None;
+        self.completion_time = None;
+        self.clear_key_history();
+        self.command_buffer.clear();
+        self.show_automatic_hint();
+    }
+
+    /// Generate the drill for the current round and start it
+    fn start_drill(&mut self, round: usize) -> Result<(), UserError> {
+        let seed = self.drill_seed.wrapping_add(round as u64);
+        let scenario = generate_motion_drill(&DrillOptions::default(), seed)?;
+        self.drill_round = Some(round);
+        self.begin_session(GameSession::new(scenario)?);
+        Ok(())
+    }
+
     /// Add a key to the history (keeps last 5)
     pub fn add_key_to_history(&mut self, key: String) {
         // Insert at the beginning (most recent first)
@@ -301,6 +526,18 @@ pub fn update(state: &mut AppState, msg: Message) -> Result<(), UserError> {
             Ok(())
         }
 
+        Message::MenuUp if state.screen == Screen::Settings => {
+            state.selected_setting = state.selected_setting.saturating_sub(1);
+            Ok(())
+        }
+
+        Message::MenuDown if state.screen == Screen::Settings => {
+            if state.selected_setting + 1 < Setting::ALL.len() {
+                state.selected_setting += 1;
+            }
+            Ok(())
+        }
+
         Message::MenuUp => {
             if state.selected_menu_item > 0 {
                 state.selected_menu_item -= 1;
@@ -309,9 +546,8 @@ pub fn update(state: &mut AppState, msg: Message) -> Result<(), UserError> {
         }
 
         Message::MenuDown => {
-            // Total menu items = scenarios + Quit option
-            let max_items = state.scenarios.len() + 1;
-            if state.selected_menu_item < max_items - 1 {
+            // Quit is the last menu item
+            if state.selected_menu_item < state.quit_index() {
                 state.selected_menu_item += 1;
             }
             Ok(())
@@ -324,7 +560,12 @@ pub fn update(state: &mut AppState, msg: Message) -> Result<(), UserError> {
             if selected < scenario_count {
                 // Start selected scenario
                 update(state, Message::StartScenario(selected))?;
-            } else if selected == scenario_count {
+            } else if selected == state.motion_drill_index() {
+                update(state, Message::StartMotionDrill)?;
+            } else if selected == state.settings_index() {
+                state.selected_setting = 0;
+                state.screen = Screen::Settings;
+            } else if selected == state.quit_index() {
                 // Quit option (last item)
                 update(state, Message::QuitApp)?;
             }
@@ -334,19 +575,14 @@ pub fn update(state: &mut AppState, msg: Message) -> Result<(), UserError> {
         Message::StartScenario(index) => {
             if let Some(scenario) = state.scenarios.get(index).cloned() {
                 let session = GameSession::new(scenario)?;
-                state.session = Some(session);
-                state.screen = Screen::Task;
-                state.show_hint_panel = false;
-                state.show_key_history = false;
-                state.current_hint = None;
-                state.last_command = None;
-                state.completion_time = None;
-                state.clear_key_history();
-                state.command_buffer.clear();
+                state.drill_round = None;
+                state.begin_session(session);
             }
             Ok(())
         }
 
+        Message::StartMotionDrill => state.start_drill(0),
+
         Message::CompleteScenario => {
             state.screen = Screen::Results;
             Ok(())
@@ -361,12 +597,7 @@ pub fn update(state: &mut AppState, msg: Message) -> Result<(), UserError> {
         }
 
         Message::ShowHint => {
-            if let Some(session) = &mut state.session
-                && let Some(hint) = session.get_hint()
-            {
-                state.current_hint = Some(hint);
-                state.show_hint_panel = true;
-            }
+            state.show_next_hint();
             Ok(())
         }
 
@@ -378,7 +609,10 @@ pub fn update(state: &mut AppState, msg: Message) -> Result<(), UserError> {
             // Show key history popup after first keypress
             state.show_key_history = true;
 
+            let mut step_changed = false;
             if let Some(session) = &mut state.session {
+                let step = session.current_step();
+
                 // In Insert mode, execute commands directly
                 if session.is_insert_mode() {
                     // Store last command for display (skip special commands and single chars)
@@ -388,46 +622,17 @@ pub fn update(state: &mut AppState, msg: Message) -> Result<(), UserError> {
 
                     // Execute command through session
                     session.record_action(command.to_string())?;
-                } else {
-                    // Normal mode: handle command buffer for multi-key commands
-                    state.command_buffer.push_str(&command);
-
-                    // Try to match a complete command
-                    let final_command = match state.command_buffer.as_str() {
-                        // Multi-key commands
-                        "dd" => Some("dd"),
-                        "gg" => Some("gg"),
-
-                        // Replace character command: r + any char
-                        cmd if cmd.starts_with('r') && cmd.len() == 2 => {
-                            Some(state.command_buffer.as_str())
-                        }
-
-                        // Partial commands - wait for more input
-                        "d" | "g" | "r" => None,
-
-                        // Single-key commands (clear buffer and execute)
-                        _ if state.command_buffer.len() == 1 => Some(state.command_buffer.as_str()),
-
-                        // Invalid sequence - clear buffer
-                        _ => {
-                            state.command_buffer.clear();
-                            return Ok(());
-                        }
-                    };
-
-                    if let Some(cmd) = final_command {
-                        // We have a complete command
-                        let cmd_string = cmd.to_string();
-                        state.command_buffer.clear();
-
-                        // Store for display
-                        state.last_command = Some(cmd_string.clone());
-
-                        // Execute command through session
-                        session.record_action(cmd_string)?;
-                    }
-                    // If None, we're waiting for more keys (buffer not cleared)
+                } else if let Some(cmd) = push_key(&mut state.command_buffer, &command) {
+                    // Normal mode: multi-key commands are buffered until complete
+                    state.last_command = Some(cmd.clone());
+                    session.record_action(cmd)?;
+                }
+
+                // A new lesson step brings its own hints
+                step_changed = session.current_step() != step;
+                if step_changed {
+                    state.show_hint_panel = false;
+                    state.current_hint = None;
                 }
 
                 // Check if scenario is complete
@@ -437,6 +642,9 @@ pub fn update(state: &mut AppState, msg: Message) -> Result<(), UserError> {
                     state.completion_time = Some(std::time::Instant::now());
                 }
             }
+            if step_changed {
+                state.show_automatic_hint();
+            }
             Ok(())
         }
 
@@ -451,11 +659,15 @@ pub fn update(state: &mut AppState, msg: Message) -> Result<(), UserError> {
                 state.completion_time = None;
                 state.clear_key_history();
                 state.command_buffer.clear();
+                state.show_automatic_hint();
             }
             Ok(())
         }
 
         Message::NextScenario => {
+            if let Some(round) = state.drill_round {
+                return state.start_drill(round + 1);
+            }
             state.screen = Screen::MainMenu;
             state.session = None;
             state.show_hint_panel = false;
@@ -466,32 +678,61 @@ pub fn update(state: &mut AppState, msg: Message) -> Result<(), UserError> {
         Message::BackToMenu => {
             state.screen = Screen::MainMenu;
             state.session = None;
+            state.drill_round = None;
             state.show_hint_panel = false;
             state.current_hint = None;
             Ok(())
         }
+
+        Message::ChangeSetting => {
+            if let Some(&setting) = Setting::ALL.get(state.selected_setting) {
+                state.change_setting(setting);
+            }
+            Ok(())
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::config::{ScoringConfig, Setup, Solution, TargetState};
+    use crate::config::{
+        Completion, PackManifest, ScoringConfig, Setup, SetupMode, Solution, TargetState,
+    };
+    use std::collections::BTreeMap;
 
     fn create_test_scenario() -> Scenario {
         Scenario {
             id: "test_001".to_string(),
             name: "Test Scenario".to_string(),
             description: "A test scenario for UI testing".to_string(),
+            category: None,
+            difficulty: None,
+            tags: Vec::new(),
+            requires: Vec::new(),
+            commands_taught: Vec::new(),
+            forbidden_commands: Vec::new(),
+            required_commands: Vec::new(),
+            estimated_seconds: None,
             setup: Setup {
                 file_content: "line 1\n".to_string(),
-                cursor_position: (0, 0),
+                cursor_position: Some((0, 0)),
+                selections: Vec::new(),
+                primary_selection: 0,
+                mode: SetupMode::Normal,
+                registers: BTreeMap::new(),
+                last_action: Vec::new(),
+                indent_unit: None,
             },
-            target: TargetState {
+            target: Some(TargetState {
                 file_content: "line 2\n".to_string(),
-                cursor_position: (0, 0),
+                cursor_position: Some((0, 0)),
                 selection: None,
-            },
+                selections: Vec::new(),
+                primary_selection: 0,
+                match_selection: false,
+                completion: Completion::Exact,
+            }),
             solution: Solution {
                 commands: vec!["dd".to_string()],
                 description: "Delete line".to_string(),
@@ -503,6 +744,9 @@ mod tests {
                 max_points: 100,
                 tolerance: 0,
             },
+            markers: false,
+            template: None,
+            steps: Vec::new(),
         }
     }
 
@@ -563,11 +807,17 @@ mod tests {
 
         // Move down again
         update(&mut state, Message::MenuDown).unwrap();
-        assert_eq!(state.selected_menu_item, 2); // Now on Quit
+        assert_eq!(state.selected_menu_item, 2); // Now on Motion Drill
+
+        update(&mut state, Message::MenuDown).unwrap();
+        assert_eq!(state.selected_menu_item, 3); // Now on Settings
+
+        update(&mut state, Message::MenuDown).unwrap();
+        assert_eq!(state.selected_menu_item, 4); // Now on Quit
 
         // Can't go past max items
         update(&mut state, Message::MenuDown).unwrap();
-        assert_eq!(state.selected_menu_item, 2);
+        assert_eq!(state.selected_menu_item, 4);
     }
 
     #[test]
@@ -587,8 +837,8 @@ mod tests {
         let scenario1 = create_test_scenario();
         let scenario2 = create_test_scenario();
         let mut state = AppState::new(vec![scenario1, scenario2]);
-        // Select Quit option (index = scenario count)
-        state.selected_menu_item = 2;
+        // Select Quit option (after the scenarios, Motion Drill and Settings)
+        state.selected_menu_item = 4;
 
         update(&mut state, Message::MenuSelect).unwrap();
 
@@ -657,6 +907,80 @@ mod tests {
         assert!(state.current_hint.is_some());
     }
 
+    #[test]
+    fn test_hint_modes() {
+        let mut state = AppState::new(vec![create_test_scenario()]);
+        state.config.hints = HintMode::Off;
+        update(&mut state, Message::StartScenario(0)).unwrap();
+        update(&mut state, Message::ShowHint).unwrap();
+        assert!(!state.show_hint_panel);
+
+        state.config.hints = HintMode::Automatic;
+        update(&mut state, Message::StartScenario(0)).unwrap();
+        assert!(state.show_hint_panel);
+        assert_eq!(
+            state.current_hint.as_deref(),
+            Some("Use dd to delete a line")
+        );
+    }
+
+    #[test]
+    fn test_sessions_use_configured_indent_unit() {
+        let scenario = Scenario {
+            setup: Setup {
+                file_content: "code\n".to_string(),
+                ..create_test_scenario().setup
+            },
+            ..create_test_scenario()
+        };
+        let mut state = AppState::new(vec![scenario]);
+        state.config.indent_unit = "\t".to_string();
+        update(&mut state, Message::StartScenario(0)).unwrap();
+        update(&mut state, Message::ExecuteCommand(">".into())).unwrap();
+
+        let session = state.session.as_ref().unwrap();
+        assert_eq!(session.current_state().content(), "\tcode\n");
+
+        // A unit pinned by the scenario wins over the configured one
+        let mut scenario = state.scenarios[0].clone();
+        scenario.setup.indent_unit = Some("    ".to_string());
+        let mut state = AppState::new(vec![scenario]);
+        state.config.indent_unit = "\t".to_string();
+        update(&mut state, Message::StartScenario(0)).unwrap();
+        update(&mut state, Message::ExecuteCommand(">".into())).unwrap();
+
+        let session = state.session.as_ref().unwrap();
+        assert_eq!(session.current_state().content(), "    code\n");
+    }
+
+    #[test]
+    fn test_settings_screen_changes_selected_setting() {
+        let mut state = AppState::new(vec![create_test_scenario()]);
+        state.locales = vec!["en".to_string(), "ru".to_string()];
+        state.selected_menu_item = state.settings_index();
+        update(&mut state, Message::MenuSelect).unwrap();
+        assert_eq!(state.screen, Screen::Settings);
+
+        update(&mut state, Message::ChangeSetting).unwrap();
+        assert_eq!(state.config.locale, "ru");
+        assert!(state.config_changed);
+        update(&mut state, Message::ChangeSetting).unwrap();
+        assert_eq!(state.config.locale, "en");
+
+        // Up and Down move between settings, not menu items
+        update(&mut state, Message::MenuUp).unwrap();
+        assert_eq!(state.selected_setting, 0);
+        for _ in 0..Setting::ALL.len() {
+            update(&mut state, Message::MenuDown).unwrap();
+        }
+        assert_eq!(state.selected_setting, Setting::ALL.len() - 1);
+        assert_eq!(state.selected_menu_item, state.settings_index());
+
+        update(&mut state, Message::ChangeSetting).unwrap();
+        assert!(!state.config.show_timer);
+        assert_eq!(state.setting_value(Setting::Timer), "settings.timer_hidden");
+    }
+
     #[test]
     fn test_retry_scenario_resets_state() {
         let scenario = create_test_scenario();
@@ -686,6 +1010,34 @@ mod tests {
         assert!(state.session.is_none());
     }
 
+    #[test]
+    fn test_motion_drill_is_endless() {
+        let mut state = AppState::new(vec![create_test_scenario()]);
+        state.drill_seed = 42;
+        state.selected_menu_item = state.motion_drill_index();
+
+        update(&mut state, Message::MenuSelect).unwrap();
+        assert_eq!(state.screen, Screen::Task);
+        assert_eq!(state.drill_round, Some(0));
+        let first = state.session().unwrap().scenario().clone();
+        assert_eq!(
+            first.setup.file_content,
+            first.target.as_ref().unwrap().file_content
+        );
+
+        // The next drill follows instead of the menu
+        update(&mut state, Message::CompleteScenario).unwrap();
+        update(&mut state, Message::NextScenario).unwrap();
+        assert_eq!(state.screen, Screen::Task);
+        assert_eq!(state.drill_round, Some(1));
+
+        update(&mut state, Message::BackToMenu).unwrap();
+        assert_eq!(state.drill_round, None);
+        update(&mut state, Message::StartScenario(0)).unwrap();
+        update(&mut state, Message::NextScenario).unwrap();
+        assert_eq!(state.screen, Screen::MainMenu);
+    }
+
     #[test]
     fn test_back_to_menu_clears_session() {
         let scenario = create_test_scenario();
@@ -725,4 +1077,45 @@ mod tests {
         assert!(state.get_scenario(1).is_some());
         assert!(state.get_scenario(999).is_none());
     }
+
+    #[test]
+    fn test_with_packs_records_pack_titles() {
+        let manifest = PackManifest {
+            schema_version: 1,
+            name: "Basics".to_string(),
+            version: "1.0.0".to_string(),
+            author: None,
+            description: None,
+            order: Vec::new(),
+            min_trainer_version: None,
+            locale: None,
+        };
+        let state = AppState::with_packs(vec![
+            ScenarioPack {
+                manifest: None,
+                scenarios: vec![create_test_scenario()],
+            },
+            ScenarioPack {
+                manifest: Some(manifest),
+                scenarios: vec![create_test_scenario(), create_test_scenario()],
+            },
+        ]);
+
+        assert_eq!(state.scenario_count(), 3);
+        assert_eq!(state.pack_title_at(0), None);
+        assert_eq!(state.pack_title_at(1), Some("Basics 1.0.0"));
+    }
+
+    #[test]
+    fn test_set_packs_keeps_settings_selected() {
+        let mut state = AppState::new(vec![create_test_scenario(), create_test_scenario()]);
+        state.selected_menu_item = state.settings_index();
+
+        state.set_packs(vec![ScenarioPack {
+            manifest: None,
+            scenarios: vec![create_test_scenario()],
+        }]);
+        assert_eq!(state.scenario_count(), 1);
+        assert_eq!(state.selected_menu_item, state.settings_index());
+    }
 }
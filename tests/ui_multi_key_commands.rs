@@ -3,9 +3,12 @@
 //! These tests verify that the UI layer correctly handles multi-key commands
 //! like 'dd', 'gg', 'r<char>' through the command buffer mechanism.
 
-use helix_trainer::config::{Scenario, ScoringConfig, Setup, Solution, TargetState};
+use helix_trainer::config::{
+    Completion, Scenario, ScoringConfig, Setup, SetupMode, Solution, TargetState,
+};
 use helix_trainer::ui::{AppState, Message, update};
 use std::borrow::Cow;
+use std::collections::BTreeMap;
 
 /// Helper to create a simple test scenario
 fn create_test_scenario(
@@ -19,15 +22,33 @@ fn create_test_scenario(
         id: id.to_string(),
         name: "Test Scenario".to_string(),
         description: "Test scenario for integration testing".to_string(),
+        category: None,
+        difficulty: None,
+        tags: Vec::new(),
+        requires: Vec::new(),
+        commands_taught: Vec::new(),
+        forbidden_commands: Vec::new(),
+        required_commands: Vec::new(),
+        estimated_seconds: None,
         setup: Setup {
             file_content: setup_content.to_string(),
-            cursor_position: setup_cursor,
+            cursor_position: Some(setup_cursor),
+            selections: Vec::new(),
+            primary_selection: 0,
+            mode: SetupMode::Normal,
+            registers: BTreeMap::new(),
+            last_action: Vec::new(),
+            indent_unit: None,
         },
-        target: TargetState {
+        target: Some(TargetState {
             file_content: target_content.to_string(),
-            cursor_position: target_cursor,
+            cursor_position: Some(target_cursor),
             selection: None,
-        },
+            selections: Vec::new(),
+            primary_selection: 0,
+            match_selection: false,
+            completion: Completion::Exact,
+        }),
         solution: Solution {
             commands: vec!["test".to_string()],
             description: "Test solution".to_string(),
@@ -39,6 +60,9 @@ fn create_test_scenario(
             max_points: 100,
             tolerance: 0,
         },
+        markers: false,
+        template: None,
+        steps: Vec::new(),
     }
 }
 
@@ -237,3 +261,184 @@ fn test_undo_integration() {
     // Note: Redo functionality (ctrl-r, U) is not yet implemented in HelixSimulator
     // The redo() method is currently a placeholder
 }
+
+#[test]
+fn test_bracket_paragraph_multi_key() {
+    let scenario = create_test_scenario(
+        "test_bracket_paragraph",
+        "one\ntwo\n\nthree",
+        (0, 0),
+        "one\ntwo\n\nthree",
+        (3, 0),
+    );
+
+    let mut state = AppState::new(vec![scenario.clone()]);
+    update(&mut state, Message::StartScenario(0)).unwrap();
+
+    // ']' waits for the second key
+    update(&mut state, Message::ExecuteCommand(Cow::Borrowed("]"))).unwrap();
+    assert_eq!(state.command_buffer, "]");
+
+    update(&mut state, Message::ExecuteCommand(Cow::Borrowed("p"))).unwrap();
+    assert_eq!(state.command_buffer, "");
+
+    if let Some(session) = &state.session {
+        let cursor = session.current_state().cursor_position();
+        assert_eq!((cursor.row, cursor.col), (3, 0));
+    }
+}
+
+#[test]
+fn test_bracket_space_multi_key() {
+    let scenario = create_test_scenario("test_bracket_space", "a\nb", (0, 0), "a\n\nb", (0, 0));
+
+    let mut state = AppState::new(vec![scenario.clone()]);
+    update(&mut state, Message::StartScenario(0)).unwrap();
+
+    update(&mut state, Message::ExecuteCommand(Cow::Borrowed("]"))).unwrap();
+    update(&mut state, Message::ExecuteCommand(Cow::Borrowed(" "))).unwrap();
+    assert_eq!(state.command_buffer, "");
+
+    if let Some(session) = &state.session {
+        assert_eq!(session.current_state().content(), "a\n\nb");
+    }
+
+    // A bare space is ignored rather than treated as an unknown command
+    update(&mut state, Message::ExecuteCommand(Cow::Borrowed(" "))).unwrap();
+    assert_eq!(state.command_buffer, "");
+}
+
+/// Helper to build `n` numbered lines
+fn numbered_lines(n: usize) -> String {
+    (1..=n)
+        .map(|i| format!("line {}", i))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[test]
+fn test_view_mode_multi_key() {
+    let content = numbered_lines(100);
+    let scenario = create_test_scenario("test_view", &content, (50, 0), &content, (0, 0));
+
+    let mut state = AppState::new(vec![scenario.clone()]);
+    update(&mut state, Message::StartScenario(0)).unwrap();
+
+    update(&mut state, Message::ExecuteCommand(Cow::Borrowed("z"))).unwrap();
+    assert_eq!(state.command_buffer, "z");
+
+    update(&mut state, Message::ExecuteCommand(Cow::Borrowed("t"))).unwrap();
+    assert_eq!(state.command_buffer, "");
+
+    if let Some(session) = &state.session {
+        assert_eq!(session.viewport().top_line(), 45);
+        assert_eq!(session.current_state().cursor_position().row, 50);
+    }
+}
+
+#[test]
+fn test_sticky_view_mode() {
+    let content = numbered_lines(100);
+    let scenario = create_test_scenario("test_sticky_view", &content, (10, 0), &content, (0, 0));
+
+    let mut state = AppState::new(vec![scenario.clone()]);
+    update(&mut state, Message::StartScenario(0)).unwrap();
+
+    // 'Z' stays active across view commands
+    update(&mut state, Message::ExecuteCommand(Cow::Borrowed("Z"))).unwrap();
+    update(&mut state, Message::ExecuteCommand(Cow::Borrowed("j"))).unwrap();
+    update(&mut state, Message::ExecuteCommand(Cow::Borrowed("j"))).unwrap();
+    assert_eq!(state.command_buffer, "Z");
+
+    if let Some(session) = &state.session {
+        assert_eq!(session.viewport().top_line(), 2);
+        assert_eq!(session.action_count(), 2);
+    }
+
+    // Keys without a view meaning are ignored
+    update(&mut state, Message::ExecuteCommand(Cow::Borrowed("x"))).unwrap();
+    if let Some(session) = &state.session {
+        assert_eq!(session.current_state().content(), content);
+    }
+
+    // Escape leaves sticky view mode
+    update(&mut state, Message::ExecuteCommand(Cow::Borrowed("Escape"))).unwrap();
+    assert_eq!(state.command_buffer, "");
+
+    // Now 'j' moves the cursor again
+    update(&mut state, Message::ExecuteCommand(Cow::Borrowed("j"))).unwrap();
+    if let Some(session) = &state.session {
+        assert_eq!(session.current_state().cursor_position().row, 11);
+    }
+}
+
+#[test]
+fn test_escape_cancels_pending_command() {
+    let scenario = create_test_scenario("test_escape", "line1\nline2", (0, 0), "line2", (0, 0));
+
+    let mut state = AppState::new(vec![scenario.clone()]);
+    update(&mut state, Message::StartScenario(0)).unwrap();
+
+    update(&mut state, Message::ExecuteCommand(Cow::Borrowed("d"))).unwrap();
+    update(&mut state, Message::ExecuteCommand(Cow::Borrowed("Escape"))).unwrap();
+    assert_eq!(state.command_buffer, "");
+
+    // A following 'd' starts a new sequence instead of completing 'dd'
+    update(&mut state, Message::ExecuteCommand(Cow::Borrowed("d"))).unwrap();
+    assert_eq!(state.command_buffer, "d");
+    if let Some(session) = &state.session {
+        assert_eq!(session.current_state().content(), "line1\nline2");
+    }
+}
+
+#[test]
+fn test_ctrl_chord_executes_immediately() {
+    let content = numbered_lines(100);
+    let scenario = create_test_scenario("test_ctrl", &content, (0, 0), &content, (0, 0));
+
+    let mut state = AppState::new(vec![scenario.clone()]);
+    update(&mut state, Message::StartScenario(0)).unwrap();
+
+    update(&mut state, Message::ExecuteCommand(Cow::Borrowed("ctrl-d"))).unwrap();
+    assert_eq!(state.command_buffer, "");
+
+    if let Some(session) = &state.session {
+        assert_eq!(session.current_state().cursor_position().row, 10);
+    }
+}
+
+#[test]
+fn test_select_mode_and_long_word_keys() {
+    let content = "call foo.bar(baz) done";
+    let scenario = create_test_scenario("test_select_long_word", content, (0, 0), content, (0, 18));
+
+    let mut state = AppState::new(vec![scenario.clone()]);
+    update(&mut state, Message::StartScenario(0)).unwrap();
+
+    update(&mut state, Message::ExecuteCommand(Cow::Borrowed("v"))).unwrap();
+    assert_eq!(state.command_buffer, "");
+    if let Some(session) = &state.session {
+        assert_eq!(session.mode_name(), "SELECT");
+    }
+
+    update(&mut state, Message::ExecuteCommand(Cow::Borrowed("W"))).unwrap();
+    update(&mut state, Message::ExecuteCommand(Cow::Borrowed("Escape"))).unwrap();
+    if let Some(session) = &state.session {
+        assert_eq!(session.mode_name(), "NORMAL");
+        assert_eq!(session.current_state().cursor_position().col, 5);
+    }
+}
+
+#[test]
+fn test_alt_chord_executes_immediately() {
+    let scenario = create_test_scenario("test_alt_chord", "a_bc", (0, 1), "abc", (0, 1));
+
+    let mut state = AppState::new(vec![scenario.clone()]);
+    update(&mut state, Message::StartScenario(0)).unwrap();
+
+    update(&mut state, Message::ExecuteCommand(Cow::Borrowed("alt-d"))).unwrap();
+    assert_eq!(state.command_buffer, "");
+    if let Some(session) = &state.session {
+        assert_eq!(session.current_state().content(), "abc");
+    }
+}